@@ -0,0 +1,41 @@
+use std::{
+    collections::BTreeSet,
+    env,
+    fs,
+    path::Path,
+};
+
+//**************************************************************************************************
+//  main, lists the builtin ProgramErrors named in the program source for the interface description,
+//  errors carrying a value are left out as the program returns none
+//--------------------------------------------------------------------------------------------------
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    let mut errors = BTreeSet::new();
+
+    for entry in fs::read_dir("src").unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.extension().map_or(false, |extension| extension == "rs") {
+            let source = fs::read_to_string(&path).unwrap();
+
+            for (index, prefix) in source.match_indices("ProgramError::") {
+                let rest = &source[index + prefix.len()..];
+                let name: String = rest.chars().take_while(|c| c.is_ascii_alphanumeric()).collect();
+
+                if name.starts_with(|c: char| c.is_ascii_uppercase()) && !rest[name.len()..].starts_with('(') {
+                    errors.insert(name);
+                }
+            }
+        }
+    }
+
+    let entries: String = errors.iter().map(|name| format!("    (\"{0}\", ProgramError::{0}),\n", name)).collect();
+
+    fs::write(
+        Path::new(&env::var("OUT_DIR").unwrap()).join("errors.rs"),
+        format!("const PROGRAM_ERRORS: &[(&str, ProgramError)] = &[\n{}];\n", entries),
+    )
+    .unwrap();
+}
@@ -0,0 +1,231 @@
+use serde::Deserialize;
+use solana_program::pubkey::Pubkey;
+use kaizen::state::{
+    Settings,
+    Tier,
+    Token,
+};
+use std::{
+    error::Error,
+    fs,
+    path::Path,
+    str::FromStr,
+};
+
+//**************************************************************************************************
+//  PoolConfig, pool parameters as read from a TOML or JSON file
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Deserialize)]
+pub struct PoolConfig {
+    revenue_owner:          String,
+    interest_basis_points:  u32,
+    locked_token:           String,
+    locked_token_owner:     String,
+    unlock_timestamp:       u64,
+    supply_total:           u64,
+    token0:                 String,
+    price:                  u64,
+    #[serde(default)]
+    soft_cap:               u64,
+    #[serde(default)]
+    sale_end:               u64,
+    #[serde(default)]
+    penalty_basis_points:   u32,
+    #[serde(default)]
+    penalty_to_holders:     bool,
+    #[serde(default)]
+    interest_apr:           bool,
+    #[serde(default)]
+    tiers:                  Vec<TierConfig>,
+    #[serde(default)]
+    auction_start_price:    u64,
+    #[serde(default)]
+    auction_floor_price:    u64,
+    #[serde(default)]
+    auction_start:          u64,
+    #[serde(default)]
+    auction_end:            u64,
+    #[serde(default)]
+    batch_end:              u64,
+    #[serde(default)]
+    referral_basis_points:  u32,
+    #[serde(default)]
+    referral_bonus:         bool,
+    #[serde(default)]
+    paused:                 bool,
+}
+
+//**************************************************************************************************
+//  TierConfig
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Deserialize)]
+struct TierConfig {
+    supply_end: u64,
+    price:      u64,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl PoolConfig {
+
+    //==================================================================================================
+    //  read, JSON for `.json` files and TOML for anything else
+    //--------------------------------------------------------------------------------------------------
+    pub fn read(_path: &str) -> Result<Self, Box<dyn Error>> {
+        let json = Path::new(_path).extension().map_or(false, |extension| extension == "json");
+        Self::parse(&fs::read_to_string(_path)?, json)
+    }
+    //==================================================================================================
+    //  parse
+    //--------------------------------------------------------------------------------------------------
+    pub fn parse(_content: &str, _json: bool) -> Result<Self, Box<dyn Error>> {
+        if _json {
+            Ok(serde_json::from_str(_content)?)
+        } else {
+            Ok(toml::from_str(_content)?)
+        }
+    }
+    //==================================================================================================
+    //  settings
+    //--------------------------------------------------------------------------------------------------
+    pub fn settings(&self) -> Result<Settings, Box<dyn Error>> {
+        if Settings::default().tiers.len() < self.tiers.len() {
+            return Err(format!("at most {} tiers are supported", Settings::default().tiers.len()).into());
+        }
+
+        let mut tiers = [Tier::default(); 4];
+        for (tier, tier_config) in tiers.iter_mut().zip(self.tiers.iter()) {
+            *tier = Tier { supply_end: tier_config.supply_end, price: tier_config.price };
+        }
+
+        Ok(Settings {
+            revenue_owner:          Pubkey::from_str(&self.revenue_owner)?,
+            interest_basis_points:  self.interest_basis_points,
+            locked_token:           Pubkey::from_str(&self.locked_token)?,
+            locked_token_owner:     Pubkey::from_str(&self.locked_token_owner)?,
+            unlock_timestamp:       self.unlock_timestamp,
+            supply_total:           self.supply_total,
+            token0:                 Token { address: Pubkey::from_str(&self.token0)?, price: self.price },
+            soft_cap:               self.soft_cap,
+            sale_end:               self.sale_end,
+            penalty_basis_points:   self.penalty_basis_points,
+            penalty_to_holders:     self.penalty_to_holders,
+            interest_apr:           self.interest_apr,
+            tiers,
+            auction_start_price:    self.auction_start_price,
+            auction_floor_price:    self.auction_floor_price,
+            auction_start:          self.auction_start,
+            auction_end:            self.auction_end,
+            batch_end:              self.batch_end,
+            referral_basis_points:  self.referral_basis_points,
+            referral_bonus:         self.referral_bonus,
+            paused:                 self.paused,
+            ..Settings::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{
+        SystemTime,
+        UNIX_EPOCH,
+    };
+
+    const KEY: &str = "11111111111111111111111111111111";
+
+    //==============================================================================================
+    //  toml, the required fields followed by `_extra`
+    //----------------------------------------------------------------------------------------------
+    fn toml(_extra: &str) -> String {
+        format!(r#"
+            revenue_owner           = "{key}"
+            interest_basis_points   = 10
+            locked_token            = "{key}"
+            locked_token_owner      = "{key}"
+            unlock_timestamp        = 1700000000
+            supply_total            = 1000000
+            token0                  = "{key}"
+            price                   = 2
+            {extra}
+        "#, key = KEY, extra = _extra)
+    }
+
+    //**********************************************************************************************
+    //  test_required_fields, the rest of the pool defaults to off
+    //----------------------------------------------------------------------------------------------
+    #[test]
+    fn test_required_fields() {
+        let settings = PoolConfig::parse(&toml(""), false).unwrap().settings().unwrap();
+
+        assert_eq!(settings, Settings {
+            interest_basis_points:  10,
+            unlock_timestamp:       1_700_000_000,
+            supply_total:           1_000_000,
+            token0:                 Token { address: Pubkey::default(), price: 2 },
+            ..Settings::default()
+        });
+
+        // every field without a default is required
+        assert!(PoolConfig::parse(&toml("").replace("price", "# price"), false).is_err());
+    }
+
+    //**********************************************************************************************
+    //  test_optional_fields
+    //----------------------------------------------------------------------------------------------
+    #[test]
+    fn test_optional_fields() {
+        let config = toml(r#"
+            soft_cap                = 500
+            sale_end                = 1690000000
+            penalty_basis_points    = 1500
+            referral_bonus          = true
+            tiers                   = [{ supply_end = 200000, price = 1 }, { supply_end = 500000, price = 2 }]
+        "#);
+        let settings = PoolConfig::parse(&config, false).unwrap().settings().unwrap();
+
+        assert_eq!((settings.soft_cap, settings.sale_end, settings.penalty_basis_points, settings.referral_bonus), (500, 1_690_000_000, 1_500, true));
+        assert_eq!(settings.tiers, [Tier { supply_end: 200_000, price: 1 }, Tier { supply_end: 500_000, price: 2 }, Tier::default(), Tier::default()]);
+    }
+
+    //**********************************************************************************************
+    //  test_json, the same pool as TOML
+    //----------------------------------------------------------------------------------------------
+    #[test]
+    fn test_json() {
+        let config = format!(r#"{{
+            "revenue_owner": "{key}", "interest_basis_points": 10, "locked_token": "{key}", "locked_token_owner": "{key}",
+            "unlock_timestamp": 1700000000, "supply_total": 1000000, "token0": "{key}", "price": 2, "batch_end": 1690000000
+        }}"#, key = KEY);
+
+        let json = PoolConfig::parse(&config, true).unwrap().settings().unwrap();
+        let toml = PoolConfig::parse(&toml("batch_end = 1690000000"), false).unwrap().settings().unwrap();
+
+        assert_eq!(json, toml);
+    }
+
+    //**********************************************************************************************
+    //  test_example, the shipped example is a pool `create-pool` accepts today
+    //----------------------------------------------------------------------------------------------
+    #[test]
+    fn test_example() {
+        let now         = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let settings    = PoolConfig::parse(include_str!("../pool.example.toml"), false).unwrap().settings().unwrap();
+
+        assert!(settings.initialize(now).is_ok());
+        assert_eq!(settings.tiers[0], Tier { supply_end: 200_000, price: 700 });
+    }
+
+    //**********************************************************************************************
+    //  test_rejects_bad_config, too many tiers, a bad pubkey or a field given twice
+    //----------------------------------------------------------------------------------------------
+    #[test]
+    fn test_rejects_bad_config() {
+        let tiers = "tiers = [{ supply_end = 1, price = 1 }, { supply_end = 2, price = 1 }, { supply_end = 3, price = 1 }, { supply_end = 4, price = 1 }, { supply_end = 5, price = 1 }]";
+
+        assert!(PoolConfig::parse(&toml(tiers), false).unwrap().settings().is_err());
+        assert!(PoolConfig::parse(&toml("").replacen(KEY, "not a pubkey", 1), false).unwrap().settings().is_err());
+        assert!(PoolConfig::parse(&toml("price = 3"), false).is_err());
+    }
+}
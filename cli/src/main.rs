@@ -0,0 +1,254 @@
+use clap::{
+    crate_description,
+    crate_name,
+    crate_version,
+    App,
+    AppSettings,
+    Arg,
+    ArgMatches,
+    SubCommand,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+use kaizen::{
+    client,
+    interface::interface,
+    state::{
+        decode_account,
+        KaizenAccount,
+        Settings,
+        UpdateSettingsArgs,
+    },
+};
+use std::{
+    error::Error,
+    str::FromStr,
+};
+use config::PoolConfig;
+
+mod config;
+
+type CliResult = Result<(), Box<dyn Error>>;
+
+//==================================================================================================
+//  send, signs with the payer and `_signers` and waits for confirmation
+//--------------------------------------------------------------------------------------------------
+fn send(_rpc_client: &RpcClient, _payer: &Keypair, _signers: &[&Keypair], _instructions: &[Instruction]) -> CliResult {
+    let (recent_blockhash, _fee_calculator) = _rpc_client.get_recent_blockhash()?;
+
+    let mut signers = vec![_payer];
+    signers.extend_from_slice(_signers);
+
+    let transaction = Transaction::new_signed_with_payer(_instructions, Some(&_payer.pubkey()), &signers, recent_blockhash);
+    let signature   = _rpc_client.send_and_confirm_transaction(&transaction)?;
+
+    println!("Signature: {}", signature);
+    Ok(())
+}
+
+//==================================================================================================
+//  create_pool
+//--------------------------------------------------------------------------------------------------
+fn create_pool(_rpc_client: &RpcClient, _program_id: &Pubkey, _payer: &Keypair, _config_path: &str) -> CliResult {
+    let settings            = PoolConfig::read(_config_path)?.settings()?;
+    let account_settings    = Keypair::new();
+    let lamports            = _rpc_client.get_minimum_balance_for_rent_exemption(Settings::LEN)?;
+
+    send(_rpc_client, _payer, &[&account_settings], &[
+        system_instruction::create_account(&_payer.pubkey(), &account_settings.pubkey(), lamports, Settings::LEN as u64, _program_id),
+        client::initialize(_program_id, &account_settings.pubkey(), &settings)?,
+    ])?;
+
+    println!("Pool: {}", account_settings.pubkey());
+    Ok(())
+}
+
+//==================================================================================================
+//  migrate_pool, moves a legacy unversioned Settings account to a new account in the latest layout,
+//  the pool is at the new address from then on
+//--------------------------------------------------------------------------------------------------
+fn migrate_pool(_rpc_client: &RpcClient, _program_id: &Pubkey, _payer: &Keypair, _legacy_settings: &Pubkey) -> CliResult {
+    let account_settings    = Keypair::new();
+    let lamports            = _rpc_client.get_minimum_balance_for_rent_exemption(Settings::LEN)?;
+
+    send(_rpc_client, _payer, &[&account_settings], &[
+        system_instruction::create_account(&_payer.pubkey(), &account_settings.pubkey(), lamports, Settings::LEN as u64, _program_id),
+        client::migrate_settings(_program_id, _legacy_settings, &account_settings.pubkey(), &_payer.pubkey())?,
+    ])?;
+
+    println!("Pool: {}", account_settings.pubkey());
+    println!("The pool moved from {} to {}, which clients have to use from now on", _legacy_settings, account_settings.pubkey());
+    Ok(())
+}
+
+//==================================================================================================
+//  update_settings, what is not given is left as it is
+//--------------------------------------------------------------------------------------------------
+fn update_settings(_rpc_client: &RpcClient, _program_id: &Pubkey, _payer: &Keypair, _settings: &Pubkey, _matches: &ArgMatches) -> CliResult {
+    let settings = match decode_account(&_rpc_client.get_account_data(_settings)?) {
+        Ok(KaizenAccount::Settings(settings)) => settings,
+        _ => return Err(format!("{} is not a Kaizen pool", _settings).into()),
+    };
+
+    let args = UpdateSettingsArgs {
+        revenue_owner:      optional_pubkey_of(_matches, "revenue_owner")?.unwrap_or(settings.revenue_owner),
+        locked_token_owner: optional_pubkey_of(_matches, "locked_token_owner")?.unwrap_or(settings.locked_token_owner),
+        price:              _matches.value_of("price").map(str::parse).transpose()?.unwrap_or(settings.token0.price),
+    };
+
+    send(_rpc_client, _payer, &[], &[client::update_settings(_program_id, _settings, &_payer.pubkey(), args)?])
+}
+
+//==================================================================================================
+//  show
+//--------------------------------------------------------------------------------------------------
+fn show(_rpc_client: &RpcClient, _account: &Pubkey, _json: bool) -> CliResult {
+    let account = decode_account(&_rpc_client.get_account_data(_account)?)
+        .map_err(|_| format!("{} is not a Kaizen account", _account))?;
+
+    if _json {
+        println!("{}", serde_json::to_string_pretty(&account)?);
+    } else {
+        println!("{:#?}", account);
+    }
+
+    Ok(())
+}
+
+//==================================================================================================
+//  holders
+//--------------------------------------------------------------------------------------------------
+fn holders(_rpc_client: &RpcClient, _program_id: &Pubkey, _settings: &Pubkey) -> CliResult {
+    for (pubkey, account) in _rpc_client.get_program_accounts(_program_id)? {
+        if let Ok(KaizenAccount::Savings(savings)) = decode_account(&account.data) {
+            if savings.is_initialized && savings.settings == *_settings {
+                println!("{} owner {} technical {} original {}", pubkey, savings.owner, savings.total_technical, savings.total_original);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+//==================================================================================================
+//  idl, the interface description other-language clients generate their bindings from, or the
+//  Anchor IDL
+//--------------------------------------------------------------------------------------------------
+fn idl(_anchor: bool) -> CliResult {
+    if _anchor {
+        println!("{}", serde_json::to_string_pretty(&kaizen::idl::idl())?);
+    } else {
+        println!("{}", serde_json::to_string_pretty(&interface())?);
+    }
+
+    Ok(())
+}
+
+//==================================================================================================
+//  pubkey_of
+//--------------------------------------------------------------------------------------------------
+fn pubkey_of(_matches: &ArgMatches, _name: &str) -> Result<Pubkey, Box<dyn Error>> {
+    Ok(Pubkey::from_str(_matches.value_of(_name).unwrap_or_default())?)
+}
+
+//==================================================================================================
+//  optional_pubkey_of
+//--------------------------------------------------------------------------------------------------
+fn optional_pubkey_of(_matches: &ArgMatches, _name: &str) -> Result<Option<Pubkey>, Box<dyn Error>> {
+    Ok(_matches.value_of(_name).map(Pubkey::from_str).transpose()?)
+}
+
+//==================================================================================================
+//  main
+//--------------------------------------------------------------------------------------------------
+fn main() -> CliResult {
+    let default_keypair = format!("{}/.config/solana/id.json", std::env::var("HOME").unwrap_or_else(|_| ".".to_string()));
+    let default_program = kaizen::id().to_string();
+
+    let matches = App::new(crate_name!())
+        .about(crate_description!())
+        .version(crate_version!())
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(Arg::with_name("url").long("url").short("u").global(true).takes_value(true)
+            .default_value("http://localhost:8899").help("RPC URL of the cluster"))
+        .arg(Arg::with_name("keypair").long("keypair").short("k").global(true).takes_value(true)
+            .default_value(&default_keypair).help("Fee payer and signer keypair file"))
+        .arg(Arg::with_name("program_id").long("program-id").global(true).takes_value(true)
+            .default_value(&default_program).help("Kaizen program id"))
+        .subcommand(SubCommand::with_name("create-pool").about("Create and initialize a pool")
+            .arg(Arg::with_name("config").required(true).help("Pool config, TOML or JSON")))
+        .subcommand(SubCommand::with_name("migrate-pool").about("Move a legacy pool to the latest layout at a new address, signed by the locked token owner")
+            .arg(Arg::with_name("settings").required(true)))
+        .subcommand(SubCommand::with_name("show").about("Decode a Kaizen account")
+            .arg(Arg::with_name("account").required(true))
+            .arg(Arg::with_name("json").long("json").help("Print as JSON")))
+        .subcommand(SubCommand::with_name("update-settings").about("Change the owners or the price of a pool, signed by the locked token owner")
+            .arg(Arg::with_name("settings").required(true))
+            .arg(Arg::with_name("revenue_owner").long("revenue-owner").takes_value(true))
+            .arg(Arg::with_name("locked_token_owner").long("locked-token-owner").takes_value(true))
+            .arg(Arg::with_name("price").long("price").takes_value(true)))
+        .subcommand(SubCommand::with_name("pause").about("Stop swaps, bids, orders, transfers and stakes, signed by the locked token owner")
+            .arg(Arg::with_name("settings").required(true)))
+        .subcommand(SubCommand::with_name("unpause").about("Resume a paused pool, signed by the locked token owner")
+            .arg(Arg::with_name("settings").required(true)))
+        .subcommand(SubCommand::with_name("collect-revenue").about("Collect the pool revenue, signed by the revenue owner")
+            .arg(Arg::with_name("settings").required(true)))
+        .subcommand(SubCommand::with_name("holders").about("List the Savings accounts of a pool")
+            .arg(Arg::with_name("settings").required(true)))
+        .subcommand(SubCommand::with_name("idl").about("Print the program interface description as JSON")
+            .arg(Arg::with_name("anchor").long("anchor").help("Print the Anchor IDL instead")))
+        .get_matches();
+
+    let rpc_client  = RpcClient::new_with_commitment(matches.value_of("url").unwrap_or_default().to_string(), CommitmentConfig::confirmed());
+    let program_id  = pubkey_of(&matches, "program_id")?;
+
+    match matches.subcommand() {
+        ("create-pool", Some(sub_matches)) => {
+            let payer = read_keypair_file(matches.value_of("keypair").unwrap_or_default())?;
+            create_pool(&rpc_client, &program_id, &payer, sub_matches.value_of("config").unwrap_or_default())
+        }
+        ("migrate-pool", Some(sub_matches)) => {
+            let payer = read_keypair_file(matches.value_of("keypair").unwrap_or_default())?;
+            migrate_pool(&rpc_client, &program_id, &payer, &pubkey_of(sub_matches, "settings")?)
+        }
+        ("show", Some(sub_matches)) => {
+            show(&rpc_client, &pubkey_of(sub_matches, "account")?, sub_matches.is_present("json"))
+        }
+        ("update-settings", Some(sub_matches)) => {
+            let payer = read_keypair_file(matches.value_of("keypair").unwrap_or_default())?;
+            update_settings(&rpc_client, &program_id, &payer, &pubkey_of(sub_matches, "settings")?, sub_matches)
+        }
+        ("pause", Some(sub_matches)) => {
+            let payer = read_keypair_file(matches.value_of("keypair").unwrap_or_default())?;
+            let ix    = client::pause(&program_id, &pubkey_of(sub_matches, "settings")?, &payer.pubkey(), true)?;
+            send(&rpc_client, &payer, &[], &[ix])
+        }
+        ("unpause", Some(sub_matches)) => {
+            let payer = read_keypair_file(matches.value_of("keypair").unwrap_or_default())?;
+            let ix    = client::pause(&program_id, &pubkey_of(sub_matches, "settings")?, &payer.pubkey(), false)?;
+            send(&rpc_client, &payer, &[], &[ix])
+        }
+        ("collect-revenue", Some(sub_matches)) => {
+            let payer = read_keypair_file(matches.value_of("keypair").unwrap_or_default())?;
+            let ix    = client::collect_revenue(&program_id, &pubkey_of(sub_matches, "settings")?, &payer.pubkey())?;
+            send(&rpc_client, &payer, &[], &[ix])
+        }
+        ("holders", Some(sub_matches)) => {
+            holders(&rpc_client, &program_id, &pubkey_of(sub_matches, "settings")?)
+        }
+        ("idl", Some(sub_matches)) => {
+            idl(sub_matches.is_present("anchor"))
+        }
+        _ => unreachable!(),
+    }
+}
\ No newline at end of file
@@ -0,0 +1,288 @@
+//**************************************************************************************************
+//  Derives of the interface description: the field lists, account lists and instruction table
+//  `kaizen::interface` lays out. They implement the traits of `crate::interface` and are only meant
+//  for the kaizen crate itself
+//--------------------------------------------------------------------------------------------------
+use proc_macro::TokenStream;
+use proc_macro2::{
+    Span,
+    TokenStream as TokenStream2,
+    TokenTree,
+};
+use quote::quote;
+use std::convert::TryFrom;
+use syn::{
+    parse_macro_input,
+    Attribute,
+    Data,
+    DeriveInput,
+    Error,
+    Expr,
+    ExprLit,
+    Fields,
+    Ident,
+    Lit,
+    Meta,
+    NestedMeta,
+    Type,
+};
+
+//==================================================================================================
+//  Layout, the fields of a struct in declaration order, which is the order Borsh and `Pack` lay
+//  them out in
+//--------------------------------------------------------------------------------------------------
+#[proc_macro_derive(Layout)]
+pub fn derive_layout(_input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(_input as DeriveInput);
+    expand(layout(&input))
+}
+
+//==================================================================================================
+//  AccountsLayout, the accounts of an Anchor `Accounts` struct, writable and signer as given by
+//  their `#[account(mut, signer)]` constraints
+//--------------------------------------------------------------------------------------------------
+#[proc_macro_derive(AccountsLayout)]
+pub fn derive_accounts_layout(_input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(_input as DeriveInput);
+    expand(accounts_layout(&input))
+}
+
+//==================================================================================================
+//  InstructionsLayout, one instruction per `#[interface(...)]` attribute of each variant, tagged by
+//  the variant index Borsh encodes:
+//      accounts = "Name"   the `crate::accounts` struct of its accounts
+//      optional = "Name"   the `crate::accounts` struct of the accounts passed all together or not
+//      name = "Name"       the instruction name, the variant name by default
+//      arg = "name"        the args are one field of the variant type, flattened by default
+//--------------------------------------------------------------------------------------------------
+#[proc_macro_derive(InstructionsLayout, attributes(interface))]
+pub fn derive_instructions_layout(_input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(_input as DeriveInput);
+    expand(instructions_layout(&input))
+}
+
+//==================================================================================================
+//  expand
+//--------------------------------------------------------------------------------------------------
+fn expand(_result: Result<TokenStream2, Error>) -> TokenStream {
+    _result.unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+//==================================================================================================
+//  layout
+//--------------------------------------------------------------------------------------------------
+fn layout(_input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let name                                        = &_input.ident;
+    let type_name                                   = name.to_string();
+    let (impl_generics, ty_generics, where_clause)  = _input.generics.split_for_impl();
+    let mut fields                                  = Vec::new();
+
+    for field in named_fields(_input)? {
+        let field_name  = field.ident.as_ref().unwrap().to_string();
+        let field_type  = type_name_of(&field.ty)?;
+        fields.push(quote! { (#field_name, #field_type) });
+    }
+
+    Ok(quote! {
+        impl #impl_generics crate::interface::Layout for #name #ty_generics #where_clause {
+            const NAME: &'static str = #type_name;
+
+            fn fields() -> Vec<(&'static str, &'static str)> {
+                vec![#(#fields),*]
+            }
+        }
+    })
+}
+
+//==================================================================================================
+//  accounts_layout
+//--------------------------------------------------------------------------------------------------
+fn accounts_layout(_input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let name                                        = &_input.ident;
+    let (impl_generics, ty_generics, where_clause)  = _input.generics.split_for_impl();
+    let mut metas                                   = Vec::new();
+
+    for field in named_fields(_input)? {
+        let field_name              = field.ident.as_ref().unwrap().to_string();
+        let (writable, signer)      = constraints(&field.attrs);
+        metas.push(quote! {
+            crate::interface::AccountMetaDef { name: #field_name, writable: #writable, signer: #signer, optional: false }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics crate::interface::AccountsLayout for #name #ty_generics #where_clause {
+            fn metas() -> Vec<crate::interface::AccountMetaDef> {
+                vec![#(#metas),*]
+            }
+        }
+    })
+}
+
+//==================================================================================================
+//  instructions_layout
+//--------------------------------------------------------------------------------------------------
+fn instructions_layout(_input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let name        = &_input.ident;
+    let variants    = match &_input.data {
+        Data::Enum(data)    => &data.variants,
+        _                   => return Err(Error::new_spanned(name, "InstructionsLayout is derived for enums only")),
+    };
+
+    let mut instructions = Vec::new();
+
+    for (tag, variant) in variants.iter().enumerate() {
+        let tag = u8::try_from(tag).map_err(|_| Error::new_spanned(variant, "more instructions than one byte tags"))?;
+
+        let args_type = match &variant.fields {
+            Fields::Unit                                        => None,
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Some(&fields.unnamed[0].ty),
+            _                                                   => return Err(Error::new_spanned(variant, "instructions carry one args type or none")),
+        };
+
+        let attributes: Vec<&Attribute> = variant.attrs.iter().filter(|attribute| attribute.path.is_ident("interface")).collect();
+
+        if attributes.is_empty() {
+            return Err(Error::new_spanned(variant, "missing #[interface(accounts = \"...\")]"));
+        }
+
+        for attribute in attributes {
+            let options         = InterfaceOptions::parse(attribute, &variant.ident)?;
+            let instruction     = options.name.unwrap_or_else(|| variant.ident.to_string());
+            let accounts        = &options.accounts;
+            let optional        = options.optional.iter().map(|optional| quote! {
+                crate::interface::optional(<crate::accounts::#optional as crate::interface::AccountsLayout>::metas())
+            });
+            let args = match (args_type, options.arg) {
+                (None, _)               => quote! { &[] },
+                (Some(ty), None)        => quote! { &<#ty as crate::interface::Layout>::fields() },
+                (Some(ty), Some(arg))   => quote! { &[(#arg, <#ty as crate::interface::Layout>::NAME)] },
+            };
+
+            instructions.push(quote! {
+                crate::interface::instruction(
+                    #tag,
+                    #instruction,
+                    [<crate::accounts::#accounts as crate::interface::AccountsLayout>::metas() #(, #optional)*].concat(),
+                    #args,
+                )
+            });
+        }
+    }
+
+    Ok(quote! {
+        impl crate::interface::InstructionsLayout for #name {
+            fn instructions() -> Vec<crate::interface::InstructionDef> {
+                vec![#(#instructions),*]
+            }
+        }
+    })
+}
+
+//**************************************************************************************************
+//  InterfaceOptions, of one `#[interface(...)]` attribute
+//--------------------------------------------------------------------------------------------------
+struct InterfaceOptions {
+    accounts:   Ident,
+    optional:   Option<Ident>,
+    name:       Option<String>,
+    arg:        Option<String>,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl InterfaceOptions {
+
+    //==================================================================================================
+    //  parse
+    //--------------------------------------------------------------------------------------------------
+    fn parse(_attribute: &Attribute, _variant: &Ident) -> Result<Self, Error> {
+        let list = match _attribute.parse_meta()? {
+            Meta::List(list)    => list,
+            meta                => return Err(Error::new_spanned(meta, "expected #[interface(key = \"value\", ...)]")),
+        };
+
+        let mut accounts    = None;
+        let mut optional    = None;
+        let mut name        = None;
+        let mut arg         = None;
+
+        for nested in list.nested.iter() {
+            let (key, value) = match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value)) => match &name_value.lit {
+                    Lit::Str(value) => (name_value.path.get_ident().map(Ident::to_string).unwrap_or_default(), value.value()),
+                    lit             => return Err(Error::new_spanned(lit, "expected a string")),
+                },
+                nested => return Err(Error::new_spanned(nested, "expected key = \"value\"")),
+            };
+
+            match key.as_str() {
+                "accounts"  => accounts = Some(Ident::new(&value, Span::call_site())),
+                "optional"  => optional = Some(Ident::new(&value, Span::call_site())),
+                "name"      => name     = Some(value),
+                "arg"       => arg      = Some(value),
+                _           => return Err(Error::new_spanned(nested, "unknown interface option")),
+            }
+        }
+
+        Ok(Self {
+            accounts:   accounts.ok_or_else(|| Error::new_spanned(_variant, "missing interface option `accounts`"))?,
+            optional,
+            name,
+            arg,
+        })
+    }
+}
+
+//==================================================================================================
+//  named_fields
+//--------------------------------------------------------------------------------------------------
+fn named_fields(_input: &DeriveInput) -> Result<Vec<&syn::Field>, Error> {
+    match &_input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields)   => Ok(fields.named.iter().collect()),
+            _                       => Err(Error::new_spanned(&_input.ident, "expected named fields")),
+        },
+        _ => Err(Error::new_spanned(&_input.ident, "expected a struct")),
+    }
+}
+
+//==================================================================================================
+//  type_name_of, bool, u8, u32, u64, u128, publicKey, the name of a nested type or `Name[n]`
+//--------------------------------------------------------------------------------------------------
+fn type_name_of(_ty: &Type) -> Result<String, Error> {
+    match _ty {
+        Type::Path(path) => {
+            let ident = path.path.segments.last().unwrap().ident.to_string();
+            Ok(if ident == "Pubkey" { "publicKey".to_string() } else { ident })
+        }
+        Type::Array(array) => match &array.len {
+            Expr::Lit(ExprLit { lit: Lit::Int(len), .. }) => Ok(format!("{}[{}]", type_name_of(&array.elem)?, len.base10_digits())),
+            len => Err(Error::new_spanned(len, "array lengths must be literals")),
+        },
+        ty => Err(Error::new_spanned(ty, "no interface type for this field")),
+    }
+}
+
+//==================================================================================================
+//  constraints, whether `#[account(...)]` holds `mut` and `signer`
+//--------------------------------------------------------------------------------------------------
+fn constraints(_attrs: &[Attribute]) -> (bool, bool) {
+    let mut writable    = false;
+    let mut signer      = false;
+
+    for attribute in _attrs.iter().filter(|attribute| attribute.path.is_ident("account")) {
+        for tree in attribute.tokens.clone() {
+            if let TokenTree::Group(group) = tree {
+                for token in group.stream() {
+                    if let TokenTree::Ident(ident) = token {
+                        writable    |= ident == "mut";
+                        signer      |= ident == "signer";
+                    }
+                }
+            }
+        }
+    }
+
+    (writable, signer)
+}
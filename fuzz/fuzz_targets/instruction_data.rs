@@ -0,0 +1,35 @@
+#![no_main]
+
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+use kaizen::instruction::{
+    KaizenInstruction,
+    SIGHASHES,
+};
+use libfuzzer_sys::fuzz_target;
+
+//**************************************************************************************************
+//  Any instruction data either fails to unpack or is one encoding of what it unpacks to, the Borsh
+//  one or the Anchor one with the sighash of its tag in place of the tag
+//--------------------------------------------------------------------------------------------------
+fuzz_target!(|data: &[u8]| {
+    let instruction = match KaizenInstruction::unpack(data) {
+        Ok(instruction) => instruction,
+        Err(_)          => {
+            assert!(KaizenInstruction::try_from_slice(data).is_err());
+            return;
+        }
+    };
+
+    let encoded = instruction.try_to_vec().unwrap();
+    if encoded == data {
+        return;
+    }
+
+    let anchor = SIGHASHES.iter()
+        .filter(|(_, _, tag)| *tag == encoded[0])
+        .any(|(_, sighash, _)| [&sighash[..], &encoded[1..]].concat() == data);
+    assert!(anchor);
+});
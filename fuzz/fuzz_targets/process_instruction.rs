@@ -0,0 +1,169 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use kaizen::{
+    processor::Processor,
+    state::{
+        Bid,
+        Order,
+        Referrer,
+        Savings,
+        Settings,
+        StakeAccount,
+        StakePool,
+    },
+};
+use libfuzzer_sys::fuzz_target;
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::SUCCESS,
+    program_pack::Pack,
+    program_stubs::{self, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use std::sync::{
+    atomic::{AtomicI64, Ordering},
+    Once,
+};
+
+// all lamports in existence fit well below this, the runtime never sees more
+const LAMPORTS_MAX: u64 = 1 << 58;
+
+// the program accounts by their data length, created zeroed like a client does, then the wallets
+const PROGRAM_ACCOUNTS: [usize; 9] = [
+    Settings::LEN,
+    Savings::LEN,
+    Savings::LEN,
+    Savings::LEN,
+    Order::LEN,
+    Bid::LEN,
+    Referrer::LEN,
+    StakePool::LEN,
+    StakeAccount::LEN,
+];
+const WALLETS: usize = 3;
+
+static NOW:     AtomicI64   = AtomicI64::new(0);
+static STUBS:   Once        = Once::new();
+
+//**************************************************************************************************
+//  Stubs, the sysvars `Processor` reads, with the clock taken from the fuzz input
+//--------------------------------------------------------------------------------------------------
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_get_clock_sysvar(&self, _var_addr: *mut u8) -> u64 {
+        let clock = Clock { unix_timestamp: NOW.load(Ordering::Relaxed), ..Clock::default() };
+        unsafe { *(_var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, _var_addr: *mut u8) -> u64 {
+        unsafe { *(_var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+}
+
+//**************************************************************************************************
+//  FuzzStep, one transaction: `accounts` index into the program accounts followed by the wallets,
+//  `signers` and `read_only` are bit sets over the same indexes
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Arbitrary)]
+struct FuzzStep {
+    elapsed:            u32,
+    accounts:           Vec<u8>,
+    signers:            u16,
+    read_only:          u16,
+    instruction_data:   Vec<u8>,
+}
+
+//**************************************************************************************************
+//  FuzzInput, the accounts only ever hold what the steps that succeeded before left in them
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Arbitrary)]
+struct FuzzInput {
+    start:      u32,
+    lamports:   [u64; PROGRAM_ACCOUNTS.len() + WALLETS],
+    steps:      Vec<FuzzStep>,
+}
+
+fuzz_target!(|input: FuzzInput| {
+    STUBS.call_once(|| { program_stubs::set_syscall_stubs(Box::new(Stubs)); });
+
+    let program_id      = kaizen::id();
+    let system_program  = Pubkey::default();
+    let count           = PROGRAM_ACCOUNTS.len() + WALLETS;
+    let keys: Vec<_>    = (1..=count as u8).map(|key| Pubkey::new_from_array([key; 32])).collect();
+    let owned           = |index: usize| index < PROGRAM_ACCOUNTS.len();
+
+    // wallets hold no data, as a system account
+    let mut lamports: Vec<u64>      = input.lamports.iter().map(|lamports| lamports % (LAMPORTS_MAX / count as u64)).collect();
+    let mut data: Vec<Vec<u8>>      = (0..count).map(|index| vec![0; PROGRAM_ACCOUNTS.get(index).copied().unwrap_or(0)]).collect();
+    let lamports_total: u128        = lamports.iter().map(|lamports| *lamports as u128).sum();
+
+    let account_infos: Vec<AccountInfo> = lamports.iter_mut()
+        .zip(data.iter_mut())
+        .enumerate()
+        .map(|(index, (lamports, data))| AccountInfo::new(
+            &keys[index],
+            false,
+            true,
+            lamports,
+            data,
+            if owned(index) { &program_id } else { &system_program },
+            false,
+            0,
+        ))
+        .collect();
+
+    let mut now = input.start as i64;
+
+    for step in input.steps.iter().take(32) {
+        now += step.elapsed as i64;
+        NOW.store(now, Ordering::Relaxed);
+
+        let accounts: Vec<AccountInfo> = step.accounts.iter()
+            .take(16)
+            .map(|index| {
+                let index = *index as usize % count;
+                let mut account = account_infos[index].clone();
+                account.is_signer   = step.signers & (1 << index) != 0;
+                account.is_writable = step.read_only & (1 << index) == 0;
+                account
+            })
+            .collect();
+
+        let lamports_before: Vec<u64>   = account_infos.iter().map(|account| account.lamports()).collect();
+        let data_before: Vec<Vec<u8>>   = account_infos.iter().map(|account| account.data.borrow().to_vec()).collect();
+
+        let result      = Processor::process_instruction(&program_id, &accounts, &step.instruction_data);
+        let changed     = |index: usize| account_infos[index].lamports() != lamports_before[index] || account_infos[index].data.borrow()[..] != data_before[index][..];
+        let writable    = |index: usize| accounts.iter().any(|account| account.key == &keys[index] && account.is_writable);
+
+        // the runtime fails a transaction that changed an account passed read-only, failed
+        // transactions leave the accounts as they were
+        if result.is_err() || (0..count).any(|index| changed(index) && !writable(index)) {
+            for (index, account) in account_infos.iter().enumerate() {
+                **account.lamports.borrow_mut() = lamports_before[index];
+                account.data.borrow_mut().copy_from_slice(&data_before[index]);
+            }
+            continue;
+        }
+
+        // what the runtime would reject after a successful instruction
+        assert_eq!(account_infos.iter().map(|account| account.lamports() as u128).sum::<u128>(), lamports_total, "lamports created or destroyed");
+
+        for (index, account) in account_infos.iter().enumerate().filter(|(index, _)| !owned(*index)) {
+            assert!(lamports_before[index] <= account.lamports(), "debited an account the program does not own");
+            assert_eq!(data_before[index][..], account.data.borrow()[..], "wrote to an account the program does not own");
+        }
+
+        if let Ok(settings) = Settings::unpack(&account_infos[0].data.borrow()) {
+            assert!(settings.supply_locked <= settings.supply_total, "supply_locked above supply_total");
+        }
+    }
+});
@@ -0,0 +1,309 @@
+use anchor_lang::prelude::*;
+
+//**************************************************************************************************
+//  Accounts of each instruction, in the order clients pass them. Anchor checks the `signer`
+//  constraints when the processor reads them, ownership and contents of the accounts stay with the
+//  processor so every rejection keeps its ProgramError
+//--------------------------------------------------------------------------------------------------
+
+//**************************************************************************************************
+//  Initialize
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  Swap, `SwapReferral` follows when the swap is referred
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct Swap<'info> {
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+    #[account(signer)]
+    pub owner:              AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  SwapReferral, the referrer and, depending on `referral_bonus`, its wallet or Savings
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct SwapReferral<'info> {
+    #[account(mut)]
+    pub referrer:           AccountInfo<'info>,
+    #[account(mut)]
+    pub referrer_to:        AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  Withdraw
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  PlaceOrder
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct PlaceOrder<'info> {
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub order:              AccountInfo<'info>,
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+    #[account(signer)]
+    pub owner:              AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  FillOrder
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct FillOrder<'info> {
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub order:              AccountInfo<'info>,
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+    #[account(signer)]
+    pub owner:              AccountInfo<'info>,
+    #[account(mut)]
+    pub seller:             AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  CancelOrder, the seller signs unless the order has expired, which the processor checks
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct CancelOrder<'info> {
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub order:              AccountInfo<'info>,
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+    #[account(mut)]
+    pub seller:             AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  TransferSavings
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct TransferSavings<'info> {
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub savings_from:       AccountInfo<'info>,
+    #[account(signer)]
+    pub owner:              AccountInfo<'info>,
+    #[account(mut)]
+    pub savings_to:         AccountInfo<'info>,
+    pub receiver:           AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  CloseSavings
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct CloseSavings<'info> {
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+    #[account(mut, signer)]
+    pub owner:              AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  ClosePool
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct ClosePool<'info> {
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+    #[account(mut, signer)]
+    pub locked_token_owner: AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  Refund
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct Refund<'info> {
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  CollectRevenue
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct CollectRevenue<'info> {
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+    #[account(mut, signer)]
+    pub revenue_owner:      AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  EarlyWithdraw
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct EarlyWithdraw<'info> {
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+    #[account(signer)]
+    pub owner:              AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  InitializeStakePool
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct InitializeStakePool<'info> {
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub stake_pool:         AccountInfo<'info>,
+    #[account(signer)]
+    pub authority:          AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  Stake, also the accounts of Unstake
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct Stake<'info> {
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub stake_pool:         AccountInfo<'info>,
+    #[account(mut)]
+    pub stake_account:      AccountInfo<'info>,
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+    #[account(signer)]
+    pub owner:              AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  ClaimRewards
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct ClaimRewards<'info> {
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub stake_pool:         AccountInfo<'info>,
+    #[account(mut)]
+    pub stake_account:      AccountInfo<'info>,
+    #[account(mut, signer)]
+    pub owner:              AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  PlaceBid, also the accounts of ClaimAllocation and ClaimRefund
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub bid:                AccountInfo<'info>,
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+    #[account(signer)]
+    pub owner:              AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  Settle
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct Settle<'info> {
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  RegisterReferrer
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct RegisterReferrer<'info> {
+    pub settings:           AccountInfo<'info>,
+    #[account(mut)]
+    pub referrer:           AccountInfo<'info>,
+    #[account(signer)]
+    pub owner:              AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  MigrateSettings, Migrate of a legacy Settings
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct MigrateSettings<'info> {
+    #[account(mut)]
+    pub legacy_settings:    AccountInfo<'info>,
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+    #[account(signer)]
+    pub locked_token_owner: AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  MigrateSavings, Migrate of a legacy Savings, which records no wallet: holding its key is the only
+//  proof of ownership, the holder names the wallet owning the migrated Savings by having it sign.
+//  Legacy Savings at program derived addresses have no key and can't migrate
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct MigrateSavings<'info> {
+    #[account(mut, signer)]
+    pub legacy_savings:     AccountInfo<'info>,
+    #[account(mut)]
+    pub savings:            AccountInfo<'info>,
+    #[account(signer)]
+    pub owner:              AccountInfo<'info>,
+    pub settings:           AccountInfo<'info>,
+    #[account(signer)]
+    pub locked_token_owner: AccountInfo<'info>,
+}
+
+//**************************************************************************************************
+//  UpdateSettings, also the accounts of Pause
+//--------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::AccountsLayout))]
+pub struct UpdateSettings<'info> {
+    #[account(mut)]
+    pub settings:           AccountInfo<'info>,
+    #[account(signer)]
+    pub locked_token_owner: AccountInfo<'info>,
+}
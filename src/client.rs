@@ -0,0 +1,355 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    pubkey::Pubkey,
+    instruction::{
+        AccountMeta,
+        Instruction,
+    },
+    program_error::ProgramError,
+};
+use crate::{
+    instruction::KaizenInstruction,
+    state::{
+        Settings,
+        SwapArgs,
+        WithdrawArgs,
+        EarlyWithdrawArgs,
+        PlaceOrderArgs,
+        FillOrderArgs,
+        TransferSavingsArgs,
+        StakePoolArgs,
+        StakeArgs,
+        BidArgs,
+        UpdateSettingsArgs,
+        PauseArgs,
+    },
+};
+
+//**************************************************************************************************
+//  Instruction builders, accounts are listed in the order `Processor` reads them. The program
+//  has no PDAs, every state account is a regular account owned by the program and sized to the
+//  `Pack::LEN` of its state
+//--------------------------------------------------------------------------------------------------
+
+//==================================================================================================
+//  instruction
+//--------------------------------------------------------------------------------------------------
+fn instruction(_program_id: &Pubkey, _accounts: Vec<AccountMeta>, _instruction: KaizenInstruction) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        program_id: *_program_id,
+        accounts:   _accounts,
+        data:       _instruction.try_to_vec()?,
+    })
+}
+
+//==================================================================================================
+//  initialize
+//--------------------------------------------------------------------------------------------------
+pub fn initialize(_program_id: &Pubkey, _settings: &Pubkey, _settings_data: &Settings) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+    ],
+    KaizenInstruction::Initialize(*_settings_data))
+}
+
+//==================================================================================================
+//  swap, `_referral` is the Referrer account and its wallet or Savings
+//--------------------------------------------------------------------------------------------------
+pub fn swap(_program_id: &Pubkey, _settings: &Pubkey, _savings: &Pubkey, _owner: &Pubkey, _amount: u64, _referral: Option<(&Pubkey, &Pubkey)>) -> Result<Instruction, ProgramError> {
+    let mut accounts = vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new_readonly(*_owner, true),
+    ];
+
+    if let Some((referrer, referrer_to)) = _referral {
+        accounts.push(AccountMeta::new(*referrer, false));
+        accounts.push(AccountMeta::new(*referrer_to, false));
+    }
+
+    instruction(_program_id, accounts, KaizenInstruction::Swap(SwapArgs { amount: _amount }))
+}
+
+//==================================================================================================
+//  withdraw
+//--------------------------------------------------------------------------------------------------
+pub fn withdraw(_program_id: &Pubkey, _settings: &Pubkey, _savings: &Pubkey, _amount: u64) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new(*_savings, false),
+    ],
+    KaizenInstruction::Withdraw(WithdrawArgs { amount: _amount }))
+}
+
+//==================================================================================================
+//  early_withdraw
+//--------------------------------------------------------------------------------------------------
+pub fn early_withdraw(_program_id: &Pubkey, _settings: &Pubkey, _savings: &Pubkey, _owner: &Pubkey, _amount: u64) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new_readonly(*_owner, true),
+    ],
+    KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs { amount: _amount }))
+}
+
+//==================================================================================================
+//  place_order
+//--------------------------------------------------------------------------------------------------
+pub fn place_order(_program_id: &Pubkey, _settings: &Pubkey, _order: &Pubkey, _savings: &Pubkey, _owner: &Pubkey, _args: PlaceOrderArgs) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new_readonly(*_settings, false),
+        AccountMeta::new(*_order, false),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new_readonly(*_owner, true),
+    ],
+    KaizenInstruction::PlaceOrder(_args))
+}
+
+//==================================================================================================
+//  fill_order
+//--------------------------------------------------------------------------------------------------
+pub fn fill_order(_program_id: &Pubkey, _settings: &Pubkey, _order: &Pubkey, _savings: &Pubkey, _owner: &Pubkey, _seller: &Pubkey, _amount: u64) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new_readonly(*_settings, false),
+        AccountMeta::new(*_order, false),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new_readonly(*_owner, true),
+        AccountMeta::new(*_seller, false),
+    ],
+    KaizenInstruction::FillOrder(FillOrderArgs { amount: _amount }))
+}
+
+//==================================================================================================
+//  cancel_order, the seller only has to sign before the order expires
+//--------------------------------------------------------------------------------------------------
+pub fn cancel_order(_program_id: &Pubkey, _settings: &Pubkey, _order: &Pubkey, _savings: &Pubkey, _seller: &Pubkey, _seller_signs: bool) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new_readonly(*_settings, false),
+        AccountMeta::new(*_order, false),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new(*_seller, _seller_signs),
+    ],
+    KaizenInstruction::CancelOrder)
+}
+
+//==================================================================================================
+//  transfer_savings
+//--------------------------------------------------------------------------------------------------
+pub fn transfer_savings(_program_id: &Pubkey, _settings: &Pubkey, _savings_from: &Pubkey, _owner: &Pubkey, _savings_to: &Pubkey, _receiver: &Pubkey, _amount: u64) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new_readonly(*_settings, false),
+        AccountMeta::new(*_savings_from, false),
+        AccountMeta::new_readonly(*_owner, true),
+        AccountMeta::new(*_savings_to, false),
+        AccountMeta::new_readonly(*_receiver, false),
+    ],
+    KaizenInstruction::TransferSavings(TransferSavingsArgs { amount: _amount }))
+}
+
+//==================================================================================================
+//  close_savings
+//--------------------------------------------------------------------------------------------------
+pub fn close_savings(_program_id: &Pubkey, _savings: &Pubkey, _owner: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new(*_owner, true),
+    ],
+    KaizenInstruction::CloseSavings)
+}
+
+//==================================================================================================
+//  close_pool
+//--------------------------------------------------------------------------------------------------
+pub fn close_pool(_program_id: &Pubkey, _settings: &Pubkey, _locked_token_owner: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new(*_locked_token_owner, true),
+    ],
+    KaizenInstruction::ClosePool)
+}
+
+//==================================================================================================
+//  refund
+//--------------------------------------------------------------------------------------------------
+pub fn refund(_program_id: &Pubkey, _settings: &Pubkey, _savings: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new(*_savings, false),
+    ],
+    KaizenInstruction::Refund)
+}
+
+//==================================================================================================
+//  collect_revenue
+//--------------------------------------------------------------------------------------------------
+pub fn collect_revenue(_program_id: &Pubkey, _settings: &Pubkey, _revenue_owner: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new(*_revenue_owner, true),
+    ],
+    KaizenInstruction::CollectRevenue)
+}
+
+//==================================================================================================
+//  initialize_stake_pool
+//--------------------------------------------------------------------------------------------------
+pub fn initialize_stake_pool(_program_id: &Pubkey, _settings: &Pubkey, _stake_pool: &Pubkey, _authority: &Pubkey, _args: StakePoolArgs) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new_readonly(*_settings, false),
+        AccountMeta::new(*_stake_pool, false),
+        AccountMeta::new_readonly(*_authority, true),
+    ],
+    KaizenInstruction::InitializeStakePool(_args))
+}
+
+//==================================================================================================
+//  stake
+//--------------------------------------------------------------------------------------------------
+pub fn stake(_program_id: &Pubkey, _settings: &Pubkey, _stake_pool: &Pubkey, _stake_account: &Pubkey, _savings: &Pubkey, _owner: &Pubkey, _amount: u64) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new_readonly(*_settings, false),
+        AccountMeta::new(*_stake_pool, false),
+        AccountMeta::new(*_stake_account, false),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new_readonly(*_owner, true),
+    ],
+    KaizenInstruction::Stake(StakeArgs { amount: _amount }))
+}
+
+//==================================================================================================
+//  unstake
+//--------------------------------------------------------------------------------------------------
+pub fn unstake(_program_id: &Pubkey, _settings: &Pubkey, _stake_pool: &Pubkey, _stake_account: &Pubkey, _savings: &Pubkey, _owner: &Pubkey, _amount: u64) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new_readonly(*_settings, false),
+        AccountMeta::new(*_stake_pool, false),
+        AccountMeta::new(*_stake_account, false),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new_readonly(*_owner, true),
+    ],
+    KaizenInstruction::Unstake(StakeArgs { amount: _amount }))
+}
+
+//==================================================================================================
+//  claim_rewards
+//--------------------------------------------------------------------------------------------------
+pub fn claim_rewards(_program_id: &Pubkey, _settings: &Pubkey, _stake_pool: &Pubkey, _stake_account: &Pubkey, _owner: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new_readonly(*_settings, false),
+        AccountMeta::new(*_stake_pool, false),
+        AccountMeta::new(*_stake_account, false),
+        AccountMeta::new(*_owner, true),
+    ],
+    KaizenInstruction::ClaimRewards)
+}
+
+//==================================================================================================
+//  place_bid
+//--------------------------------------------------------------------------------------------------
+pub fn place_bid(_program_id: &Pubkey, _settings: &Pubkey, _bid: &Pubkey, _savings: &Pubkey, _owner: &Pubkey, _amount: u64) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new(*_bid, false),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new_readonly(*_owner, true),
+    ],
+    KaizenInstruction::PlaceBid(BidArgs { amount: _amount }))
+}
+
+//==================================================================================================
+//  settle
+//--------------------------------------------------------------------------------------------------
+pub fn settle(_program_id: &Pubkey, _settings: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+    ],
+    KaizenInstruction::Settle)
+}
+
+//==================================================================================================
+//  claim_allocation
+//--------------------------------------------------------------------------------------------------
+pub fn claim_allocation(_program_id: &Pubkey, _settings: &Pubkey, _bid: &Pubkey, _savings: &Pubkey, _owner: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new(*_bid, false),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new_readonly(*_owner, true),
+    ],
+    KaizenInstruction::ClaimAllocation)
+}
+
+//==================================================================================================
+//  claim_refund
+//--------------------------------------------------------------------------------------------------
+pub fn claim_refund(_program_id: &Pubkey, _settings: &Pubkey, _bid: &Pubkey, _savings: &Pubkey, _owner: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new(*_bid, false),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new_readonly(*_owner, true),
+    ],
+    KaizenInstruction::ClaimRefund)
+}
+
+//==================================================================================================
+//  register_referrer
+//--------------------------------------------------------------------------------------------------
+pub fn register_referrer(_program_id: &Pubkey, _settings: &Pubkey, _referrer: &Pubkey, _owner: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new_readonly(*_settings, false),
+        AccountMeta::new(*_referrer, false),
+        AccountMeta::new_readonly(*_owner, true),
+    ],
+    KaizenInstruction::RegisterReferrer)
+}
+
+//==================================================================================================
+//  migrate_settings, `_settings` is a new zeroed account of `Settings::LEN` bytes
+//--------------------------------------------------------------------------------------------------
+pub fn migrate_settings(_program_id: &Pubkey, _legacy_settings: &Pubkey, _settings: &Pubkey, _locked_token_owner: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_legacy_settings, false),
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new_readonly(*_locked_token_owner, true),
+    ],
+    KaizenInstruction::Migrate)
+}
+
+//==================================================================================================
+//  migrate_savings, `_savings` is a new zeroed account of `Savings::LEN` bytes owned by the `_owner`
+//  wallet, the legacy Savings key, `_owner` and the locked token owner of `_settings` sign
+//--------------------------------------------------------------------------------------------------
+pub fn migrate_savings(_program_id: &Pubkey, _legacy_savings: &Pubkey, _savings: &Pubkey, _owner: &Pubkey, _settings: &Pubkey, _locked_token_owner: &Pubkey) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_legacy_savings, true),
+        AccountMeta::new(*_savings, false),
+        AccountMeta::new_readonly(*_owner, true),
+        AccountMeta::new_readonly(*_settings, false),
+        AccountMeta::new_readonly(*_locked_token_owner, true),
+    ],
+    KaizenInstruction::Migrate)
+}
+
+//==================================================================================================
+//  update_settings, signed by the current locked token owner
+//--------------------------------------------------------------------------------------------------
+pub fn update_settings(_program_id: &Pubkey, _settings: &Pubkey, _locked_token_owner: &Pubkey, _args: UpdateSettingsArgs) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new_readonly(*_locked_token_owner, true),
+    ],
+    KaizenInstruction::UpdateSettings(_args))
+}
+
+//==================================================================================================
+//  pause, `_paused` false resumes the pool
+//--------------------------------------------------------------------------------------------------
+pub fn pause(_program_id: &Pubkey, _settings: &Pubkey, _locked_token_owner: &Pubkey, _paused: bool) -> Result<Instruction, ProgramError> {
+    instruction(_program_id, vec![
+        AccountMeta::new(*_settings, false),
+        AccountMeta::new_readonly(*_locked_token_owner, true),
+    ],
+    KaizenInstruction::Pause(PauseArgs { paused: _paused }))
+}
@@ -0,0 +1,437 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+//**************************************************************************************************
+//  Events are logged Anchor style: base64 of the 8 bytes discriminator followed by the Borsh
+//  encoded event, every event starts with the `EVENT_VERSION` it was emitted with
+//--------------------------------------------------------------------------------------------------
+pub const EVENT_VERSION: u8 = 1;
+
+//**************************************************************************************************
+//  Initialized
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Initialized {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub revenue_owner:      Pubkey,
+    pub locked_token:       Pubkey,
+    pub locked_token_owner: Pubkey,
+    pub unlock_timestamp:   u64,
+    pub supply_total:       u64,
+}
+
+//**************************************************************************************************
+//  Swapped
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Swapped {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub savings:            Pubkey,
+    pub owner:              Pubkey,
+    pub amount:             u64,
+    pub custom_amount:      u64,
+    pub timestamp:          u64,
+}
+
+//**************************************************************************************************
+//  Withdrawn
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Withdrawn {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub savings:            Pubkey,
+    pub amount:             u64,
+    pub penalty_share:      u64,
+    pub timestamp:          u64,
+}
+
+//**************************************************************************************************
+//  EarlyWithdrawn, `payout` is what is left of the `amount` after the penalty
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct EarlyWithdrawn {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub savings:            Pubkey,
+    pub amount:             u64,
+    pub payout:             u64,
+    pub timestamp:          u64,
+}
+
+//**************************************************************************************************
+//  Refunded
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Refunded {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub savings:            Pubkey,
+    pub amount:             u64,
+    pub timestamp:          u64,
+}
+
+//**************************************************************************************************
+//  ReferrerRegistered
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct ReferrerRegistered {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub referrer:           Pubkey,
+    pub owner:              Pubkey,
+}
+
+//**************************************************************************************************
+//  ReferrerCredited, by a referred Swap
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct ReferrerCredited {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub referrer:           Pubkey,
+    pub volume:             u64,
+    pub payment:            u64,
+    pub custom_bonus:       u64,
+}
+
+//**************************************************************************************************
+//  OrderPlaced
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct OrderPlaced {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub order:              Pubkey,
+    pub savings:            Pubkey,
+    pub seller:             Pubkey,
+    pub amount:             u64,
+    pub paid:               u64,
+    pub price:              u64,
+    pub expiry_timestamp:   u64,
+}
+
+//**************************************************************************************************
+//  OrderFilled
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct OrderFilled {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub order:              Pubkey,
+    pub savings:            Pubkey,
+    pub buyer:              Pubkey,
+    pub amount:             u64,
+    pub paid:               u64,
+    pub cost:               u64,
+}
+
+//**************************************************************************************************
+//  OrderCancelled, the remainder back on the seller Savings
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct OrderCancelled {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub order:              Pubkey,
+    pub savings:            Pubkey,
+    pub amount:             u64,
+    pub paid:               u64,
+}
+
+//**************************************************************************************************
+//  SavingsTransferred
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct SavingsTransferred {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub from:               Pubkey,
+    pub to:                 Pubkey,
+    pub receiver:           Pubkey,
+    pub amount:             u64,
+    pub paid:               u64,
+}
+
+//**************************************************************************************************
+//  SavingsClosed
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct SavingsClosed {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub savings:            Pubkey,
+    pub owner:              Pubkey,
+}
+
+//**************************************************************************************************
+//  RevenueCollected
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct RevenueCollected {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub revenue_owner:      Pubkey,
+    pub amount:             u64,
+}
+
+//**************************************************************************************************
+//  PoolClosed
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct PoolClosed {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub locked_token_owner: Pubkey,
+}
+
+//**************************************************************************************************
+//  StakePoolInitialized
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct StakePoolInitialized {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub stake_pool:         Pubkey,
+    pub reward_rate:        u64,
+    pub reward_end:         u64,
+}
+
+//**************************************************************************************************
+//  Staked
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Staked {
+    pub version:            u8,
+    pub stake_pool:         Pubkey,
+    pub stake:              Pubkey,
+    pub savings:            Pubkey,
+    pub amount:             u64,
+    pub paid:               u64,
+}
+
+//**************************************************************************************************
+//  Unstaked
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Unstaked {
+    pub version:            u8,
+    pub stake_pool:         Pubkey,
+    pub stake:              Pubkey,
+    pub savings:            Pubkey,
+    pub amount:             u64,
+    pub paid:               u64,
+}
+
+//**************************************************************************************************
+//  RewardsClaimed
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct RewardsClaimed {
+    pub version:            u8,
+    pub stake_pool:         Pubkey,
+    pub stake:              Pubkey,
+    pub owner:              Pubkey,
+    pub amount:             u64,
+}
+
+//**************************************************************************************************
+//  BidPlaced
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct BidPlaced {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub bid:                Pubkey,
+    pub savings:            Pubkey,
+    pub owner:              Pubkey,
+    pub amount:             u64,
+}
+
+//**************************************************************************************************
+//  BatchSettled
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct BatchSettled {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub demand:             u64,
+    pub filled:             u64,
+}
+
+//**************************************************************************************************
+//  AllocationClaimed, `used` is the part of the bid paid for `custom_amount`
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct AllocationClaimed {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub bid:                Pubkey,
+    pub savings:            Pubkey,
+    pub custom_amount:      u64,
+    pub used:               u64,
+}
+
+//**************************************************************************************************
+//  BidRefunded
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct BidRefunded {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub bid:                Pubkey,
+    pub savings:            Pubkey,
+    pub amount:             u64,
+}
+
+//**************************************************************************************************
+//  Migrated
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Migrated {
+    pub version:            u8,
+    pub legacy:             Pubkey,
+    pub migrated:           Pubkey,
+}
+
+//**************************************************************************************************
+//  SettingsUpdated, by UpdateSettings or Pause
+//--------------------------------------------------------------------------------------------------
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct SettingsUpdated {
+    pub version:            u8,
+    pub settings:           Pubkey,
+    pub revenue_owner:      Pubkey,
+    pub locked_token_owner: Pubkey,
+    pub price:              u64,
+    pub paused:             bool,
+}
+
+//**************************************************************************************************
+//  KaizenEvent
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq)]
+pub enum KaizenEvent {
+    Initialized(Initialized),
+    Swapped(Swapped),
+    Withdrawn(Withdrawn),
+    EarlyWithdrawn(EarlyWithdrawn),
+    Refunded(Refunded),
+    ReferrerRegistered(ReferrerRegistered),
+    ReferrerCredited(ReferrerCredited),
+    OrderPlaced(OrderPlaced),
+    OrderFilled(OrderFilled),
+    OrderCancelled(OrderCancelled),
+    SavingsTransferred(SavingsTransferred),
+    SavingsClosed(SavingsClosed),
+    RevenueCollected(RevenueCollected),
+    PoolClosed(PoolClosed),
+    StakePoolInitialized(StakePoolInitialized),
+    Staked(Staked),
+    Unstaked(Unstaked),
+    RewardsClaimed(RewardsClaimed),
+    BidPlaced(BidPlaced),
+    BatchSettled(BatchSettled),
+    AllocationClaimed(AllocationClaimed),
+    BidRefunded(BidRefunded),
+    Migrated(Migrated),
+    SettingsUpdated(SettingsUpdated),
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl KaizenEvent {
+
+    //==================================================================================================
+    //  decodes the base64 decoded data of a program log line, `None` for anything else, including
+    //  an event followed by more data
+    //--------------------------------------------------------------------------------------------------
+    pub fn decode(_data: &[u8]) -> Option<Self> {
+        if _data.len() < 8 {
+            return None;
+        }
+
+        let (discriminator, event_data) = _data.split_at(8);
+        let event_data                  = &mut &event_data[..];
+
+        let event = match discriminator {
+            d if d == Initialized::discriminator()                 => Initialized::deserialize(event_data).ok().map(Self::Initialized),
+            d if d == Swapped::discriminator()                     => Swapped::deserialize(event_data).ok().map(Self::Swapped),
+            d if d == Withdrawn::discriminator()                   => Withdrawn::deserialize(event_data).ok().map(Self::Withdrawn),
+            d if d == EarlyWithdrawn::discriminator()              => EarlyWithdrawn::deserialize(event_data).ok().map(Self::EarlyWithdrawn),
+            d if d == Refunded::discriminator()                    => Refunded::deserialize(event_data).ok().map(Self::Refunded),
+            d if d == ReferrerRegistered::discriminator()          => ReferrerRegistered::deserialize(event_data).ok().map(Self::ReferrerRegistered),
+            d if d == ReferrerCredited::discriminator()            => ReferrerCredited::deserialize(event_data).ok().map(Self::ReferrerCredited),
+            d if d == OrderPlaced::discriminator()                 => OrderPlaced::deserialize(event_data).ok().map(Self::OrderPlaced),
+            d if d == OrderFilled::discriminator()                 => OrderFilled::deserialize(event_data).ok().map(Self::OrderFilled),
+            d if d == OrderCancelled::discriminator()              => OrderCancelled::deserialize(event_data).ok().map(Self::OrderCancelled),
+            d if d == SavingsTransferred::discriminator()          => SavingsTransferred::deserialize(event_data).ok().map(Self::SavingsTransferred),
+            d if d == SavingsClosed::discriminator()               => SavingsClosed::deserialize(event_data).ok().map(Self::SavingsClosed),
+            d if d == RevenueCollected::discriminator()            => RevenueCollected::deserialize(event_data).ok().map(Self::RevenueCollected),
+            d if d == PoolClosed::discriminator()                  => PoolClosed::deserialize(event_data).ok().map(Self::PoolClosed),
+            d if d == StakePoolInitialized::discriminator()        => StakePoolInitialized::deserialize(event_data).ok().map(Self::StakePoolInitialized),
+            d if d == Staked::discriminator()                      => Staked::deserialize(event_data).ok().map(Self::Staked),
+            d if d == Unstaked::discriminator()                    => Unstaked::deserialize(event_data).ok().map(Self::Unstaked),
+            d if d == RewardsClaimed::discriminator()              => RewardsClaimed::deserialize(event_data).ok().map(Self::RewardsClaimed),
+            d if d == BidPlaced::discriminator()                   => BidPlaced::deserialize(event_data).ok().map(Self::BidPlaced),
+            d if d == BatchSettled::discriminator()                => BatchSettled::deserialize(event_data).ok().map(Self::BatchSettled),
+            d if d == AllocationClaimed::discriminator()           => AllocationClaimed::deserialize(event_data).ok().map(Self::AllocationClaimed),
+            d if d == BidRefunded::discriminator()                 => BidRefunded::deserialize(event_data).ok().map(Self::BidRefunded),
+            d if d == Migrated::discriminator()                    => Migrated::deserialize(event_data).ok().map(Self::Migrated),
+            d if d == SettingsUpdated::discriminator()             => SettingsUpdated::deserialize(event_data).ok().map(Self::SettingsUpdated),
+            _                                                      => None,
+        };
+
+        event.filter(|_| event_data.is_empty())
+    }
+}
\ No newline at end of file
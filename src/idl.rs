@@ -0,0 +1,194 @@
+use serde::Serialize;
+use crate::interface::{
+    interface,
+    Field,
+};
+
+//**************************************************************************************************
+//  Idl, the interface description in the IDL format Anchor clients load. State accounts start with
+//  their AccountType tag instead of an Anchor discriminator, so they are listed as types decoded
+//  from byte 0, the Settings account from byte 2 after its tag and version
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Idl {
+    pub version:        &'static str,
+    pub name:           &'static str,
+    pub instructions:   Vec<IdlInstruction>,
+    pub types:          Vec<IdlTypeDef>,
+    pub events:         Vec<IdlEvent>,
+    pub metadata:       IdlMetadata,
+}
+
+//**************************************************************************************************
+//  IdlInstruction
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdlInstruction {
+    pub name:       String,
+    pub accounts:   Vec<IdlAccount>,
+    pub args:       Vec<IdlField>,
+}
+
+//**************************************************************************************************
+//  IdlAccount
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlAccount {
+    pub name:           String,
+    pub is_mut:         bool,
+    pub is_signer:      bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_optional:    bool,
+}
+
+//**************************************************************************************************
+//  IdlTypeDef, always a struct
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdlTypeDef {
+    pub name:   &'static str,
+    #[serde(rename = "type")]
+    pub ty:     IdlStruct,
+}
+
+//**************************************************************************************************
+//  IdlStruct
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdlStruct {
+    pub kind:   &'static str,
+    pub fields: Vec<IdlField>,
+}
+
+//**************************************************************************************************
+//  IdlEvent, event fields are never indexed
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdlEvent {
+    pub name:   &'static str,
+    pub fields: Vec<IdlEventField>,
+}
+
+//**************************************************************************************************
+//  IdlField
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdlField {
+    pub name:   String,
+    #[serde(rename = "type")]
+    pub ty:     IdlType,
+}
+
+//**************************************************************************************************
+//  IdlEventField
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdlEventField {
+    pub name:   String,
+    #[serde(rename = "type")]
+    pub ty:     IdlType,
+    pub index:  bool,
+}
+
+//**************************************************************************************************
+//  IdlType, `"u64"`, `{ "defined": "Token" }` or `{ "array": [{ "defined": "Tier" }, 4] }`
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum IdlType {
+    Primitive(&'static str),
+    Defined { defined: &'static str },
+    Array { array: (Box<IdlType>, usize) },
+}
+
+//**************************************************************************************************
+//  IdlMetadata
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdlMetadata {
+    pub address: String,
+}
+
+//==================================================================================================
+//  idl, Anchor names are camel case, the sighashes are of their snake case
+//--------------------------------------------------------------------------------------------------
+pub fn idl() -> Idl {
+    let interface = interface();
+
+    let instructions = interface.instructions.iter().map(|instruction| IdlInstruction {
+            name:       camel_case(instruction.name),
+            accounts:   instruction.accounts.iter().map(|account| IdlAccount {
+                    name:           camel_case(account.name),
+                    is_mut:         account.writable,
+                    is_signer:      account.signer,
+                    is_optional:    account.optional,
+                })
+                .collect(),
+            args:       fields(&instruction.args),
+        })
+        .collect();
+
+    // the Settings type is the body of the Settings account
+    let types = interface.types.iter().map(|ty| (ty.name, &ty.fields))
+        .chain(interface.accounts.iter().filter(|account| account.name != "Settings").map(|account| (account.name, &account.fields)))
+        .map(|(name, type_fields)| IdlTypeDef { name, ty: IdlStruct { kind: "struct", fields: fields(type_fields) } })
+        .collect();
+
+    let events = interface.events.iter().map(|event| IdlEvent {
+            name:   event.name,
+            fields: fields(&event.fields).into_iter().map(|field| IdlEventField { name: field.name, ty: field.ty, index: false }).collect(),
+        })
+        .collect();
+
+    Idl {
+        version:        interface.version,
+        name:           interface.name,
+        instructions,
+        types,
+        events,
+        metadata:       IdlMetadata { address: interface.program_id },
+    }
+}
+
+//==================================================================================================
+//  fields
+//--------------------------------------------------------------------------------------------------
+fn fields(_fields: &[Field]) -> Vec<IdlField> {
+    _fields.iter().map(|field| IdlField { name: camel_case(field.name), ty: idl_type(field.ty) }).collect()
+}
+
+//==================================================================================================
+//  idl_type, from an interface field type
+//--------------------------------------------------------------------------------------------------
+fn idl_type(_ty: &'static str) -> IdlType {
+    match _ty.find('[') {
+        Some(bracket) => IdlType::Array {
+            array: (Box::new(idl_type(&_ty[..bracket])), _ty[bracket + 1.._ty.len() - 1].parse().unwrap()),
+        },
+        None if _ty.starts_with(char::is_uppercase) => IdlType::Defined { defined: _ty },
+        None => IdlType::Primitive(_ty),
+    }
+}
+
+//==================================================================================================
+//  camel_case, from snake or Pascal case
+//--------------------------------------------------------------------------------------------------
+pub fn camel_case(_name: &str) -> String {
+    let mut name = String::new();
+
+    for (index, word) in _name.split('_').enumerate() {
+        let mut chars = word.chars();
+
+        if let Some(first) = chars.next() {
+            if index == 0 {
+                name.extend(first.to_lowercase());
+            } else {
+                name.extend(first.to_uppercase());
+            }
+            name.push_str(chars.as_str());
+        }
+    }
+
+    name
+}
@@ -2,18 +2,130 @@ use borsh::{
     BorshDeserialize,
     BorshSerialize,
 };
+use solana_program::program_error::ProgramError;
 use crate::state::{
     Settings,
     SwapArgs,
     WithdrawArgs,
+    EarlyWithdrawArgs,
+    PlaceOrderArgs,
+    FillOrderArgs,
+    TransferSavingsArgs,
+    StakePoolArgs,
+    StakeArgs,
+    BidArgs,
+    UpdateSettingsArgs,
+    PauseArgs,
 };
 
 //**************************************************************************************************
-//  Instruction
+//  Instruction, with the accounts struct of each variant for the interface description. Migrate
+//  takes either a legacy Settings or a legacy Savings, with one account list each, and moves it to
+//  a new address: clients switch to the migrated pool, a migrated Savings is owned by the wallet
+//  signing along with the legacy Savings key
 //--------------------------------------------------------------------------------------------------
 #[derive(Debug, BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::InstructionsLayout))]
 pub enum KaizenInstruction {
+    #[cfg_attr(feature = "json", interface(accounts = "Initialize", arg = "settings"))]
     Initialize(Settings),
+    #[cfg_attr(feature = "json", interface(accounts = "Swap", optional = "SwapReferral"))]
     Swap(SwapArgs),
+    #[cfg_attr(feature = "json", interface(accounts = "Withdraw"))]
     Withdraw(WithdrawArgs),
-}
\ No newline at end of file
+    #[cfg_attr(feature = "json", interface(accounts = "PlaceOrder"))]
+    PlaceOrder(PlaceOrderArgs),
+    #[cfg_attr(feature = "json", interface(accounts = "FillOrder"))]
+    FillOrder(FillOrderArgs),
+    #[cfg_attr(feature = "json", interface(accounts = "CancelOrder"))]
+    CancelOrder,
+    #[cfg_attr(feature = "json", interface(accounts = "TransferSavings"))]
+    TransferSavings(TransferSavingsArgs),
+    #[cfg_attr(feature = "json", interface(accounts = "CloseSavings"))]
+    CloseSavings,
+    #[cfg_attr(feature = "json", interface(accounts = "ClosePool"))]
+    ClosePool,
+    #[cfg_attr(feature = "json", interface(accounts = "Refund"))]
+    Refund,
+    #[cfg_attr(feature = "json", interface(accounts = "CollectRevenue"))]
+    CollectRevenue,
+    #[cfg_attr(feature = "json", interface(accounts = "EarlyWithdraw"))]
+    EarlyWithdraw(EarlyWithdrawArgs),
+    #[cfg_attr(feature = "json", interface(accounts = "InitializeStakePool"))]
+    InitializeStakePool(StakePoolArgs),
+    #[cfg_attr(feature = "json", interface(accounts = "Stake"))]
+    Stake(StakeArgs),
+    #[cfg_attr(feature = "json", interface(accounts = "Stake"))]
+    Unstake(StakeArgs),
+    #[cfg_attr(feature = "json", interface(accounts = "ClaimRewards"))]
+    ClaimRewards,
+    #[cfg_attr(feature = "json", interface(accounts = "PlaceBid"))]
+    PlaceBid(BidArgs),
+    #[cfg_attr(feature = "json", interface(accounts = "Settle"))]
+    Settle,
+    #[cfg_attr(feature = "json", interface(accounts = "PlaceBid"))]
+    ClaimAllocation,
+    #[cfg_attr(feature = "json", interface(accounts = "PlaceBid"))]
+    ClaimRefund,
+    #[cfg_attr(feature = "json", interface(accounts = "RegisterReferrer"))]
+    RegisterReferrer,
+    #[cfg_attr(feature = "json", interface(name = "MigrateSettings", accounts = "MigrateSettings"))]
+    #[cfg_attr(feature = "json", interface(name = "MigrateSavings", accounts = "MigrateSavings"))]
+    Migrate,
+    #[cfg_attr(feature = "json", interface(accounts = "UpdateSettings"))]
+    UpdateSettings(UpdateSettingsArgs),
+    #[cfg_attr(feature = "json", interface(accounts = "UpdateSettings"))]
+    Pause(PauseArgs),
+}
+
+//**************************************************************************************************
+//  SIGHASHES, Anchor clients prefix the same Borsh args with the first 8 bytes of
+//  sha256("global:<snake case name>") instead of the tag, Migrate has one name per account list
+//--------------------------------------------------------------------------------------------------
+pub const SIGHASHES: [(&str, [u8; 8], u8); 25] = [
+    ("initialize",            [0xaf, 0xaf, 0x6d, 0x1f, 0x0d, 0x98, 0x9b, 0xed],  0),
+    ("swap",                  [0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8],  1),
+    ("withdraw",              [0xb7, 0x12, 0x46, 0x9c, 0x94, 0x6d, 0xa1, 0x22],  2),
+    ("place_order",           [0x33, 0xc2, 0x9b, 0xaf, 0x6d, 0x82, 0x60, 0x6a],  3),
+    ("fill_order",            [0xe8, 0x7a, 0x73, 0x19, 0xc7, 0x8f, 0x88, 0xa2],  4),
+    ("cancel_order",          [0x5f, 0x81, 0xed, 0xf0, 0x08, 0x31, 0xdf, 0x84],  5),
+    ("transfer_savings",      [0x08, 0xd0, 0xf7, 0xb7, 0x94, 0x5e, 0x48, 0xbb],  6),
+    ("close_savings",         [0xa0, 0x27, 0x77, 0xfe, 0x32, 0x6c, 0x38, 0xfb],  7),
+    ("close_pool",            [0x8c, 0xbd, 0xd1, 0x17, 0xef, 0x3e, 0xef, 0x0b],  8),
+    ("refund",                [0x02, 0x60, 0xb7, 0xfb, 0x3f, 0xd0, 0x2e, 0x2e],  9),
+    ("collect_revenue",       [0x57, 0x60, 0xd3, 0x24, 0xf0, 0x2b, 0xf6, 0x57], 10),
+    ("early_withdraw",        [0x13, 0x93, 0x67, 0x3e, 0x62, 0x37, 0x41, 0xf9], 11),
+    ("initialize_stake_pool", [0x30, 0xbd, 0xf3, 0x49, 0x13, 0x43, 0x24, 0x53], 12),
+    ("stake",                 [0xce, 0xb0, 0xca, 0x12, 0xc8, 0xd1, 0xb3, 0x6c], 13),
+    ("unstake",               [0x5a, 0x5f, 0x6b, 0x2a, 0xcd, 0x7c, 0x32, 0xe1], 14),
+    ("claim_rewards",         [0x04, 0x90, 0x84, 0x47, 0x74, 0x17, 0x97, 0x50], 15),
+    ("place_bid",             [0xee, 0x4d, 0x94, 0x5b, 0xc8, 0x97, 0x5c, 0x92], 16),
+    ("settle",                [0xaf, 0x2a, 0xb9, 0x57, 0x90, 0x83, 0x66, 0xd4], 17),
+    ("claim_allocation",      [0x13, 0x94, 0x80, 0x2e, 0xdc, 0xab, 0xb1, 0x2b], 18),
+    ("claim_refund",          [0x0f, 0x10, 0x1e, 0xa1, 0xff, 0xe4, 0x61, 0x3c], 19),
+    ("register_referrer",     [0x7a, 0xe5, 0xd7, 0xa9, 0x64, 0x91, 0xc6, 0x78], 20),
+    ("migrate_settings",      [0x44, 0x65, 0xec, 0xa5, 0xef, 0x58, 0x38, 0xac], 21),
+    ("migrate_savings",       [0x47, 0x78, 0x7a, 0xfd, 0x7e, 0x4e, 0x30, 0x81], 21),
+    ("update_settings",       [0x51, 0xa6, 0x33, 0xd5, 0x9e, 0x54, 0x9d, 0x6c], 22),
+    ("pause",                 [0xd3, 0x16, 0xdd, 0xfb, 0x4a, 0x79, 0xc1, 0x2f], 23),
+];
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl KaizenInstruction {
+
+    //==================================================================================================
+    //  unpack, Anchor encoded data or else the tag followed by the Borsh args deployed clients send
+    //--------------------------------------------------------------------------------------------------
+    pub fn unpack(_data: &[u8]) -> Result<Self, ProgramError> {
+        if _data.len() >= 8 {
+            if let Some((_, _, tag)) = SIGHASHES.iter().find(|(_, sighash, _)| _data[..8] == sighash[..]) {
+                if let Ok(instruction) = Self::try_from_slice(&[&[*tag], &_data[8..]].concat()) {
+                    return Ok(instruction);
+                }
+            }
+        }
+
+        Ok(Self::try_from_slice(_data)?)
+    }
+}
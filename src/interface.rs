@@ -0,0 +1,324 @@
+use anchor_lang::Discriminator;
+use serde::Serialize;
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::Pack,
+};
+use crate::{
+    instruction::KaizenInstruction,
+    event::{
+        AllocationClaimed,
+        BatchSettled,
+        BidPlaced,
+        BidRefunded,
+        EarlyWithdrawn,
+        Initialized,
+        Migrated,
+        OrderCancelled,
+        OrderFilled,
+        OrderPlaced,
+        PoolClosed,
+        ReferrerCredited,
+        ReferrerRegistered,
+        Refunded,
+        RevenueCollected,
+        RewardsClaimed,
+        SavingsClosed,
+        SavingsTransferred,
+        SettingsUpdated,
+        StakePoolInitialized,
+        Staked,
+        Swapped,
+        Unstaked,
+        Withdrawn,
+    },
+    state::{
+        AccountType,
+        Bid,
+        Order,
+        Referrer,
+        Savings,
+        Settings,
+        StakeAccount,
+        StakePool,
+        Tier,
+        Token,
+    },
+};
+
+//==================================================================================================
+//  PROGRAM_ERRORS, the builtin ProgramErrors named in the program source, listed by build.rs
+//--------------------------------------------------------------------------------------------------
+include!(concat!(env!("OUT_DIR"), "/errors.rs"));
+
+//**************************************************************************************************
+//  Interface, the program as seen by clients in other languages: instruction data is the
+//  instruction `tag`, or its Anchor sighash, followed by its Borsh encoded args, accounts are fixed
+//  little endian layouts, events are logged as base64 of their `discriminator` followed by their
+//  Borsh encoded fields
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Interface {
+    pub name:           &'static str,
+    pub version:        &'static str,
+    pub program_id:     String,
+    pub instructions:   Vec<InstructionDef>,
+    pub accounts:       Vec<AccountDef>,
+    pub types:          Vec<TypeDef>,
+    pub errors:         Vec<ErrorDef>,
+    pub events:         Vec<EventDef>,
+}
+
+//**************************************************************************************************
+//  InstructionDef
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InstructionDef {
+    pub name:       &'static str,
+    pub tag:        u8,
+    pub accounts:   Vec<AccountMetaDef>,
+    pub args:       Vec<Field>,
+}
+
+//**************************************************************************************************
+//  AccountMetaDef, `optional` accounts are passed all together or not at all
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountMetaDef {
+    pub name:       &'static str,
+    pub writable:   bool,
+    pub signer:     bool,
+    pub optional:   bool,
+}
+
+//**************************************************************************************************
+//  AccountDef, `tag` is the leading AccountType byte, `version` the byte after it if versioned
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountDef {
+    pub name:       &'static str,
+    pub tag:        u8,
+    pub version:    Option<u8>,
+    pub len:        usize,
+    pub fields:     Vec<Field>,
+}
+
+//**************************************************************************************************
+//  TypeDef, a struct nested in accounts or args, laid out the same in both
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TypeDef {
+    pub name:       &'static str,
+    pub len:        usize,
+    pub fields:     Vec<Field>,
+}
+
+//**************************************************************************************************
+//  ErrorDef, `code` is the value of the failed transaction's InstructionError
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorDef {
+    pub name:       &'static str,
+    pub code:       u64,
+    pub when:       &'static str,
+}
+
+//**************************************************************************************************
+//  EventDef, field offsets start right after the discriminator
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventDef {
+    pub name:           &'static str,
+    pub discriminator:  [u8; 8],
+    pub fields:         Vec<Field>,
+}
+
+//**************************************************************************************************
+//  Field, `type` is one of bool, u8, u32, u64, u128, publicKey, a TypeDef name or `Name[n]`
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Field {
+    pub name:       &'static str,
+    #[serde(rename = "type")]
+    pub ty:         &'static str,
+    pub offset:     usize,
+    pub size:       usize,
+}
+
+//**************************************************************************************************
+//  Layout, derived for the state, args and event structs: their fields in the order they are laid
+//  out, each with its interface type
+//--------------------------------------------------------------------------------------------------
+pub trait Layout {
+    const NAME: &'static str;
+
+    fn fields() -> Vec<(&'static str, &'static str)>;
+}
+
+//**************************************************************************************************
+//  AccountsLayout, derived for the `crate::accounts` structs
+//--------------------------------------------------------------------------------------------------
+pub trait AccountsLayout {
+    fn metas() -> Vec<AccountMetaDef>;
+}
+
+//**************************************************************************************************
+//  InstructionsLayout, derived for KaizenInstruction
+//--------------------------------------------------------------------------------------------------
+pub trait InstructionsLayout {
+    fn instructions() -> Vec<InstructionDef>;
+}
+
+//==================================================================================================
+//  interface
+//--------------------------------------------------------------------------------------------------
+pub fn interface() -> Interface {
+    Interface {
+        name:           "kaizen",
+        version:        env!("CARGO_PKG_VERSION"),
+        program_id:     crate::id().to_string(),
+        instructions:   KaizenInstruction::instructions(),
+        accounts:       accounts(),
+        types:          nested_types().into_iter().map(|(name, fields)| type_def(name, &fields)).collect(),
+        errors:         PROGRAM_ERRORS.iter().map(|(name, program_error)| error(name, program_error.clone())).collect(),
+        events:         events(),
+    }
+}
+
+//==================================================================================================
+//  nested_types, the structs nested in accounts or args
+//--------------------------------------------------------------------------------------------------
+fn nested_types() -> Vec<(&'static str, Vec<(&'static str, &'static str)>)> {
+    vec![
+        (Token::NAME,       Token::fields()),
+        (Tier::NAME,        Tier::fields()),
+        (Settings::NAME,    Settings::fields()),
+    ]
+}
+
+//==================================================================================================
+//  accounts, fields follow the AccountType tag and, for versioned accounts, the version byte
+//--------------------------------------------------------------------------------------------------
+fn accounts() -> Vec<AccountDef> {
+    vec![
+        account::<Settings>(AccountType::Settings,          Some(Settings::VERSION),    Settings::LEN),
+        account::<Savings>(AccountType::Savings,            Some(Savings::VERSION),     Savings::LEN),
+        account::<Order>(AccountType::Order,                None,                       Order::LEN),
+        account::<Bid>(AccountType::Bid,                    None,                       Bid::LEN),
+        account::<Referrer>(AccountType::Referrer,          None,                       Referrer::LEN),
+        account::<StakePool>(AccountType::StakePool,        None,                       StakePool::LEN),
+        account::<StakeAccount>(AccountType::StakeAccount,  None,                       StakeAccount::LEN),
+    ]
+}
+
+//==================================================================================================
+//  when, the cause of each of the builtin ProgramErrors the program returns
+//--------------------------------------------------------------------------------------------------
+fn when(_name: &str) -> &'static str {
+    match _name {
+        "InvalidInstructionData"    => "the instruction is not allowed by the pool at this time or for this amount",
+        "InvalidAccountData"        => "an account has the wrong size, type, version, pool or owner",
+        "UninitializedAccount"      => "an account expected to be initialized is not",
+        "AccountAlreadyInitialized" => "an account expected to be zeroed is already initialized",
+        "IncorrectProgramId"        => "an account is not owned by the program",
+        "MissingRequiredSignature"  => "an owner or authority did not sign",
+        "NotEnoughAccountKeys"      => "accounts are missing, or only one of a pair of optional accounts was passed",
+        "InsufficientFunds"         => "an account cannot pay the token0 the instruction moves",
+        _                           => "",
+    }
+}
+
+//==================================================================================================
+//  events, every event starts with the EVENT_VERSION it was emitted with
+//--------------------------------------------------------------------------------------------------
+fn events() -> Vec<EventDef> {
+    vec![
+        event::<Initialized>(),
+        event::<Swapped>(),
+        event::<Withdrawn>(),
+        event::<EarlyWithdrawn>(),
+        event::<Refunded>(),
+        event::<ReferrerRegistered>(),
+        event::<ReferrerCredited>(),
+        event::<OrderPlaced>(),
+        event::<OrderFilled>(),
+        event::<OrderCancelled>(),
+        event::<SavingsTransferred>(),
+        event::<SavingsClosed>(),
+        event::<RevenueCollected>(),
+        event::<PoolClosed>(),
+        event::<StakePoolInitialized>(),
+        event::<Staked>(),
+        event::<Unstaked>(),
+        event::<RewardsClaimed>(),
+        event::<BidPlaced>(),
+        event::<BatchSettled>(),
+        event::<AllocationClaimed>(),
+        event::<BidRefunded>(),
+        event::<Migrated>(),
+        event::<SettingsUpdated>(),
+    ]
+}
+
+//==================================================================================================
+//  layout, consecutive fields starting at `_offset`
+//--------------------------------------------------------------------------------------------------
+fn layout(_offset: usize, _fields: &[(&'static str, &'static str)]) -> Vec<Field> {
+    let mut offset = _offset;
+
+    _fields.iter().map(|&(name, ty)| {
+        let field = Field { name, ty, offset, size: size_of(ty) };
+        offset += field.size;
+        field
+    })
+    .collect()
+}
+
+//==================================================================================================
+//  size_of, of a primitive, `Name[n]` or a nested type
+//--------------------------------------------------------------------------------------------------
+fn size_of(_ty: &str) -> usize {
+    match _ty {
+        "bool" | "u8"   => 1,
+        "u32"           => 4,
+        "u64"           => 8,
+        "u128"          => 16,
+        "publicKey"     => 32,
+        _               => match _ty.find('[') {
+            Some(bracket)   => size_of(&_ty[..bracket]) * _ty[bracket + 1.._ty.len() - 1].parse::<usize>().unwrap(),
+            None            => nested_types().iter()
+                .find(|(name, _)| *name == _ty)
+                .map(|(_, fields)| fields.iter().map(|(_, ty)| size_of(ty)).sum())
+                .unwrap_or_else(|| unreachable!("unknown interface type {}", _ty)),
+        },
+    }
+}
+
+//==================================================================================================
+//  constructors, `instruction` and `optional` are called by the derived InstructionsLayout
+//--------------------------------------------------------------------------------------------------
+pub(crate) fn instruction(_tag: u8, _name: &'static str, _accounts: Vec<AccountMetaDef>, _args: &[(&'static str, &'static str)]) -> InstructionDef {
+    InstructionDef { name: _name, tag: _tag, accounts: _accounts, args: layout(0, _args) }
+}
+pub(crate) fn optional(_metas: Vec<AccountMetaDef>) -> Vec<AccountMetaDef> {
+    _metas.into_iter().map(|meta| AccountMetaDef { optional: true, ..meta }).collect()
+}
+fn type_def(_name: &'static str, _fields: &[(&'static str, &'static str)]) -> TypeDef {
+    TypeDef { name: _name, len: size_of(_name), fields: layout(0, _fields) }
+}
+fn account<T: Layout>(_account_type: AccountType, _version: Option<u8>, _len: usize) -> AccountDef {
+    let mut fields = vec![("account_type", "u8")];
+
+    if _version.is_some() {
+        fields.push(("version", "u8"));
+    }
+
+    fields.extend(T::fields());
+    AccountDef { name: T::NAME, tag: _account_type as u8, version: _version, len: _len, fields: layout(0, &fields) }
+}
+fn error(_name: &'static str, _error: ProgramError) -> ErrorDef {
+    ErrorDef { name: _name, code: u64::from(_error), when: when(_name) }
+}
+fn event<T: Layout + Discriminator>() -> EventDef {
+    EventDef { name: T::NAME, discriminator: T::discriminator(), fields: layout(0, &T::fields()) }
+}
@@ -0,0 +1,34 @@
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serializer,
+    de::Error,
+};
+use std::{
+    fmt::Display,
+    str::FromStr,
+};
+
+//**************************************************************************************************
+//  JSON rendering of the state types: pubkeys are base58 and amounts are decimal strings, so
+//  clients without 64 bit integers can read them without losing precision
+//--------------------------------------------------------------------------------------------------
+
+//==================================================================================================
+//  serialize, `#[serde(with = "crate::json")]`
+//--------------------------------------------------------------------------------------------------
+pub fn serialize<T: Display, S: Serializer>(_value: &T, _serializer: S) -> Result<S::Ok, S::Error> {
+    _serializer.collect_str(_value)
+}
+
+//==================================================================================================
+//  deserialize, `#[serde(with = "crate::json")]`
+//--------------------------------------------------------------------------------------------------
+pub fn deserialize<'de, T, D>(_deserializer: D) -> Result<T, D::Error>
+where
+    T:          FromStr,
+    T::Err:     Display,
+    D:          Deserializer<'de>,
+{
+    String::deserialize(_deserializer)?.parse().map_err(D::Error::custom)
+}
\ No newline at end of file
@@ -3,8 +3,20 @@
 #[cfg(not(feature = "no_entrypoint"))]
 mod entrypoint;
 
+pub mod accounts;
 pub mod processor;
 pub mod instruction;
 pub mod state;
+pub mod event;
+pub mod model;
+pub mod quote;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "json")]
+pub mod interface;
+#[cfg(feature = "json")]
+pub mod idl;
 
 solana_program::declare_id!("invoker333333333333333333333333333333333399");
\ No newline at end of file
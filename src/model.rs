@@ -0,0 +1,157 @@
+use solana_program::{
+    pubkey::Pubkey,
+    program_error::ProgramError,
+};
+use crate::state::{
+    Savings,
+    Settings,
+};
+use std::collections::BTreeMap;
+
+//**************************************************************************************************
+//  PoolEvent, one user action replayed by `PoolModel` at `timestamp`
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PoolEvent {
+    Swap            { owner: Pubkey, amount: u64, timestamp: u64 },
+    Withdraw        { owner: Pubkey, amount: u64, timestamp: u64 },
+    EarlyWithdraw   { owner: Pubkey, amount: u64, timestamp: u64 },
+    Refund          { owner: Pubkey, timestamp: u64 },
+    CollectRevenue  { timestamp: u64 },
+}
+
+//**************************************************************************************************
+//  PoolStep, the outcome of one replayed event and the pool totals right after it
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolStep {
+    pub event:          PoolEvent,
+    pub result:         Result<u64, ProgramError>,
+    pub supply_locked:  u64,
+    pub total_raised:   u64,
+    pub pool_lamports:  u64,
+}
+
+//**************************************************************************************************
+//  PoolModel, an off-chain pool running the same `Settings` transitions as the processor. Savings
+//  are keyed by wallet, `pool_lamports` is the token0 held by the Settings account, its `rent`
+//  reserve included. Referrals, orders, bids and staking are not modelled
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolModel {
+    pub settings:       Settings,
+    pub savings:        BTreeMap<Pubkey, Savings>,
+    pub pool_lamports:  u64,
+    pub rent:           u64,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl PoolModel {
+
+    //==================================================================================================
+    //  new, the pool `Initialize` creates at `_now` out of `_settings`, in a Settings account holding
+    //  `_rent`
+    //--------------------------------------------------------------------------------------------------
+    pub fn new(_settings: &Settings, _rent: u64, _now: u64) -> Result<Self, ProgramError> {
+        Ok(Self {
+            settings:       _settings.initialize(_now)?,
+            savings:        BTreeMap::new(),
+            pool_lamports:  _rent,
+            rent:           _rent,
+        })
+    }
+    //==================================================================================================
+    //  apply, returns the token0 moved by `_event`, paid in for swaps and paid out otherwise. A
+    //  rejected event leaves the pool untouched, as a failed transaction would
+    //--------------------------------------------------------------------------------------------------
+    pub fn apply(&mut self, _event: &PoolEvent) -> Result<u64, ProgramError> {
+        let mut settings = self.settings;
+
+        let (position, amount, pool_lamports) = match *_event {
+            PoolEvent::Swap { owner, amount, timestamp } => {
+                let mut user_savings = self.savings.get(&owner).copied().unwrap_or_default();
+
+                settings.swap(&mut user_savings, amount, timestamp, false)?;
+                (Some((owner, user_savings)), amount, self.pool_lamports.checked_add(amount).ok_or(ProgramError::InvalidInstructionData)?)
+            }
+            PoolEvent::Withdraw { owner, amount, timestamp } => {
+                let mut user_savings    = self.position(&owner)?;
+                let penalty_share       = settings.withdraw(&mut user_savings, amount, timestamp)?;
+
+                (Some((owner, user_savings)), penalty_share, self.pool_lamports.checked_sub(penalty_share).ok_or(ProgramError::InsufficientFunds)?)
+            }
+            PoolEvent::EarlyWithdraw { owner, amount, timestamp } => {
+                let mut user_savings    = self.position(&owner)?;
+                let payout              = settings.early_withdraw(&mut user_savings, amount, timestamp)?;
+
+                // the rent reserve of the pool is never paid out
+                if self.pool_lamports.saturating_sub(self.rent) < payout {
+                    return Err(ProgramError::InsufficientFunds);
+                }
+
+                (Some((owner, user_savings)), payout, self.pool_lamports - payout)
+            }
+            PoolEvent::Refund { owner, timestamp } => {
+                let mut user_savings    = self.position(&owner)?;
+                let refund              = settings.refund(&mut user_savings, timestamp)?;
+
+                (Some((owner, user_savings)), refund, self.pool_lamports.checked_sub(refund).ok_or(ProgramError::InsufficientFunds)?)
+            }
+            PoolEvent::CollectRevenue { timestamp } => {
+                let revenue = settings.revenue(self.pool_lamports.saturating_sub(self.rent), timestamp)?;
+
+                // a released penalty pool is collected with the rest
+                if settings.is_penalty_pool_released(timestamp) {
+                    settings.penalty_pool = 0;
+                }
+
+                (None, revenue, self.pool_lamports - revenue)
+            }
+        };
+
+        self.settings       = settings;
+        self.pool_lamports  = pool_lamports;
+
+        if let Some((owner, user_savings)) = position {
+            self.savings.insert(owner, Savings { owner, ..user_savings });
+        }
+
+        Ok(amount)
+    }
+    //==================================================================================================
+    //  position, the Savings of `_owner`, who has to have swapped before
+    //--------------------------------------------------------------------------------------------------
+    fn position(&self, _owner: &Pubkey) -> Result<Savings, ProgramError> {
+        self.savings.get(_owner).copied().ok_or(ProgramError::UninitializedAccount)
+    }
+    //==================================================================================================
+    //  replay, applies `_events` in order, rejected ones included, and reports every step
+    //--------------------------------------------------------------------------------------------------
+    pub fn replay(&mut self, _events: &[PoolEvent]) -> Vec<PoolStep> {
+        _events.iter().map(|event| {
+            let result = self.apply(event);
+
+            PoolStep {
+                event:          *event,
+                result,
+                supply_locked:  self.settings.supply_locked,
+                total_raised:   self.settings.total_raised,
+                pool_lamports:  self.pool_lamports,
+            }
+        })
+        .collect()
+    }
+    //==================================================================================================
+    //  holders, wallets still holding a position
+    //--------------------------------------------------------------------------------------------------
+    pub fn holders(&self) -> usize {
+        self.savings.values().filter(|savings| savings.total_technical != 0 || savings.interest_reserved != 0).count()
+    }
+    //==================================================================================================
+    //  is_sold_out
+    //--------------------------------------------------------------------------------------------------
+    pub fn is_sold_out(&self) -> bool {
+        self.settings.supply_total <= self.settings.supply_locked
+    }
+}
\ No newline at end of file
@@ -1,13 +1,77 @@
 use crate::{
+    accounts::{
+        Initialize,
+        Swap,
+        SwapReferral,
+        Withdraw,
+        PlaceOrder,
+        FillOrder,
+        CancelOrder,
+        TransferSavings,
+        CloseSavings,
+        ClosePool,
+        Refund,
+        CollectRevenue,
+        EarlyWithdraw,
+        InitializeStakePool,
+        Stake,
+        ClaimRewards,
+        PlaceBid,
+        Settle,
+        RegisterReferrer,
+        MigrateSettings,
+        MigrateSavings,
+        UpdateSettings,
+    },
     instruction::KaizenInstruction,
+    event::{
+        EVENT_VERSION,
+        Initialized,
+        Swapped,
+        Withdrawn,
+        RevenueCollected,
+        PoolClosed,
+        StakePoolInitialized,
+        BatchSettled,
+        Migrated,
+        SettingsUpdated,
+        EarlyWithdrawn,
+        Refunded,
+        ReferrerCredited,
+        ReferrerRegistered,
+        OrderPlaced,
+        OrderFilled,
+        OrderCancelled,
+        SavingsTransferred,
+        SavingsClosed,
+        Staked,
+        Unstaked,
+        RewardsClaimed,
+        BidPlaced,
+        AllocationClaimed,
+        BidRefunded,
+    },
     state::{
         Settings,
         SwapArgs,
         WithdrawArgs,
+        EarlyWithdrawArgs,
+        PlaceOrderArgs,
+        FillOrderArgs,
+        TransferSavingsArgs,
+        StakePoolArgs,
+        StakeArgs,
+        BidArgs,
+        UpdateSettingsArgs,
+        PauseArgs,
         Savings,
+        Order,
+        StakePool,
+        StakeAccount,
+        Bid,
+        Referrer,
     },
 };
-use ::borsh::BorshDeserialize;
 use solana_program::{
     pubkey::Pubkey,
     account_info::AccountInfo,
@@ -29,113 +93,1251 @@ impl Processor {
     //==================================================================================================
     //  initialize
     //--------------------------------------------------------------------------------------------------
-    fn initialize(_accounts: &[AccountInfo], _settings: &Settings) -> ProgramResult {
-        let accounts_info_it    = &mut _accounts.iter();
-        let account_settings    = next_account_info(accounts_info_it)?;
+    fn initialize(_program_id: &Pubkey, _accounts: &[AccountInfo], _settings: &Settings) -> ProgramResult {
+        let accounts            = Initialize::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
         let settings            = Settings::unpack_unchecked(&account_settings.data.borrow())?;
 
         if settings.is_initialized() {
             return Err(ProgramError::AccountAlreadyInitialized);
         }
 
-        if _settings.unlock_timestamp <= (Clock::get()?.unix_timestamp as u64) {
-            return Err(ProgramError::InvalidInstructionData); 
+        Settings::pack(_settings.initialize(Clock::get()?.unix_timestamp as u64)?, &mut account_settings.data.borrow_mut())?;
+
+        emit!(Initialized {
+            version:            EVENT_VERSION,
+            settings:           *account_settings.key,
+            revenue_owner:      _settings.revenue_owner,
+            locked_token:       _settings.locked_token,
+            locked_token_owner: _settings.locked_token_owner,
+            unlock_timestamp:   _settings.unlock_timestamp,
+            supply_total:       _settings.supply_total,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  swap
+    //--------------------------------------------------------------------------------------------------
+    fn swap(_program_id: &Pubkey, _accounts: &[AccountInfo], _swap_args: &SwapArgs) -> ProgramResult {
+        let accounts_remaining  = &mut &_accounts[..];
+        let accounts            = Swap::try_accounts(_program_id, accounts_remaining, &[])?;
+        let account_settings    = &accounts.settings;
+        let account_from        = &accounts.savings;
+        let account_owner       = &accounts.owner;
+        let mut settings        = Settings::unpack(&account_settings.data.borrow())?;
+
+        // the referral accounts are optional, but passed both or none
+        let referral = match accounts_remaining.is_empty() {
+            true    => None,
+            false   => Some(SwapReferral::try_accounts(_program_id, accounts_remaining, &[])?),
+        };
+
+        if account_from.try_borrow_data()?.len() != Savings::LEN {
+            return Err(ProgramError::InvalidAccountData); 
+        }
+
+        let mut user_savings = Savings::unpack_unchecked(&account_from.data.borrow())?;
+
+        if user_savings.is_initialized() && (user_savings.owner != *account_owner.key || user_savings.settings != *account_settings.key) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let now             = Clock::get()?.unix_timestamp as u64;
+        let swap_amounts    = settings.swap(&mut user_savings, _swap_args.amount, now, referral.is_some())?;
+
+        let account_from_lamports = account_from.lamports().checked_sub(_swap_args.amount).ok_or(ProgramError::InsufficientFunds)?;
+
+        **account_from.try_borrow_mut_lamports()?       = account_from_lamports;
+        **account_settings.try_borrow_mut_lamports()?   += _swap_args.amount;
+
+        Settings::pack(settings, &mut account_settings.data.borrow_mut())?;
+        Savings::pack(Savings {
+            owner:      *account_owner.key,
+            settings:   *account_settings.key,
+            ..user_savings
+        },
+        &mut account_from.data.borrow_mut())?;
+
+        if let Some(referral) = referral {
+            Self::credit_referrer(_program_id, account_settings, account_owner, &referral.referrer, &referral.referrer_to, _swap_args.amount, swap_amounts.referral_payment, swap_amounts.referral_bonus)?;
+        }
+
+        emit!(Swapped {
+            version:        EVENT_VERSION,
+            settings:       *account_settings.key,
+            savings:        *account_from.key,
+            owner:          *account_owner.key,
+            amount:         _swap_args.amount,
+            custom_amount:  swap_amounts.custom_amount,
+            timestamp:      now,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  credit_referrer
+    //--------------------------------------------------------------------------------------------------
+    #[allow(clippy::too_many_arguments)]
+    fn credit_referrer(
+        _program_id:            &Pubkey,
+        _account_settings:      &AccountInfo,
+        _account_owner:         &AccountInfo,
+        _account_referrer:      &AccountInfo,
+        _account_referrer_to:   &AccountInfo,
+        _volume:                u64,
+        _payment:               u64,
+        _custom_bonus:          u64,
+    ) -> ProgramResult {
+        if _account_referrer.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut referrer = Referrer::unpack(&_account_referrer.data.borrow())?;
+
+        if referrer.settings != *_account_settings.key || referrer.owner == *_account_owner.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if _payment != 0 {
+            if *_account_referrer_to.key != referrer.owner {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            **_account_settings.try_borrow_mut_lamports()?      -= _payment;
+            **_account_referrer_to.try_borrow_mut_lamports()?   += _payment;
+        }
+
+        if _custom_bonus != 0 {
+            let referrer_savings = Savings::unpack_unchecked(&_account_referrer_to.data.borrow())?;
+
+            if referrer_savings.is_initialized() && (referrer_savings.owner != referrer.owner || referrer_savings.settings != referrer.settings) {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            let total_technical = referrer_savings.total_technical.checked_add(_custom_bonus).ok_or(ProgramError::InvalidInstructionData)?;
+
+            Savings::pack(Savings {
+                is_initialized:     true,
+                total_technical,
+                owner:              referrer.owner,
+                settings:           referrer.settings,
+                ..referrer_savings
+            },
+            &mut _account_referrer_to.data.borrow_mut())?;
+        }
+
+        referrer.volume     = referrer.volume.checked_add(_volume).ok_or(ProgramError::InvalidInstructionData)?;
+        referrer.rewards    = _payment.checked_add(_custom_bonus)
+            .and_then(|rewards| referrer.rewards.checked_add(rewards))
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Referrer::pack(referrer, &mut _account_referrer.data.borrow_mut())?;
+
+        emit!(ReferrerCredited {
+            version:        EVENT_VERSION,
+            settings:       *_account_settings.key,
+            referrer:       *_account_referrer.key,
+            volume:         _volume,
+            payment:        _payment,
+            custom_bonus:   _custom_bonus,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  register_referrer
+    //--------------------------------------------------------------------------------------------------
+    fn register_referrer(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts            = RegisterReferrer::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_referrer    = &accounts.referrer;
+        let account_owner       = &accounts.owner;
+        Settings::unpack(&account_settings.data.borrow())?;
+
+        if account_referrer.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if Referrer::unpack_unchecked(&account_referrer.data.borrow())?.is_initialized() {
+            return Err(ProgramError::AccountAlreadyInitialized);
         }
 
-        Settings::pack(Settings {
+        Referrer::pack(Referrer {
             is_initialized: true,
-            supply_locked:  0,
-            ..*_settings
+            settings:       *account_settings.key,
+            owner:          *account_owner.key,
+            volume:         0,
+            rewards:        0,
         },
-        &mut account_settings.data.borrow_mut())?;
+        &mut account_referrer.data.borrow_mut())?;
+
+        emit!(ReferrerRegistered {
+            version:    EVENT_VERSION,
+            settings:   *account_settings.key,
+            referrer:   *account_referrer.key,
+            owner:      *account_owner.key,
+        });
 
         Ok(())
     }
     //==================================================================================================
-    //  swap
+    //  withdraw
     //--------------------------------------------------------------------------------------------------
-    fn swap(_accounts: &[AccountInfo], _swap_args: &SwapArgs) -> ProgramResult {
-        let accounts_info_it    = &mut _accounts.iter();
-        let account_settings    = next_account_info(accounts_info_it)?;
-        let account_from        = next_account_info(accounts_info_it)?;
+    fn withdraw(_program_id: &Pubkey, _accounts: &[AccountInfo], _withdraw_args: &WithdrawArgs) -> ProgramResult {
+        let accounts            = Withdraw::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_from        = &accounts.savings;
         let mut settings        = Settings::unpack(&account_settings.data.borrow())?;
 
-        if settings.unlock_timestamp <= (Clock::get()?.unix_timestamp as u64) {
-            return Err(ProgramError::InvalidInstructionData); 
+        if account_from.try_borrow_data()?.len() != Savings::LEN {
+            return Err(ProgramError::InvalidAccountData); 
         }
 
-        if !account_from.try_borrow_data()?.is_empty() && account_from.try_borrow_data()?.len() != Savings::LEN {
-            return Err(ProgramError::InvalidAccountData); 
+        let mut user_savings = Savings::unpack(&account_from.data.borrow())?;
+
+        if user_savings.settings != *account_settings.key {
+            return Err(ProgramError::InvalidAccountData);
         }
 
-        let amount_with_interest        = _swap_args.amount + (_swap_args.amount * (settings.interest_basis_points as u64) / 100);
-        let custom_amount_with_interest = amount_with_interest / settings.token0.price;
-        let custom_allowed              = settings.supply_total - settings.supply_locked;
+        let now             = Clock::get()?.unix_timestamp as u64;
+        let penalty_share   = settings.withdraw(&mut user_savings, _withdraw_args.amount, now)?;
 
-        if custom_allowed < custom_amount_with_interest {
-            return Err(ProgramError::InvalidInstructionData);
+        Settings::pack(settings, &mut account_settings.data.borrow_mut())?;
+        Savings::pack(user_savings, &mut account_from.data.borrow_mut())?;
+
+        **account_settings.try_borrow_mut_lamports()?   -= penalty_share;
+        **account_from.try_borrow_mut_lamports()?       += penalty_share;
+
+        emit!(Withdrawn {
+            version:        EVENT_VERSION,
+            settings:       *account_settings.key,
+            savings:        *account_from.key,
+            amount:         _withdraw_args.amount,
+            penalty_share,
+            timestamp:      now,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  early_withdraw, only on pools with a penalty
+    //--------------------------------------------------------------------------------------------------
+    fn early_withdraw(_program_id: &Pubkey, _accounts: &[AccountInfo], _early_withdraw_args: &EarlyWithdrawArgs) -> ProgramResult {
+        let accounts            = EarlyWithdraw::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_from        = &accounts.savings;
+        let account_owner       = &accounts.owner;
+        let mut settings        = Settings::unpack(&account_settings.data.borrow())?;
+
+        let now                 = Clock::get()?.unix_timestamp as u64;
+        let mut user_savings    = Self::unpack_savings(account_settings, account_from, account_owner)?;
+        let payout              = settings.early_withdraw(&mut user_savings, _early_withdraw_args.amount, now)?;
+
+        // the rent reserve of the pool is never paid out
+        let rent_exempt = Rent::get()?.minimum_balance(account_settings.data_len());
+
+        if account_settings.lamports().saturating_sub(rent_exempt) < payout {
+            return Err(ProgramError::InsufficientFunds);
         }
 
-        **account_from.try_borrow_mut_lamports()?       -= _swap_args.amount;
-        **account_settings.try_borrow_mut_lamports()?   += _swap_args.amount;
+        **account_settings.try_borrow_mut_lamports()?   -= payout;
+        **account_from.try_borrow_mut_lamports()?       += payout;
+
+        Settings::pack(settings, &mut account_settings.data.borrow_mut())?;
+        Savings::pack(user_savings, &mut account_from.data.borrow_mut())?;
+
+        emit!(EarlyWithdrawn {
+            version:        EVENT_VERSION,
+            settings:       *account_settings.key,
+            savings:        *account_from.key,
+            amount:         _early_withdraw_args.amount,
+            payout,
+            timestamp:      now,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  refund
+    //--------------------------------------------------------------------------------------------------
+    fn refund(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts            = Refund::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_from        = &accounts.savings;
+        let mut settings        = Settings::unpack(&account_settings.data.borrow())?;
+
+        let mut user_savings = Savings::unpack(&account_from.data.borrow())?;
+
+        if user_savings.settings != *account_settings.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // contributions go back to the account they were paid from
+        let now                         = Clock::get()?.unix_timestamp as u64;
+        let refund                      = settings.refund(&mut user_savings, now)?;
+        let account_settings_lamports   = account_settings.lamports().checked_sub(refund).ok_or(ProgramError::InsufficientFunds)?;
+
+        **account_settings.try_borrow_mut_lamports()?   = account_settings_lamports;
+        **account_from.try_borrow_mut_lamports()?       += refund;
 
-        settings.supply_locked                          += custom_amount_with_interest;
         Settings::pack(settings, &mut account_settings.data.borrow_mut())?;
+        Savings::pack(user_savings, &mut account_from.data.borrow_mut())?;
+
+        emit!(Refunded {
+            version:        EVENT_VERSION,
+            settings:       *account_settings.key,
+            savings:        *account_from.key,
+            amount:         refund,
+            timestamp:      now,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  collect_revenue
+    //--------------------------------------------------------------------------------------------------
+    fn collect_revenue(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts            = CollectRevenue::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_owner       = &accounts.revenue_owner;
+        let mut settings        = Settings::unpack(&account_settings.data.borrow())?;
+
+        if settings.revenue_owner != *account_owner.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let now         = Clock::get()?.unix_timestamp as u64;
+        let rent_exempt = Rent::get()?.minimum_balance(account_settings.data_len());
+        let revenue     = settings.revenue(account_settings.lamports().saturating_sub(rent_exempt), now)?;
+
+        // a released penalty pool is collected with the rest, withdrawals must not pay it out again
+        if settings.is_penalty_pool_released(now) && settings.penalty_pool != 0 {
+            settings.penalty_pool = 0;
+            Settings::pack(settings, &mut account_settings.data.borrow_mut())?;
+        }
+
+        **account_settings.try_borrow_mut_lamports()?   -= revenue;
+        **account_owner.try_borrow_mut_lamports()?      += revenue;
+
+        emit!(RevenueCollected {
+            version:        EVENT_VERSION,
+            settings:       *account_settings.key,
+            revenue_owner:  *account_owner.key,
+            amount:         revenue,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  place_order
+    //--------------------------------------------------------------------------------------------------
+    fn place_order(_program_id: &Pubkey, _accounts: &[AccountInfo], _place_order_args: &PlaceOrderArgs) -> ProgramResult {
+        let accounts            = PlaceOrder::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_order       = &accounts.order;
+        let account_from        = &accounts.savings;
+        let account_owner       = &accounts.owner;
+        let settings            = Self::unpack_unpaused(account_settings)?;
+
+        if account_order.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if Order::unpack_unchecked(&account_order.data.borrow())?.is_initialized() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        if _place_order_args.amount == 0 || _place_order_args.price == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let now = Clock::get()?.unix_timestamp as u64;
+
+        if _place_order_args.expiry_timestamp <= now {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // orders carry no interest reserve, positions are sold once their interest has accrued
+        let mut user_savings = Self::unpack_savings(account_settings, account_from, account_owner)?;
+        user_savings.accrue_interest(&settings, now);
+
+        let paid = user_savings.split(_place_order_args.amount)?;
+        Savings::pack(user_savings, &mut account_from.data.borrow_mut())?;
+
+        Order::pack(Order {
+            is_initialized:     true,
+            settings:           *account_settings.key,
+            seller:             *account_owner.key,
+            amount:             _place_order_args.amount,
+            paid,
+            price:              _place_order_args.price,
+            expiry_timestamp:   _place_order_args.expiry_timestamp,
+        },
+        &mut account_order.data.borrow_mut())?;
+
+        emit!(OrderPlaced {
+            version:            EVENT_VERSION,
+            settings:           *account_settings.key,
+            order:              *account_order.key,
+            savings:            *account_from.key,
+            seller:             *account_owner.key,
+            amount:             _place_order_args.amount,
+            paid,
+            price:              _place_order_args.price,
+            expiry_timestamp:   _place_order_args.expiry_timestamp,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  fill_order
+    //--------------------------------------------------------------------------------------------------
+    fn fill_order(_program_id: &Pubkey, _accounts: &[AccountInfo], _fill_order_args: &FillOrderArgs) -> ProgramResult {
+        let accounts            = FillOrder::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_order       = &accounts.order;
+        let account_to          = &accounts.savings;
+        let account_owner       = &accounts.owner;
+        let account_seller      = &accounts.seller;
+        Self::unpack_unpaused(account_settings)?;
+
+        if account_order.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut order = Order::unpack(&account_order.data.borrow())?;
+
+        if order.settings != *account_settings.key || order.seller != *account_seller.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if order.expiry_timestamp <= (Clock::get()?.unix_timestamp as u64) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if _fill_order_args.amount == 0 || order.amount < _fill_order_args.amount {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let user_savings = Savings::unpack_unchecked(&account_to.data.borrow())?;
+
+        if user_savings.is_initialized() && (user_savings.owner != *account_owner.key || user_savings.settings != *account_settings.key) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let cost = _fill_order_args.amount.checked_mul(order.price).ok_or(ProgramError::InvalidInstructionData)?;
+        let account_to_lamports = account_to.lamports().checked_sub(cost).ok_or(ProgramError::InsufficientFunds)?;
+
+        **account_to.try_borrow_mut_lamports()?     = account_to_lamports;
+        **account_seller.try_borrow_mut_lamports()? += cost;
+
+        let paid = order.split(_fill_order_args.amount)?;
+        Order::pack(order, &mut account_order.data.borrow_mut())?;
 
-        let user_savings                                = Savings::unpack_unchecked(&account_from.data.borrow())?;
         Savings::pack(Savings {
-            is_initialized: true,
-            total_technical: if user_savings.is_initialized() {
-                    user_savings.total_technical + custom_amount_with_interest
-                } else {
-                    custom_amount_with_interest
-                },
+            is_initialized:     true,
+            total_technical:    user_savings.total_technical + _fill_order_args.amount,
+            total_paid:         user_savings.total_paid + paid,
+            owner:              *account_owner.key,
+            settings:           *account_settings.key,
+            ..user_savings
+        },
+        &mut account_to.data.borrow_mut())?;
+
+        emit!(OrderFilled {
+            version:    EVENT_VERSION,
+            settings:   *account_settings.key,
+            order:      *account_order.key,
+            savings:    *account_to.key,
+            buyer:      *account_owner.key,
+            amount:     _fill_order_args.amount,
+            paid,
+            cost,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  cancel_order
+    //--------------------------------------------------------------------------------------------------
+    fn cancel_order(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts            = CancelOrder::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_order       = &accounts.order;
+        let account_from        = &accounts.savings;
+        let account_owner       = &accounts.seller;
+
+        if account_order.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let order = Order::unpack(&account_order.data.borrow())?;
+
+        if order.settings != *account_settings.key || order.seller != *account_owner.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // once expired anyone may cancel, the remainder always goes back to the seller
+        if !account_owner.is_signer && (Clock::get()?.unix_timestamp as u64) < order.expiry_timestamp {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        // the seller may have closed the position meanwhile, an empty account is reopened
+        let user_savings = Savings::unpack_unchecked(&account_from.data.borrow())?;
+
+        if user_savings.is_initialized() && (user_savings.owner != order.seller || user_savings.settings != order.settings) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Savings::pack(Savings {
+            is_initialized:     true,
+            total_technical:    user_savings.total_technical + order.amount,
+            total_paid:         user_savings.total_paid + order.paid,
+            owner:              order.seller,
+            settings:           order.settings,
             ..user_savings
         },
         &mut account_from.data.borrow_mut())?;
 
+        emit!(OrderCancelled {
+            version:    EVENT_VERSION,
+            settings:   order.settings,
+            order:      *account_order.key,
+            savings:    *account_from.key,
+            amount:     order.amount,
+            paid:       order.paid,
+        });
+
+        Self::close_account(account_order, account_owner)
+    }
+    //==================================================================================================
+    //  transfer_savings
+    //--------------------------------------------------------------------------------------------------
+    fn transfer_savings(_program_id: &Pubkey, _accounts: &[AccountInfo], _transfer_args: &TransferSavingsArgs) -> ProgramResult {
+        let accounts            = TransferSavings::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_from        = &accounts.savings_from;
+        let account_owner       = &accounts.owner;
+        let account_to          = &accounts.savings_to;
+        let account_receiver    = &accounts.receiver;
+        let settings            = Self::unpack_unpaused(account_settings)?;
+
+        if _transfer_args.amount == 0 || account_from.key == account_to.key {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut user_savings = Self::unpack_savings(account_settings, account_from, account_owner)?;
+
+        // an empty receiving account is opened for the receiver wallet
+        let mut receiver_savings = Savings::unpack_unchecked(&account_to.data.borrow())?;
+
+        if receiver_savings.is_initialized() && (receiver_savings.owner != *account_receiver.key || receiver_savings.settings != *account_settings.key) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let paid = user_savings.transfer(&mut receiver_savings, _transfer_args.amount, &settings, Clock::get()?.unix_timestamp as u64)?;
+
+        Savings::pack(user_savings, &mut account_from.data.borrow_mut())?;
+        Savings::pack(Savings {
+            is_initialized:     true,
+            owner:              *account_receiver.key,
+            settings:           *account_settings.key,
+            ..receiver_savings
+        },
+        &mut account_to.data.borrow_mut())?;
+
+        emit!(SavingsTransferred {
+            version:    EVENT_VERSION,
+            settings:   *account_settings.key,
+            from:       *account_from.key,
+            to:         *account_to.key,
+            receiver:   *account_receiver.key,
+            amount:     _transfer_args.amount,
+            paid,
+        });
+
         Ok(())
     }
     //==================================================================================================
-    //  withdraw
+    //  initialize_stake_pool
+    //--------------------------------------------------------------------------------------------------
+    fn initialize_stake_pool(_program_id: &Pubkey, _accounts: &[AccountInfo], _stake_pool_args: &StakePoolArgs) -> ProgramResult {
+        let accounts            = InitializeStakePool::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_stake_pool  = &accounts.stake_pool;
+        let account_authority   = &accounts.authority;
+        let settings            = Settings::unpack(&account_settings.data.borrow())?;
+
+        if settings.locked_token_owner != *account_authority.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if account_stake_pool.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if StakePool::unpack_unchecked(&account_stake_pool.data.borrow())?.is_initialized() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let now = Clock::get()?.unix_timestamp as u64;
+
+        if _stake_pool_args.reward_end <= now {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        StakePool::pack(StakePool {
+            is_initialized:     true,
+            settings:           *account_settings.key,
+            authority:          *account_authority.key,
+            reward_rate:        _stake_pool_args.reward_rate,
+            reward_end:         _stake_pool_args.reward_end,
+            reward_per_share:   0,
+            last_update:        now,
+            total_staked:       0,
+        },
+        &mut account_stake_pool.data.borrow_mut())?;
+
+        emit!(StakePoolInitialized {
+            version:        EVENT_VERSION,
+            settings:       *account_settings.key,
+            stake_pool:     *account_stake_pool.key,
+            reward_rate:    _stake_pool_args.reward_rate,
+            reward_end:     _stake_pool_args.reward_end,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  stake
     //--------------------------------------------------------------------------------------------------
-    fn withdraw(_accounts: &[AccountInfo], _withdraw_args: &WithdrawArgs) -> ProgramResult {
-        let accounts_info_it    = &mut _accounts.iter();
-        let account_settings    = next_account_info(accounts_info_it)?;
-        let account_from        = next_account_info(accounts_info_it)?;
+    fn stake(_program_id: &Pubkey, _accounts: &[AccountInfo], _stake_args: &StakeArgs) -> ProgramResult {
+        let accounts                = Stake::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings        = &accounts.settings;
+        let account_stake_pool      = &accounts.stake_pool;
+        let account_stake           = &accounts.stake_account;
+        let account_from            = &accounts.savings;
+        let account_owner           = &accounts.owner;
+        let mut stake_pool          = Self::unpack_stake_pool(_program_id, account_settings, account_stake_pool)?;
+        let settings                = Self::unpack_unpaused(account_settings)?;
+
+        if account_stake.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let stake_account = StakeAccount::unpack_unchecked(&account_stake.data.borrow())?;
+
+        if stake_account.is_initialized() && (stake_account.stake_pool != *account_stake_pool.key || stake_account.owner != *account_owner.key) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut user_savings = Self::unpack_savings(account_settings, account_from, account_owner)?;
+
+        if _stake_args.amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut stake_account = StakeAccount {
+            is_initialized: true,
+            stake_pool:     *account_stake_pool.key,
+            owner:          *account_owner.key,
+            ..stake_account
+        };
+
+        let now = Clock::get()?.unix_timestamp as u64;
+
+        stake_pool.update(now)?;
+        stake_account.settle(&stake_pool)?;
+
+        // stakes carry no interest reserve, positions are staked once their interest has accrued
+        user_savings.accrue_interest(&settings, now);
+
+        let paid                        = user_savings.split(_stake_args.amount)?;
+        stake_account.paid              += paid;
+        stake_account.amount            += _stake_args.amount;
+        stake_account.reward_debt       = stake_pool.reward_debt_of(stake_account.amount)?;
+        stake_pool.total_staked         += _stake_args.amount;
+
+        Savings::pack(user_savings, &mut account_from.data.borrow_mut())?;
+        StakeAccount::pack(stake_account, &mut account_stake.data.borrow_mut())?;
+        StakePool::pack(stake_pool, &mut account_stake_pool.data.borrow_mut())?;
+
+        emit!(Staked {
+            version:    EVENT_VERSION,
+            stake_pool: *account_stake_pool.key,
+            stake:      *account_stake.key,
+            savings:    *account_from.key,
+            amount:     _stake_args.amount,
+            paid,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  unstake
+    //--------------------------------------------------------------------------------------------------
+    fn unstake(_program_id: &Pubkey, _accounts: &[AccountInfo], _stake_args: &StakeArgs) -> ProgramResult {
+        let accounts                = Stake::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings        = &accounts.settings;
+        let account_stake_pool      = &accounts.stake_pool;
+        let account_stake           = &accounts.stake_account;
+        let account_from            = &accounts.savings;
+        let account_owner           = &accounts.owner;
+        let mut stake_pool          = Self::unpack_stake_pool(_program_id, account_settings, account_stake_pool)?;
+        let mut stake_account       = Self::unpack_stake_account(account_stake_pool, account_stake, account_owner)?;
+        // the owner may have closed the position meanwhile
+        let mut user_savings        = Self::unpack_reopened_savings(account_settings, account_from, account_owner)?;
+
+        if _stake_args.amount == 0 || stake_account.amount < _stake_args.amount {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        stake_pool.update(Clock::get()?.unix_timestamp as u64)?;
+        stake_account.settle(&stake_pool)?;
+
+        let paid                        = stake_account.split(_stake_args.amount)?;
+        user_savings.total_paid         += paid;
+        user_savings.total_technical    += _stake_args.amount;
+        stake_account.reward_debt       = stake_pool.reward_debt_of(stake_account.amount)?;
+        stake_pool.total_staked         -= _stake_args.amount;
+
+        Savings::pack(user_savings, &mut account_from.data.borrow_mut())?;
+        StakeAccount::pack(stake_account, &mut account_stake.data.borrow_mut())?;
+        StakePool::pack(stake_pool, &mut account_stake_pool.data.borrow_mut())?;
+
+        emit!(Unstaked {
+            version:    EVENT_VERSION,
+            stake_pool: *account_stake_pool.key,
+            stake:      *account_stake.key,
+            savings:    *account_from.key,
+            amount:     _stake_args.amount,
+            paid,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  claim_rewards
+    //--------------------------------------------------------------------------------------------------
+    fn claim_rewards(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts                = ClaimRewards::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings        = &accounts.settings;
+        let account_stake_pool      = &accounts.stake_pool;
+        let account_stake           = &accounts.stake_account;
+        let account_owner           = &accounts.owner;
+        let mut stake_pool          = Self::unpack_stake_pool(_program_id, account_settings, account_stake_pool)?;
+        let mut stake_account       = Self::unpack_stake_account(account_stake_pool, account_stake, account_owner)?;
+
+        stake_pool.update(Clock::get()?.unix_timestamp as u64)?;
+        stake_account.settle(&stake_pool)?;
+
+        // the stake pool account is the reward vault, whatever it can't cover stays pending
+        let rent_exempt = Rent::get()?.minimum_balance(account_stake_pool.data_len());
+        let available   = account_stake_pool.lamports().saturating_sub(rent_exempt);
+        let rewards     = stake_account.rewards_pending.min(available);
+
+        stake_account.rewards_pending -= rewards;
+
+        **account_stake_pool.try_borrow_mut_lamports()? -= rewards;
+        **account_owner.try_borrow_mut_lamports()?      += rewards;
+
+        StakeAccount::pack(stake_account, &mut account_stake.data.borrow_mut())?;
+        StakePool::pack(stake_pool, &mut account_stake_pool.data.borrow_mut())?;
+
+        emit!(RewardsClaimed {
+            version:    EVENT_VERSION,
+            stake_pool: *account_stake_pool.key,
+            stake:      *account_stake.key,
+            owner:      *account_owner.key,
+            amount:     rewards,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  place_bid
+    //--------------------------------------------------------------------------------------------------
+    fn place_bid(_program_id: &Pubkey, _accounts: &[AccountInfo], _bid_args: &BidArgs) -> ProgramResult {
+        let accounts            = PlaceBid::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_bid         = &accounts.bid;
+        let account_from        = &accounts.savings;
+        let account_owner       = &accounts.owner;
+        let mut settings        = Self::unpack_unpaused(account_settings)?;
+
+        if settings.batch_end == 0 || settings.batch_end <= (Clock::get()?.unix_timestamp as u64) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if _bid_args.amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if account_bid.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let bid = Bid::unpack_unchecked(&account_bid.data.borrow())?;
+
+        if bid.is_initialized() && (bid.settings != *account_settings.key || bid.owner != *account_owner.key) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // bids are paid from the Savings account the allocation will be credited to
+        let user_savings = Self::unpack_reopened_savings(account_settings, account_from, account_owner)?;
+
+        let account_from_lamports = account_from.lamports().checked_sub(_bid_args.amount).ok_or(ProgramError::InsufficientFunds)?;
+
+        **account_from.try_borrow_mut_lamports()?       = account_from_lamports;
+        **account_settings.try_borrow_mut_lamports()?   += _bid_args.amount;
+
+        settings.batch_bids += _bid_args.amount;
+        Settings::pack(settings, &mut account_settings.data.borrow_mut())?;
+
+        Savings::pack(user_savings, &mut account_from.data.borrow_mut())?;
+
+        Bid::pack(Bid {
+            is_initialized:     true,
+            settings:           *account_settings.key,
+            owner:              *account_owner.key,
+            amount:             bid.amount + _bid_args.amount,
+            ..bid
+        },
+        &mut account_bid.data.borrow_mut())?;
+
+        emit!(BidPlaced {
+            version:    EVENT_VERSION,
+            settings:   *account_settings.key,
+            bid:        *account_bid.key,
+            savings:    *account_from.key,
+            owner:      *account_owner.key,
+            amount:     _bid_args.amount,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  settle
+    //--------------------------------------------------------------------------------------------------
+    fn settle(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts            = Settle::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
         let mut settings        = Settings::unpack(&account_settings.data.borrow())?;
 
-        if (Clock::get()?.unix_timestamp as u64) < settings.unlock_timestamp {
-            return Err(ProgramError::InvalidInstructionData); 
+        if settings.batch_end == 0 || settings.batch_settled || (Clock::get()?.unix_timestamp as u64) < settings.batch_end {
+            return Err(ProgramError::InvalidInstructionData);
         }
 
-        if account_from.try_borrow_data()?.is_empty() || account_from.try_borrow_data()?.len() != Savings::LEN {
-            return Err(ProgramError::InvalidAccountData); 
+        settings.batch_demand   = settings.batch_demand_of(settings.batch_bids);
+        settings.batch_filled   = settings.batch_demand.min(settings.supply_total - settings.supply_locked);
+        settings.batch_settled  = true;
+        Settings::pack(settings, &mut account_settings.data.borrow_mut())?;
+
+        emit!(BatchSettled {
+            version:    EVENT_VERSION,
+            settings:   *account_settings.key,
+            demand:     settings.batch_demand,
+            filled:     settings.batch_filled,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  claim_allocation
+    //--------------------------------------------------------------------------------------------------
+    fn claim_allocation(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts            = PlaceBid::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_bid         = &accounts.bid;
+        let account_from        = &accounts.savings;
+        let account_owner       = &accounts.owner;
+        let mut settings        = Settings::unpack(&account_settings.data.borrow())?;
+        let mut bid             = Self::unpack_bid(_program_id, account_settings, account_bid, account_owner)?;
+        // the bidder may have closed the position before settlement
+        let mut user_savings    = Self::unpack_reopened_savings(account_settings, account_from, account_owner)?;
+
+        if !settings.batch_settled || bid.allocation_claimed {
+            return Err(ProgramError::InvalidInstructionData);
         }
 
-        if settings.supply_locked < _withdraw_args.amount {
+        let (custom_amount, used) = settings.batch_allocation(bid.amount);
+
+        // the filled part of the bid is now paid for the position, the rest stays held for its refund
+        settings.supply_locked  += custom_amount;
+        settings.total_raised   += used;
+        settings.batch_bids     = settings.batch_bids.checked_sub(used).ok_or(ProgramError::InvalidAccountData)?;
+        Settings::pack(settings, &mut account_settings.data.borrow_mut())?;
+
+        user_savings.total_technical    += custom_amount;
+        user_savings.total_paid         += used;
+        Savings::pack(user_savings, &mut account_from.data.borrow_mut())?;
+
+        bid.allocation_claimed = true;
+        Bid::pack(bid, &mut account_bid.data.borrow_mut())?;
+
+        emit!(AllocationClaimed {
+            version:    EVENT_VERSION,
+            settings:   *account_settings.key,
+            bid:        *account_bid.key,
+            savings:    *account_from.key,
+            custom_amount,
+            used,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  claim_refund
+    //--------------------------------------------------------------------------------------------------
+    fn claim_refund(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts            = PlaceBid::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_bid         = &accounts.bid;
+        let account_from        = &accounts.savings;
+        let account_owner       = &accounts.owner;
+        let mut settings        = Settings::unpack(&account_settings.data.borrow())?;
+        let mut bid             = Self::unpack_bid(_program_id, account_settings, account_bid, account_owner)?;
+        let user_savings        = Self::unpack_reopened_savings(account_settings, account_from, account_owner)?;
+
+        if !settings.batch_settled || bid.refund_claimed {
             return Err(ProgramError::InvalidInstructionData);
         }
 
-        settings.supply_locked  -= _withdraw_args.amount;
-        let data: &mut [u8]     = &mut account_settings.data.borrow_mut();
-        Settings::pack(settings, data)?;
+        // the unfilled part goes back to the account the bid was paid from
+        let (_, used)                   = settings.batch_allocation(bid.amount);
+        let refund                      = bid.amount - used;
+        let account_settings_lamports   = account_settings.lamports().checked_sub(refund).ok_or(ProgramError::InsufficientFunds)?;
 
-        let mut user_savings = Savings::unpack(&account_from.data.borrow())?;
+        settings.batch_bids = settings.batch_bids.checked_sub(refund).ok_or(ProgramError::InvalidAccountData)?;
+        Settings::pack(settings, &mut account_settings.data.borrow_mut())?;
+
+        **account_settings.try_borrow_mut_lamports()?   = account_settings_lamports;
+        **account_from.try_borrow_mut_lamports()?       += refund;
+
+        Savings::pack(user_savings, &mut account_from.data.borrow_mut())?;
+
+        bid.refund_claimed = true;
+        Bid::pack(bid, &mut account_bid.data.borrow_mut())?;
+
+        emit!(BidRefunded {
+            version:    EVENT_VERSION,
+            settings:   *account_settings.key,
+            bid:        *account_bid.key,
+            savings:    *account_from.key,
+            amount:     refund,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  close_savings
+    //--------------------------------------------------------------------------------------------------
+    fn close_savings(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts            = CloseSavings::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_from        = &accounts.savings;
+        let account_owner       = &accounts.owner;
+        let user_savings        = Savings::unpack(&account_from.data.borrow())?;
+
+        if user_savings.owner != *account_owner.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if user_savings.total_technical != 0 || user_savings.interest_reserved != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-        if user_savings.total_technical < _withdraw_args.amount {
+        emit!(SavingsClosed {
+            version:    EVENT_VERSION,
+            settings:   user_savings.settings,
+            savings:    *account_from.key,
+            owner:      *account_owner.key,
+        });
+
+        Self::close_account(account_from, account_owner)
+    }
+    //==================================================================================================
+    //  close_pool
+    //--------------------------------------------------------------------------------------------------
+    fn close_pool(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts            = ClosePool::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_owner       = &accounts.locked_token_owner;
+        let settings            = Settings::unpack(&account_settings.data.borrow())?;
+
+        if settings.locked_token_owner != *account_owner.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if (Clock::get()?.unix_timestamp as u64) < settings.unlock_timestamp || settings.supply_locked != 0 {
             return Err(ProgramError::InvalidInstructionData);
         }
 
-        user_savings.total_technical    -= _withdraw_args.amount;
-        user_savings.total_original     += _withdraw_args.amount;
+        // only the rent goes back to the locked token owner, revenue is collected first
+        if Rent::get()?.minimum_balance(account_settings.data_len()) < account_settings.lamports() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        emit!(PoolClosed {
+            version:            EVENT_VERSION,
+            settings:           *account_settings.key,
+            locked_token_owner: *account_owner.key,
+        });
+
+        Self::close_account(account_settings, account_owner)
+    }
+    //==================================================================================================
+    //  migrate, solana-program 1.6 cannot realloc: the latest layout is written to a new account the
+    //  client creates with `Pack::LEN` bytes in the same transaction, the legacy account is closed
+    //  into it. The pool or position changes address, clients have to switch to the migrated one
+    //--------------------------------------------------------------------------------------------------
+    fn migrate(_program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+        // the size of the legacy account tells which accounts follow it
+        let legacy_len = _accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?.data_len();
+
+        let (account_legacy, account_migrated) = match legacy_len {
+            Settings::LEN_V0 => {
+                let accounts        = MigrateSettings::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+                let account_owner   = &accounts.locked_token_owner;
+
+                Self::check_migrated(_program_id, &accounts.legacy_settings, &accounts.settings)?;
+
+                let settings = Settings::unpack_any_version(&accounts.legacy_settings.data.borrow())?;
+
+                if !settings.is_initialized() || settings.locked_token_owner != *account_owner.key {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+
+                // the upgraded pool has to be one `Initialize` could have created
+                settings.validate().map_err(|_| ProgramError::InvalidAccountData)?;
+
+                if Settings::unpack_unchecked(&accounts.settings.data.borrow())?.is_initialized() {
+                    return Err(ProgramError::AccountAlreadyInitialized);
+                }
+
+                Settings::pack(settings, &mut accounts.settings.data.borrow_mut())?;
+                (accounts.legacy_settings, accounts.settings)
+            }
+            Savings::LEN_V0 => {
+                // legacy Savings record neither a wallet nor their pool: holding the Savings key is the
+                // only proof of ownership, and the wallet signing along owns the migrated Savings, so
+                // Savings at program derived addresses can't migrate. The pool's locked token owner
+                // vouches for the pool
+                let accounts                    = MigrateSavings::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+                let account_owner               = &accounts.owner;
+                let account_settings            = &accounts.settings;
+                let account_locked_token_owner  = &accounts.locked_token_owner;
+
+                Self::check_migrated(_program_id, &accounts.legacy_savings, &accounts.savings)?;
+
+                let user_savings = Savings::unpack_any_version(&accounts.legacy_savings.data.borrow())?;
+
+                if account_settings.owner != _program_id {
+                    return Err(ProgramError::IncorrectProgramId);
+                }
+
+                if Settings::unpack(&account_settings.data.borrow())?.locked_token_owner != *account_locked_token_owner.key {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+
+                if !user_savings.is_initialized() {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+
+                if Savings::unpack_unchecked(&accounts.savings.data.borrow())?.is_initialized() {
+                    return Err(ProgramError::AccountAlreadyInitialized);
+                }
 
-        let account_from_data: &mut [u8] = &mut account_from.data.borrow_mut();
-        Savings::pack(user_savings, account_from_data)?;
+                Savings::pack(Savings {
+                    owner:      *account_owner.key,
+                    settings:   *account_settings.key,
+                    ..user_savings
+                },
+                &mut accounts.savings.data.borrow_mut())?;
+                (accounts.legacy_savings, accounts.savings)
+            }
+            _ => {
+                let account_legacy      = &_accounts[0];
+                let account_migrated    = _accounts.get(1).ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+                Self::check_migrated(_program_id, account_legacy, account_migrated)?;
+                return Err(ProgramError::InvalidAccountData);
+            }
+        };
+
+        emit!(Migrated {
+            version:    EVENT_VERSION,
+            legacy:     *account_legacy.key,
+            migrated:   *account_migrated.key,
+        });
+
+        Self::close_account(&account_legacy, &account_migrated)
+    }
+    //==================================================================================================
+    //  check_migrated, both sides of a Migrate are program accounts
+    //--------------------------------------------------------------------------------------------------
+    fn check_migrated(_program_id: &Pubkey, _account_legacy: &AccountInfo, _account_migrated: &AccountInfo) -> ProgramResult {
+        if _account_legacy.owner != _program_id || _account_migrated.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
 
         Ok(())
     }
     //==================================================================================================
+    //  update_settings
+    //--------------------------------------------------------------------------------------------------
+    fn update_settings(_program_id: &Pubkey, _accounts: &[AccountInfo], _update_args: &UpdateSettingsArgs) -> ProgramResult {
+        let accounts            = UpdateSettings::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_owner       = &accounts.locked_token_owner;
+        let settings            = Self::unpack_owned_settings(_program_id, account_settings, account_owner)?;
+
+        Self::pack_updated_settings(account_settings, settings.update(_update_args)?)
+    }
+    //==================================================================================================
+    //  pause, swaps, bids, orders, transfers and stakes stop, every way out stays open
+    //--------------------------------------------------------------------------------------------------
+    fn pause(_program_id: &Pubkey, _accounts: &[AccountInfo], _pause_args: &PauseArgs) -> ProgramResult {
+        let accounts            = UpdateSettings::try_accounts(_program_id, &mut &_accounts[..], &[])?;
+        let account_settings    = &accounts.settings;
+        let account_owner       = &accounts.locked_token_owner;
+        let settings            = Self::unpack_owned_settings(_program_id, account_settings, account_owner)?;
+
+        Self::pack_updated_settings(account_settings, Settings { paused: _pause_args.paused, ..settings })
+    }
+    //==================================================================================================
+    //  unpack_owned_settings, the pool's Settings for an instruction its locked token owner signed
+    //--------------------------------------------------------------------------------------------------
+    fn unpack_owned_settings(_program_id: &Pubkey, _account_settings: &AccountInfo, _account_owner: &AccountInfo) -> std::result::Result<Settings, ProgramError> {
+        if _account_settings.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let settings = Settings::unpack(&_account_settings.data.borrow())?;
+
+        if settings.locked_token_owner != *_account_owner.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(settings)
+    }
+    //==================================================================================================
+    //  pack_updated_settings
+    //--------------------------------------------------------------------------------------------------
+    fn pack_updated_settings(_account_settings: &AccountInfo, _settings: Settings) -> ProgramResult {
+        Settings::pack(_settings, &mut _account_settings.data.borrow_mut())?;
+
+        emit!(SettingsUpdated {
+            version:            EVENT_VERSION,
+            settings:           *_account_settings.key,
+            revenue_owner:      _settings.revenue_owner,
+            locked_token_owner: _settings.locked_token_owner,
+            price:              _settings.token0.price,
+            paused:             _settings.paused,
+        });
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  close_account
+    //--------------------------------------------------------------------------------------------------
+    fn close_account(_account: &AccountInfo, _account_destination: &AccountInfo) -> ProgramResult {
+        let account_lamports                                = _account.lamports();
+        **_account.try_borrow_mut_lamports()?               = 0;
+        **_account_destination.try_borrow_mut_lamports()?   += account_lamports;
+
+        for byte in _account.try_borrow_mut_data()?.iter_mut() {
+            *byte = 0;
+        }
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  unpack_unpaused, the pool's Settings for the instructions a paused pool rejects
+    //--------------------------------------------------------------------------------------------------
+    fn unpack_unpaused(_account_settings: &AccountInfo) -> std::result::Result<Settings, ProgramError> {
+        let settings = Settings::unpack(&_account_settings.data.borrow())?;
+
+        if settings.paused {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(settings)
+    }
+    //==================================================================================================
+    //  unpack_savings
+    //--------------------------------------------------------------------------------------------------
+    fn unpack_savings(_account_settings: &AccountInfo, _account_savings: &AccountInfo, _account_owner: &AccountInfo) -> std::result::Result<Savings, ProgramError> {
+        let user_savings = Savings::unpack(&_account_savings.data.borrow())?;
+
+        if user_savings.owner != *_account_owner.key || user_savings.settings != *_account_settings.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(user_savings)
+    }
+    //==================================================================================================
+    //  unpack_reopened_savings, like `unpack_savings` but an empty account, new or closed and created
+    //  again, is opened for `_account_owner`
+    //--------------------------------------------------------------------------------------------------
+    fn unpack_reopened_savings(_account_settings: &AccountInfo, _account_savings: &AccountInfo, _account_owner: &AccountInfo) -> std::result::Result<Savings, ProgramError> {
+        let user_savings = Savings::unpack_unchecked(&_account_savings.data.borrow())?;
+
+        if user_savings.is_initialized() && (user_savings.owner != *_account_owner.key || user_savings.settings != *_account_settings.key) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Savings {
+            is_initialized: true,
+            owner:          *_account_owner.key,
+            settings:       *_account_settings.key,
+            ..user_savings
+        })
+    }
+    //==================================================================================================
+    //  unpack_bid
+    //--------------------------------------------------------------------------------------------------
+    fn unpack_bid(_program_id: &Pubkey, _account_settings: &AccountInfo, _account_bid: &AccountInfo, _account_owner: &AccountInfo) -> std::result::Result<Bid, ProgramError> {
+        if _account_bid.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let bid = Bid::unpack(&_account_bid.data.borrow())?;
+
+        if bid.settings != *_account_settings.key || bid.owner != *_account_owner.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(bid)
+    }
+    //==================================================================================================
+    //  unpack_stake_pool
+    //--------------------------------------------------------------------------------------------------
+    fn unpack_stake_pool(_program_id: &Pubkey, _account_settings: &AccountInfo, _account_stake_pool: &AccountInfo) -> std::result::Result<StakePool, ProgramError> {
+        if _account_stake_pool.owner != _program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let stake_pool = StakePool::unpack(&_account_stake_pool.data.borrow())?;
+
+        if stake_pool.settings != *_account_settings.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(stake_pool)
+    }
+    //==================================================================================================
+    //  unpack_stake_account
+    //--------------------------------------------------------------------------------------------------
+    fn unpack_stake_account(_account_stake_pool: &AccountInfo, _account_stake: &AccountInfo, _account_owner: &AccountInfo) -> std::result::Result<StakeAccount, ProgramError> {
+        let stake_account = StakeAccount::unpack(&_account_stake.data.borrow())?;
+
+        if stake_account.stake_pool != *_account_stake_pool.key || stake_account.owner != *_account_owner.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(stake_account)
+    }
+    //==================================================================================================
     //  process_instruction
     //--------------------------------------------------------------------------------------------------
     pub fn process_instruction(
@@ -143,11 +1345,32 @@ impl Processor {
         _accounts:          &[AccountInfo],
         _instruction_data:  &[u8]
     ) -> ProgramResult {
-        let instruction = KaizenInstruction::try_from_slice(_instruction_data)?;
+        let instruction = KaizenInstruction::unpack(_instruction_data)?;
         match instruction {
-            KaizenInstruction::Initialize(settings)     => Self::initialize(_accounts, &settings),
-            KaizenInstruction::Swap(swap_args)          => Self::swap(_accounts, &swap_args),
-            KaizenInstruction::Withdraw(withdraw_args)  => Self::withdraw(_accounts, &withdraw_args),
+            KaizenInstruction::Initialize(settings)     => Self::initialize(_program_id, _accounts, &settings),
+            KaizenInstruction::Swap(swap_args)          => Self::swap(_program_id, _accounts, &swap_args),
+            KaizenInstruction::Withdraw(withdraw_args)  => Self::withdraw(_program_id, _accounts, &withdraw_args),
+            KaizenInstruction::EarlyWithdraw(args)      => Self::early_withdraw(_program_id, _accounts, &args),
+            KaizenInstruction::InitializeStakePool(args)=> Self::initialize_stake_pool(_program_id, _accounts, &args),
+            KaizenInstruction::Stake(stake_args)        => Self::stake(_program_id, _accounts, &stake_args),
+            KaizenInstruction::Unstake(stake_args)      => Self::unstake(_program_id, _accounts, &stake_args),
+            KaizenInstruction::ClaimRewards             => Self::claim_rewards(_program_id, _accounts),
+            KaizenInstruction::PlaceBid(bid_args)       => Self::place_bid(_program_id, _accounts, &bid_args),
+            KaizenInstruction::Settle                   => Self::settle(_program_id, _accounts),
+            KaizenInstruction::ClaimAllocation          => Self::claim_allocation(_program_id, _accounts),
+            KaizenInstruction::ClaimRefund              => Self::claim_refund(_program_id, _accounts),
+            KaizenInstruction::RegisterReferrer         => Self::register_referrer(_program_id, _accounts),
+            KaizenInstruction::PlaceOrder(order_args)   => Self::place_order(_program_id, _accounts, &order_args),
+            KaizenInstruction::FillOrder(fill_args)     => Self::fill_order(_program_id, _accounts, &fill_args),
+            KaizenInstruction::CancelOrder              => Self::cancel_order(_program_id, _accounts),
+            KaizenInstruction::TransferSavings(args)    => Self::transfer_savings(_program_id, _accounts, &args),
+            KaizenInstruction::CloseSavings             => Self::close_savings(_program_id, _accounts),
+            KaizenInstruction::ClosePool                => Self::close_pool(_program_id, _accounts),
+            KaizenInstruction::Refund                   => Self::refund(_program_id, _accounts),
+            KaizenInstruction::CollectRevenue           => Self::collect_revenue(_program_id, _accounts),
+            KaizenInstruction::Migrate                  => Self::migrate(_program_id, _accounts),
+            KaizenInstruction::UpdateSettings(args)     => Self::update_settings(_program_id, _accounts, &args),
+            KaizenInstruction::Pause(pause_args)        => Self::pause(_program_id, _accounts, &pause_args),
         }
     }
 
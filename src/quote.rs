@@ -0,0 +1,146 @@
+use solana_program::program_error::ProgramError;
+use crate::state::{
+    Savings,
+    Settings,
+};
+use std::fmt;
+
+//**************************************************************************************************
+//  SwapQuote, what a swap of `amount` token0 would lock for a new position
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SwapQuote {
+    pub amount:             u64,
+    pub price:              u64,
+    pub custom_amount:      u64,
+    pub interest_reserved:  u64,
+    pub receive:            u64,
+    pub unlock_timestamp:   u64,
+}
+
+//**************************************************************************************************
+//  WithdrawQuote, what withdrawing a whole position would pay out
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WithdrawQuote {
+    pub amount:             u64,
+    pub penalty_share:      u64,
+    pub unlock_timestamp:   u64,
+}
+
+//**************************************************************************************************
+//  QuoteError, why the processor would reject the quoted instruction. It reports most of these as
+//  `InvalidInstructionData`, the conversion to `ProgramError` gives back what it would return
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuoteError {
+    NotInitialized,
+    Paused,
+    SaleClosed,
+    BatchAuction,
+    Oversubscribed,
+    Locked { unlock_timestamp: u64 },
+    SaleFailed,
+    Rejected(ProgramError),
+}
+
+//==================================================================================================
+//  quote_swap, `_amount` of token0 swapped at `_now`
+//--------------------------------------------------------------------------------------------------
+pub fn quote_swap(_settings: &Settings, _amount: u64, _now: u64) -> Result<SwapQuote, QuoteError> {
+    if !_settings.is_initialized {
+        return Err(QuoteError::NotInitialized);
+    }
+
+    if _settings.paused {
+        return Err(QuoteError::Paused);
+    }
+
+    if _settings.unlock_timestamp <= _now || _settings.is_sale_over(_now) {
+        return Err(QuoteError::SaleClosed);
+    }
+
+    if _settings.batch_end != 0 {
+        return Err(QuoteError::BatchAuction);
+    }
+
+    // past the checks above `swap` fails on the supply left or on amounts past u64, the same swap
+    // without a supply limit tells them apart
+    let mut settings    = *_settings;
+    let mut savings     = Savings::default();
+    let swap_amounts    = settings.swap(&mut savings, _amount, _now, false).map_err(|error| {
+        let mut unlimited = Settings { supply_total: u64::MAX, ..*_settings };
+
+        match unlimited.swap(&mut Savings::default(), _amount, _now, false) {
+            Ok(_)   => QuoteError::Oversubscribed,
+            Err(_)  => QuoteError::Rejected(error),
+        }
+    })?;
+
+    Ok(SwapQuote {
+        amount:             _amount,
+        price:              _settings.price(_now),
+        custom_amount:      swap_amounts.custom_amount,
+        interest_reserved:  swap_amounts.interest_reserved,
+        receive:            swap_amounts.custom_amount + swap_amounts.interest_reserved,
+        unlock_timestamp:   _settings.unlock_timestamp,
+    })
+}
+
+//==================================================================================================
+//  quote_withdraw, all of `_savings` withdrawn at `_now`, interest accrued until then included
+//--------------------------------------------------------------------------------------------------
+pub fn quote_withdraw(_settings: &Settings, _savings: &Savings, _now: u64) -> Result<WithdrawQuote, QuoteError> {
+    if !_settings.is_initialized || !_savings.is_initialized {
+        return Err(QuoteError::NotInitialized);
+    }
+
+    if _settings.is_sale_failed(_now) {
+        return Err(QuoteError::SaleFailed);
+    }
+
+    if _now < _settings.unlock_timestamp {
+        return Err(QuoteError::Locked { unlock_timestamp: _settings.unlock_timestamp });
+    }
+
+    let mut settings    = *_settings;
+    let mut savings     = *_savings;
+    savings.accrue_interest(_settings, _now);
+
+    let amount          = savings.total_technical;
+    let penalty_share   = settings.withdraw(&mut savings, amount, _now).map_err(QuoteError::Rejected)?;
+
+    Ok(WithdrawQuote {
+        amount,
+        penalty_share,
+        unlock_timestamp:   _settings.unlock_timestamp,
+    })
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QuoteError::NotInitialized                      => write!(f, "account is not initialized"),
+            QuoteError::Paused                              => write!(f, "pool is paused"),
+            QuoteError::SaleClosed                          => write!(f, "sale is closed"),
+            QuoteError::BatchAuction                        => write!(f, "pool only sells through batch auction bids"),
+            QuoteError::Oversubscribed                      => write!(f, "not enough supply left"),
+            QuoteError::Locked { unlock_timestamp }         => write!(f, "locked until {}", unlock_timestamp),
+            QuoteError::SaleFailed                          => write!(f, "sale failed, contributions are refundable"),
+            QuoteError::Rejected(error)                     => write!(f, "{}", error),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl From<QuoteError> for ProgramError {
+    fn from(_error: QuoteError) -> Self {
+        match _error {
+            QuoteError::NotInitialized  => ProgramError::UninitializedAccount,
+            QuoteError::Rejected(error) => error,
+            _                           => ProgramError::InvalidInstructionData,
+        }
+    }
+}
\ No newline at end of file
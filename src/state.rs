@@ -8,31 +8,116 @@ use solana_program::{
     program_error::ProgramError,
 };
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
-use std::convert::TryInto;
+use std::convert::TryFrom;
 use std::fmt;
 
+//**************************************************************************************************
+//  AccountType, the first byte of every state account. Zeroed accounts, created but not yet
+//  initialized, carry no type
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum AccountType {
+    Uninitialized   = 0,
+    Settings        = 1,
+    Savings         = 2,
+    Order           = 3,
+    Bid             = 4,
+    Referrer        = 5,
+    StakePool       = 6,
+    StakeAccount    = 7,
+}
+
 //**************************************************************************************************
 //  Settings
 //--------------------------------------------------------------------------------------------------
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
 pub struct Settings {
     pub is_initialized:         bool,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
     pub revenue_owner:          Pubkey,
     pub interest_basis_points:  u32,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
     pub locked_token:           Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
     pub locked_token_owner:     Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
     pub unlock_timestamp:       u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
     pub supply_total:           u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
     pub supply_locked:          u64,
     pub token0:                 Token,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub soft_cap:               u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub sale_end:               u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub total_raised:           u64,
+    pub penalty_basis_points:   u32,
+    pub penalty_to_holders:     bool,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub penalty_pool:           u64,
+    pub interest_apr:           bool,
+    pub tiers:                  [Tier; 4],
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub auction_start_price:    u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub auction_floor_price:    u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub auction_start:          u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub auction_end:            u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub batch_end:              u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub batch_bids:             u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub batch_demand:           u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub batch_filled:           u64,
+    pub batch_settled:          bool,
+    pub referral_basis_points:  u32,
+    pub referral_bonus:         bool,
+    pub paused:                 bool,
 }
 
 //**************************************************************************************************
 //  Token
 //--------------------------------------------------------------------------------------------------
 #[derive(Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
 pub struct Token {
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
     pub address:    Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub price:      u64,
+}
+
+//**************************************************************************************************
+//  SwapAmounts, locked tokens a swap credits and the token0 it owes a referrer
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct SwapAmounts {
+    pub custom_amount:      u64,
+    pub interest_reserved:  u64,
+    pub referral_bonus:     u64,
+    pub referral_payment:   u64,
+}
+
+//**************************************************************************************************
+//  Tier
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Tier {
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub supply_end: u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
     pub price:      u64,
 }
 
@@ -40,6 +125,7 @@ pub struct Token {
 //  SwapArgs
 //--------------------------------------------------------------------------------------------------
 #[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
 pub struct SwapArgs {
     pub amount: u64,
 }
@@ -48,97 +134,219 @@ pub struct SwapArgs {
 //  WithdrawArgs
 //--------------------------------------------------------------------------------------------------
 #[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
 pub struct WithdrawArgs {
     pub amount: u64,
 }
 
 //**************************************************************************************************
-//  Savings
+//  EarlyWithdrawArgs
 //--------------------------------------------------------------------------------------------------
 #[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct EarlyWithdrawArgs {
+    pub amount: u64,
+}
+
+//**************************************************************************************************
+//  Savings
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
 pub struct Savings {
     pub is_initialized:     bool,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
     pub total_technical:    u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
     pub total_original:     u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub total_paid:         u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub interest_reserved:  u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub accrual_timestamp:  u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub owner:              Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub settings:           Pubkey,
 }
 
-////////////////////////////////////////////////////////////////////////////////////////////////////
-////////////////////////////////////////////////////////////////////////////////////////////////////
-impl IsInitialized for Settings {
+//**************************************************************************************************
+//  PlaceOrderArgs
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct PlaceOrderArgs {
+    pub amount:             u64,
+    pub price:              u64,
+    pub expiry_timestamp:   u64,
+}
 
-    //==================================================================================================
-    fn is_initialized(&self) -> bool {
-        self.is_initialized
-    }
+//**************************************************************************************************
+//  FillOrderArgs
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct FillOrderArgs {
+    pub amount: u64,
+}
+
+//**************************************************************************************************
+//  TransferSavingsArgs
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct TransferSavingsArgs {
+    pub amount: u64,
+}
+
+//**************************************************************************************************
+//  StakePoolArgs
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct StakePoolArgs {
+    pub reward_rate:    u64,
+    pub reward_end:     u64,
+}
+
+//**************************************************************************************************
+//  StakeArgs
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct StakeArgs {
+    pub amount: u64,
+}
+
+//**************************************************************************************************
+//  BidArgs
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct BidArgs {
+    pub amount: u64,
+}
+
+//**************************************************************************************************
+//  UpdateSettingsArgs, the owners and the token0 price of a pool
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct UpdateSettingsArgs {
+    pub revenue_owner:      Pubkey,
+    pub locked_token_owner: Pubkey,
+    pub price:              u64,
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
-impl BorshDeserialize for Settings {
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl BorshDeserialize for UpdateSettingsArgs {
 
     //==================================================================================================
+    #[inline]
     fn deserialize(_buf: &mut &[u8]) -> std::io::Result<Self> {
-        let input = array_ref![_buf, 0, 165];
-        let (
-            is_initialized_b,
-            revenue_owner_b,
-            interest_basis_points_b,
-            locked_token_b,
-            locked_token_owner_b,
-            unlock_timestamp_b,
-            supply_total_b,
-            supply_locked_b,
-            tokens_b
-        ) = array_refs![input, 1, 32, 4, 32, 32, 8, 8, 8, 40];
-
-        let settings = Settings {
-            is_initialized: match is_initialized_b {
-                [0] => false,
-                _ => true,
-            },
-            revenue_owner:          Pubkey::new_from_array(*revenue_owner_b),
-            interest_basis_points:  u32::from_le_bytes(*interest_basis_points_b),
-            locked_token:           Pubkey::new_from_array(*locked_token_b),
-            locked_token_owner:     Pubkey::new_from_array(*locked_token_owner_b),
-            unlock_timestamp:       u64::from_le_bytes(*unlock_timestamp_b),
-            supply_total:           u64::from_le_bytes(*supply_total_b),
-            supply_locked:          u64::from_le_bytes(*supply_locked_b),
-            token0:                 Token::try_from_slice(tokens_b[0..40].try_into().expect("slice with incorrect length"))?
-        };
-
-        *_buf = &_buf[165..];
+        let revenue_owner       = <[u8; 32]>::deserialize(_buf)?;
+        let locked_token_owner  = <[u8; 32]>::deserialize(_buf)?;
+        let price               = u64::deserialize(_buf)?;
 
-        Ok(settings)
+        Ok(UpdateSettingsArgs {
+            revenue_owner:      Pubkey::new_from_array(revenue_owner),
+            locked_token_owner: Pubkey::new_from_array(locked_token_owner),
+            price,
+        })
     }
 }
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
-impl BorshSerialize for Settings {
+impl BorshSerialize for UpdateSettingsArgs {
 
     //==================================================================================================
     #[inline]
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        (self.is_initialized as u8).serialize(writer)?;
         self.revenue_owner.to_bytes().serialize(writer)?;
-        self.interest_basis_points.serialize(writer)?;
-        self.locked_token.to_bytes().serialize(writer)?;
         self.locked_token_owner.to_bytes().serialize(writer)?;
-        self.unlock_timestamp.serialize(writer)?;
-        self.supply_total.serialize(writer)?;
-        self.supply_locked.serialize(writer)?;
-        self.token0.serialize(writer)?;
-        Ok(())
+        self.price.serialize(writer)
     }
 }
 
+//**************************************************************************************************
+//  PauseArgs
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, BorshSerialize, BorshDeserialize, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct PauseArgs {
+    pub paused: bool,
+}
+
+//**************************************************************************************************
+//  Order
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Order {
+    pub is_initialized:     bool,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub settings:           Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub seller:             Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub amount:             u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub paid:               u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub price:              u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub expiry_timestamp:   u64,
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
-impl Sealed for Settings {}
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl AccountType {
+
+    //==================================================================================================
+    //  of, the type tag of account data, `None` for empty data or an unknown tag
+    //--------------------------------------------------------------------------------------------------
+    pub fn of(_data: &[u8]) -> Option<Self> {
+        match _data.first()? {
+            0 => Some(Self::Uninitialized),
+            1 => Some(Self::Settings),
+            2 => Some(Self::Savings),
+            3 => Some(Self::Order),
+            4 => Some(Self::Bid),
+            5 => Some(Self::Referrer),
+            6 => Some(Self::StakePool),
+            7 => Some(Self::StakeAccount),
+            _ => None,
+        }
+    }
+    //==================================================================================================
+    //  check, `_tag` and `_is_initialized` are the first two bytes of an account of type `self`
+    //--------------------------------------------------------------------------------------------------
+    fn check(self, _tag: u8, _is_initialized: u8) -> Result<(), ProgramError> {
+        if _tag == self as u8 || (_tag == Self::Uninitialized as u8 && _is_initialized == 0) {
+            Ok(())
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
-impl Pack for Settings {
-    const LEN: usize = 165;
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Settings {
+    pub const SECONDS_PER_YEAR: u64     = 31_536_000;
+    pub const VERSION:          u8      = 1;
+    pub const LEN_V0:           usize   = 165;
+    pub const BODY_LEN:         usize   = 338;
 
     //==================================================================================================
-    fn pack_into_slice(&self, output: &mut [u8]) {
-        let output = array_mut_ref![output, 0, 165];
+    //  pack_body, the one layout of the fields: Borsh encodes it as is, `Pack` after the account type
+    //  and version bytes
+    //--------------------------------------------------------------------------------------------------
+    fn pack_body(&self, output: &mut [u8; 338]) {
         let (
             is_initialized_b,
             revenue_owner_b,
@@ -149,7 +357,27 @@ impl Pack for Settings {
             supply_total_b,
             supply_locked_b,
             tokens_b0,
-        ) = mut_array_refs![output, 1, 32, 4, 32, 32, 8, 8, 8, 40];
+            soft_cap_b,
+            sale_end_b,
+            total_raised_b,
+            penalty_basis_points_b,
+            penalty_to_holders_b,
+            penalty_pool_b,
+            interest_apr_b,
+            tiers_b,
+            auction_start_price_b,
+            auction_floor_price_b,
+            auction_start_b,
+            auction_end_b,
+            batch_end_b,
+            batch_bids_b,
+            batch_demand_b,
+            batch_filled_b,
+            batch_settled_b,
+            referral_basis_points_b,
+            referral_bonus_b,
+            paused_b,
+        ) = mut_array_refs![output, 1, 32, 4, 32, 32, 8, 8, 8, 40, 8, 8, 8, 4, 1, 8, 1, 64, 8, 8, 8, 8, 8, 8, 8, 8, 1, 4, 1, 1];
 
         is_initialized_b[0]         = self.is_initialized as u8;
         revenue_owner_b             .copy_from_slice(self.revenue_owner.as_ref());
@@ -159,11 +387,34 @@ impl Pack for Settings {
         *unlock_timestamp_b         = self.unlock_timestamp.to_le_bytes();
         *supply_total_b             = self.supply_total.to_le_bytes();
         *supply_locked_b            = self.supply_locked.to_le_bytes();
-        Token::pack(self.token0, &mut *tokens_b0).expect("slice with incorrect length");
+        self.token0                 .pack_into_slice(tokens_b0);
+        *soft_cap_b                 = self.soft_cap.to_le_bytes();
+        *sale_end_b                 = self.sale_end.to_le_bytes();
+        *total_raised_b             = self.total_raised.to_le_bytes();
+        *penalty_basis_points_b     = self.penalty_basis_points.to_le_bytes();
+        penalty_to_holders_b[0]     = self.penalty_to_holders as u8;
+        *penalty_pool_b             = self.penalty_pool.to_le_bytes();
+        interest_apr_b[0]           = self.interest_apr as u8;
+        for (tier, tier_b) in self.tiers.iter().zip(tiers_b.chunks_mut(Tier::LEN)) {
+            tier.pack_into_slice(tier_b);
+        }
+        *auction_start_price_b      = self.auction_start_price.to_le_bytes();
+        *auction_floor_price_b      = self.auction_floor_price.to_le_bytes();
+        *auction_start_b            = self.auction_start.to_le_bytes();
+        *auction_end_b              = self.auction_end.to_le_bytes();
+        *batch_end_b                = self.batch_end.to_le_bytes();
+        *batch_bids_b               = self.batch_bids.to_le_bytes();
+        *batch_demand_b             = self.batch_demand.to_le_bytes();
+        *batch_filled_b             = self.batch_filled.to_le_bytes();
+        batch_settled_b[0]          = self.batch_settled as u8;
+        *referral_basis_points_b    = self.referral_basis_points.to_le_bytes();
+        referral_bonus_b[0]         = self.referral_bonus as u8;
+        paused_b[0]                 = self.paused as u8;
     }
     //==================================================================================================
-    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
-        let input = array_ref![input, 0, 165];
+    //  unpack_body, booleans other than 0 or 1 are rejected on both paths
+    //--------------------------------------------------------------------------------------------------
+    fn unpack_body(input: &[u8; 338]) -> Result<Self, ProgramError> {
         let (
             is_initialized_b,
             revenue_owner_b,
@@ -173,8 +424,28 @@ impl Pack for Settings {
             unlock_timestamp_b,
             supply_total_b,
             supply_locked_b,
-            tokens_b
-        ) = array_refs![input, 1, 32, 4, 32, 32, 8, 8, 8, 40];
+            tokens_b,
+            soft_cap_b,
+            sale_end_b,
+            total_raised_b,
+            penalty_basis_points_b,
+            penalty_to_holders_b,
+            penalty_pool_b,
+            interest_apr_b,
+            tiers_b,
+            auction_start_price_b,
+            auction_floor_price_b,
+            auction_start_b,
+            auction_end_b,
+            batch_end_b,
+            batch_bids_b,
+            batch_demand_b,
+            batch_filled_b,
+            batch_settled_b,
+            referral_basis_points_b,
+            referral_bonus_b,
+            paused_b,
+        ) = array_refs![input, 1, 32, 4, 32, 32, 8, 8, 8, 40, 8, 8, 8, 4, 1, 8, 1, 64, 8, 8, 8, 8, 8, 8, 8, 8, 1, 4, 1, 1];
 
         Ok(Self {
             is_initialized: match is_initialized_b {
@@ -189,126 +460,1433 @@ impl Pack for Settings {
             unlock_timestamp:       u64::from_le_bytes(*unlock_timestamp_b),
             supply_total:           u64::from_le_bytes(*supply_total_b),
             supply_locked:          u64::from_le_bytes(*supply_locked_b),
-            token0:                 Token::unpack_unchecked(tokens_b[0..40].try_into().expect("slice with incorrect length"))?,
+            token0:                 Token::unpack_unchecked(tokens_b)?,
+            soft_cap:               u64::from_le_bytes(*soft_cap_b),
+            sale_end:               u64::from_le_bytes(*sale_end_b),
+            total_raised:           u64::from_le_bytes(*total_raised_b),
+            penalty_basis_points:   u32::from_le_bytes(*penalty_basis_points_b),
+            penalty_to_holders: match penalty_to_holders_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            penalty_pool:           u64::from_le_bytes(*penalty_pool_b),
+            interest_apr: match interest_apr_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            tiers: [
+                Tier::unpack_unchecked(&tiers_b[0..16])?,
+                Tier::unpack_unchecked(&tiers_b[16..32])?,
+                Tier::unpack_unchecked(&tiers_b[32..48])?,
+                Tier::unpack_unchecked(&tiers_b[48..64])?,
+            ],
+            auction_start_price:    u64::from_le_bytes(*auction_start_price_b),
+            auction_floor_price:    u64::from_le_bytes(*auction_floor_price_b),
+            auction_start:          u64::from_le_bytes(*auction_start_b),
+            auction_end:            u64::from_le_bytes(*auction_end_b),
+            batch_end:              u64::from_le_bytes(*batch_end_b),
+            batch_bids:             u64::from_le_bytes(*batch_bids_b),
+            batch_demand:           u64::from_le_bytes(*batch_demand_b),
+            batch_filled:           u64::from_le_bytes(*batch_filled_b),
+            batch_settled: match batch_settled_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            referral_basis_points:  u32::from_le_bytes(*referral_basis_points_b),
+            referral_bonus: match referral_bonus_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            paused: match paused_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
         })
     }
-}
-
-////////////////////////////////////////////////////////////////////////////////////////////////////
-////////////////////////////////////////////////////////////////////////////////////////////////////
-impl BorshDeserialize for Token {
 
     //==================================================================================================
-    #[inline]
-    fn deserialize(_buf: &mut &[u8]) -> std::io::Result<Self> {
-        let input               = array_ref![_buf, 0, 40];
-        let (pubkey_b, price_b) = array_refs![input, 32, 8];
-        let token = Token {
-            address:    Pubkey::new_from_array(*pubkey_b),
-            price:      u64::from_le_bytes(*price_b),
-        };
-        *_buf = &_buf[40..];
-        Ok(token)
+    //  unpack_any_version, the current layout or the legacy unversioned one (version 0)
+    //--------------------------------------------------------------------------------------------------
+    pub fn unpack_any_version(_input: &[u8]) -> Result<Self, ProgramError> {
+        match _input.len() {
+            Self::LEN       => Self::unpack_unchecked(_input),
+            Self::LEN_V0    => Self::unpack_v0(_input),
+            _               => Err(ProgramError::InvalidAccountData),
+        }
     }
-}
+    //==================================================================================================
+    //  unpack_v0, fields added since default to zero which leaves every later feature off
+    //--------------------------------------------------------------------------------------------------
+    fn unpack_v0(_input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![_input, 0, 165];
+        let (
+            is_initialized_b,
+            revenue_owner_b,
+            interest_basis_points_b,
+            locked_token_b,
+            locked_token_owner_b,
+            unlock_timestamp_b,
+            supply_total_b,
+            supply_locked_b,
+            tokens_b,
+        ) = array_refs![input, 1, 32, 4, 32, 32, 8, 8, 8, 40];
 
-////////////////////////////////////////////////////////////////////////////////////////////////////
-impl BorshSerialize for Token {
+        Ok(Self {
+            is_initialized: match is_initialized_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            revenue_owner:          Pubkey::new_from_array(*revenue_owner_b),
+            interest_basis_points:  u32::from_le_bytes(*interest_basis_points_b),
+            locked_token:           Pubkey::new_from_array(*locked_token_b),
+            locked_token_owner:     Pubkey::new_from_array(*locked_token_owner_b),
+            unlock_timestamp:       u64::from_le_bytes(*unlock_timestamp_b),
+            supply_total:           u64::from_le_bytes(*supply_total_b),
+            supply_locked:          u64::from_le_bytes(*supply_locked_b),
+            token0:                 Token::unpack_unchecked(tokens_b)?,
+            ..Self::default()
+        })
+    }
 
     //==================================================================================================
-    #[inline]
-    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        self.address.to_bytes().serialize(writer)?;
-        self.price.serialize(writer)?;
-        Ok(())
-    }
-}
+    //  initialize, the pool `Initialize` creates at `_now` out of these settings, with the running
+    //  totals reset
+    //--------------------------------------------------------------------------------------------------
+    pub fn initialize(&self, _now: u64) -> Result<Settings, ProgramError> {
+        if self.unlock_timestamp <= _now || (self.sale_end != 0 && self.sale_end <= _now) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-////////////////////////////////////////////////////////////////////////////////////////////////////
-impl fmt::Debug for Token {
+        self.validate()?;
 
-    //==================================================================================================
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Token")
-         .field("address", &self.address)
-         .field("price", &self.price)
-         .finish()
+        Ok(Settings {
+            is_initialized: true,
+            supply_locked:  0,
+            total_raised:   0,
+            penalty_pool:   0,
+            batch_bids:     0,
+            batch_demand:   0,
+            batch_filled:   0,
+            batch_settled:  false,
+            ..*self
+        })
     }
-}
+    //==================================================================================================
+    //  validate, the checks of a pool that hold whenever it is created, `Initialize` adds the ones
+    //  against the current time
+    //--------------------------------------------------------------------------------------------------
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        if self.sale_end != 0 && self.unlock_timestamp < self.sale_end {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-////////////////////////////////////////////////////////////////////////////////////////////////////
-impl Sealed for Token {}
+        // the soft cap outcome is decided at `sale_end`
+        if self.soft_cap != 0 && self.sale_end == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-////////////////////////////////////////////////////////////////////////////////////////////////////
-impl Pack for Token {
-    const LEN: usize = 40;
+        if self.token0.price == 0 || 10_000 < self.penalty_basis_points {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-    //==================================================================================================
-    fn pack_into_slice(&self, output: &mut [u8]) {
-        let output                  = array_mut_ref![output, 0, 40];
-        let (address_b, price_b)    = mut_array_refs![output, 32, 8];
+        if !self.is_tiers_valid() || !self.is_auction_valid() || !self.is_batch_valid() || !self.is_referral_valid() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-        address_b.copy_from_slice(self.address.as_ref());
-        *price_b = self.price.to_le_bytes();
+        Ok(())
     }
     //==================================================================================================
-    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
-        let input                   = array_ref![input, 0, 40];
-        let (address_b, price_b)    = array_refs![input, 32, 8];
-        Ok(Self {
-            address:    Pubkey::new_from_array(*address_b),
-            price:      u64::from_le_bytes(*price_b),
+    //  update, the pool `UpdateSettings` leaves. Batch auctions settle and allocate at the price bids
+    //  were placed at, so theirs is fixed
+    //--------------------------------------------------------------------------------------------------
+    pub fn update(&self, _args: &UpdateSettingsArgs) -> Result<Settings, ProgramError> {
+        if _args.price == 0 || (self.batch_end != 0 && _args.price != self.token0.price) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Settings {
+            revenue_owner:      _args.revenue_owner,
+            locked_token_owner: _args.locked_token_owner,
+            token0:             Token { price: _args.price, ..self.token0 },
+            ..*self
         })
     }
-}
+    //==================================================================================================
+    //  swap, `_amount` of token0 paid for `_savings` at `_now`. Everything is checked before the pool
+    //  or the position change, token0 itself is moved by the caller
+    //--------------------------------------------------------------------------------------------------
+    pub fn swap(&mut self, _savings: &mut Savings, _amount: u64, _now: u64, _referral: bool) -> Result<SwapAmounts, ProgramError> {
+        if self.paused || self.unlock_timestamp <= _now || self.is_sale_over(_now) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-////////////////////////////////////////////////////////////////////////////////////////////////////
-////////////////////////////////////////////////////////////////////////////////////////////////////
-impl IsInitialized for Savings {
-    
+        // batch auction pools only sell through bids
+        if self.batch_end != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount_with_interest    = if self.interest_apr {
+                _amount
+            } else {
+                u64::try_from(_amount as u128 + _amount as u128 * self.interest_basis_points as u128 / 100).map_err(|_| ProgramError::InvalidInstructionData)?
+            };
+        let custom_amount           = self.custom_amount(amount_with_interest, _now);
+        // `interest_apr` pools reserve the interest up to unlock right away and credit it as it accrues
+        let interest_reserved       = self.interest_until_unlock(custom_amount, _now)?;
+        let referral_bonus          = if _referral && self.referral_bonus {
+                (custom_amount as u128 * self.referral_basis_points as u128 / 10_000) as u64
+            } else {
+                0
+            };
+        let referral_payment        = if _referral && !self.referral_bonus {
+                (_amount as u128 * self.referral_basis_points as u128 / 10_000) as u64
+            } else {
+                0
+            };
+        let swap_amounts            = SwapAmounts { custom_amount, interest_reserved, referral_bonus, referral_payment };
+        let supply_locked           = swap_amounts.locked()
+            .and_then(|locked| self.supply_locked.checked_add(locked))
+            .filter(|supply_locked| *supply_locked <= self.supply_total)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        // the referral payment leaves the pool, only the rest is refundable or paid back on early exit
+        let paid                    = _amount - referral_payment;
+        let total_raised            = self.total_raised.checked_add(paid).ok_or(ProgramError::InvalidInstructionData)?;
+        let total_paid              = _savings.total_paid.checked_add(paid).ok_or(ProgramError::InvalidInstructionData)?;
+
+        self.supply_locked          = supply_locked;
+        self.total_raised           = total_raised;
+
+        _savings.accrue_interest(self, _now);
+        _savings.is_initialized     = true;
+        _savings.total_technical    += custom_amount;
+        _savings.total_paid         = total_paid;
+        _savings.interest_reserved  += interest_reserved;
+        _savings.accrual_timestamp  = _now;
+
+        Ok(swap_amounts)
+    }
     //==================================================================================================
-    fn is_initialized(&self) -> bool {
-        self.is_initialized
+    //  withdraw, `_amount` locked tokens out of `_savings` at `_now`, returns the share of
+    //  `penalty_pool` it is paid in token0
+    //--------------------------------------------------------------------------------------------------
+    pub fn withdraw(&mut self, _savings: &mut Savings, _amount: u64, _now: u64) -> Result<u64, ProgramError> {
+        if _now < self.unlock_timestamp || self.is_sale_failed(_now) || self.supply_locked < _amount {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut user_savings = *_savings;
+        user_savings.accrue_interest(self, _now);
+
+        if user_savings.total_technical < _amount {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // early exit penalties kept for holders are shared pro rata to the amount withdrawn
+        let penalty_share = if self.penalty_pool != 0 && self.supply_locked != 0 {
+                (self.penalty_pool as u128 * _amount as u128 / self.supply_locked as u128) as u64
+            } else {
+                0
+            };
+
+        self.supply_locked              -= _amount;
+        self.penalty_pool               -= penalty_share;
+        user_savings.total_technical    -= _amount;
+        user_savings.total_original     += _amount;
+        *_savings                       = user_savings;
+
+        Ok(penalty_share)
     }
-}
+    //==================================================================================================
+    //  early_withdraw, burns `_amount` of `_savings` before unlock and returns the token0 paid back,
+    //  what was paid for that part minus `penalty_basis_points`. Pools opt in by setting a penalty,
+    //  without one the lock can't be left early
+    //--------------------------------------------------------------------------------------------------
+    pub fn early_withdraw(&mut self, _savings: &mut Savings, _amount: u64, _now: u64) -> Result<u64, ProgramError> {
+        if self.penalty_basis_points == 0 || self.unlock_timestamp <= _now || self.is_sale_failed(_now) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-////////////////////////////////////////////////////////////////////////////////////////////////////
-impl Sealed for Savings {}
+        let mut user_savings = *_savings;
+        user_savings.accrue_interest(self, _now);
 
-////////////////////////////////////////////////////////////////////////////////////////////////////
-impl Pack for Savings {
-    const LEN: usize = 17;
+        if _amount == 0 || user_savings.total_technical < _amount {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // the burned part of the position is worth the same part of what was paid for it
+        let paid_share  = (user_savings.total_paid as u128 * _amount as u128 / user_savings.total_technical as u128) as u64;
+        let penalty     = (paid_share as u128 * self.penalty_basis_points as u128 / 10_000) as u64;
+        // interest not accrued yet on the burned part is given back to the pool
+        let released    = (user_savings.interest_reserved as u128 * _amount as u128 / user_savings.total_technical as u128) as u64;
+
+        self.supply_locked  -= _amount + released;
+
+        // `total_raised` is what the sale raised once it is over, so exits can't fail a sale that met
+        // its soft cap
+        if !self.is_sale_over(_now) {
+            self.total_raised -= paid_share;
+        }
 
+        if self.penalty_to_holders {
+            self.penalty_pool += penalty;
+        }
+
+        user_savings.total_technical    -= _amount;
+        user_savings.total_paid         -= paid_share;
+        user_savings.interest_reserved  -= released;
+        *_savings                       = user_savings;
+
+        Ok(paid_share - penalty)
+    }
     //==================================================================================================
-    fn pack_into_slice(&self, output: &mut [u8]) {
-        let output = array_mut_ref![output, 0, 17];
-        let (
-            is_initialized_b,
-            total_technical_b,
-            total_original_b,
-        ) = mut_array_refs![output, 1, 8, 8];
+    //  refund, empties `_savings` of a failed sale and returns the token0 it paid, `total_raised`
+    //  keeps what the sale raised
+    //--------------------------------------------------------------------------------------------------
+    pub fn refund(&mut self, _savings: &mut Savings, _now: u64) -> Result<u64, ProgramError> {
+        if !self.is_sale_failed(_now) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-        is_initialized_b[0] = self.is_initialized as u8;
-        *total_technical_b  = self.total_technical.to_le_bytes();
-        *total_original_b   = self.total_original.to_le_bytes();
+        let refund = _savings.total_paid;
+
+        self.supply_locked              -= _savings.total_technical + _savings.interest_reserved;
+        _savings.total_technical        = 0;
+        _savings.total_paid             = 0;
+        _savings.interest_reserved      = 0;
+
+        Ok(refund)
     }
     //==================================================================================================
-    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
-        let input = array_ref![input, 0, 17];
-        let (
-            is_initialized_b,
-            total_technical_b,
-            total_original_b
-        ) = array_refs![input, 1, 8, 8];
+    //  revenue, the part of `_available` token0 above rent `CollectRevenue` may take at `_now`. What
+    //  early exits or refunds may still pay back, bids not claimed yet and the penalties kept for
+    //  holders stay in the pool
+    //--------------------------------------------------------------------------------------------------
+    pub fn revenue(&self, _available: u64, _now: u64) -> Result<u64, ProgramError> {
+        // contributions stay refundable until the sale is over and the soft cap outcome is known
+        if self.soft_cap != 0 && !self.is_sale_over(_now) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-        Ok(Self {
-            is_initialized: match is_initialized_b {
+        // an early exit or a refund pays back at most what was paid, which `total_raised` covers,
+        // and nothing once no position is left. Only pools with a penalty allow early exits and only
+        // pools with a soft cap refund
+        let early_exits     = self.penalty_basis_points != 0 && _now < self.unlock_timestamp;
+        let refunds         = self.soft_cap != 0 && self.is_sale_failed(_now);
+        let paid_back       = if self.supply_locked != 0 && (early_exits || refunds) {
+                self.total_raised
+            } else {
+                0
+            };
+        let penalty_pool    = if self.is_penalty_pool_released(_now) { 0 } else { self.penalty_pool };
+
+        Ok(_available.saturating_sub(penalty_pool).saturating_sub(self.batch_bids).saturating_sub(paid_back))
+    }
+    //==================================================================================================
+    //  penalty pool is released, no holder is left to share it or the failed sale refunds holders
+    //  instead, it is revenue from then on
+    //--------------------------------------------------------------------------------------------------
+    pub fn is_penalty_pool_released(&self, _now: u64) -> bool {
+        self.supply_locked == 0 || self.is_sale_failed(_now)
+    }
+    //==================================================================================================
+    //  sale is over, `sale_end` has passed
+    //--------------------------------------------------------------------------------------------------
+    pub fn is_sale_over(&self, _now: u64) -> bool {
+        self.sale_end != 0 && self.sale_end <= _now
+    }
+    //==================================================================================================
+    //  sale is over and `soft_cap` was not reached, contributions are refundable
+    //--------------------------------------------------------------------------------------------------
+    pub fn is_sale_failed(&self, _now: u64) -> bool {
+        self.soft_cap != 0 && self.is_sale_over(_now) && self.total_raised < self.soft_cap
+    }
+    //==================================================================================================
+    //  interest on `_amount` for the time left until `unlock_timestamp`, `interest_apr` pools only
+    //--------------------------------------------------------------------------------------------------
+    pub fn interest_until_unlock(&self, _amount: u64, _now: u64) -> Result<u64, ProgramError> {
+        if !self.interest_apr || self.unlock_timestamp <= _now {
+            return Ok(0);
+        }
+
+        let interest = (_amount as u128 * self.interest_basis_points as u128)
+            .checked_mul((self.unlock_timestamp - _now) as u128)
+            .ok_or(ProgramError::InvalidInstructionData)?
+            / (100 * Self::SECONDS_PER_YEAR as u128);
+
+        u64::try_from(interest).map_err(|_| ProgramError::InvalidInstructionData)
+    }
+    //==================================================================================================
+    //  locked tokens `_amount` of token0 buys from `supply_locked` on, walking the price tiers first
+    //  and `price` past the last one, a purchase crossing a boundary is priced in both tiers
+    //--------------------------------------------------------------------------------------------------
+    pub fn custom_amount(&self, _amount: u64, _now: u64) -> u64 {
+        let mut budget  = _amount;
+        let mut supply  = self.supply_locked;
+        let mut custom  = 0;
+
+        for tier in self.tiers.iter().take_while(|tier| tier.supply_end != 0) {
+            if supply < tier.supply_end {
+                let take = (budget / tier.price).min(tier.supply_end - supply);
+
+                budget  -= take * tier.price;
+                supply  += take;
+                custom  += take;
+
+                if supply < tier.supply_end {
+                    return custom;
+                }
+            }
+        }
+
+        custom + budget / self.price(_now)
+    }
+    //==================================================================================================
+    //  `token0.price`, or for auctions the price decaying linearly from `auction_start_price` to
+    //  `auction_floor_price` between `auction_start` and `auction_end`
+    //--------------------------------------------------------------------------------------------------
+    pub fn price(&self, _now: u64) -> u64 {
+        if self.auction_end == 0 {
+            return self.token0.price;
+        }
+
+        if _now <= self.auction_start {
+            return self.auction_start_price;
+        }
+
+        if self.auction_end <= _now {
+            return self.auction_floor_price;
+        }
+
+        let decay = (self.auction_start_price - self.auction_floor_price) as u128 * (_now - self.auction_start) as u128 / (self.auction_end - self.auction_start) as u128;
+        self.auction_start_price - decay as u64
+    }
+    //==================================================================================================
+    //  auctions are optional and can't be combined with tiers
+    //--------------------------------------------------------------------------------------------------
+    pub fn is_auction_valid(&self) -> bool {
+        self.auction_end == 0 || (
+            self.auction_start < self.auction_end
+            && self.auction_end <= self.unlock_timestamp
+            && self.auction_floor_price != 0
+            && self.auction_floor_price <= self.auction_start_price
+            && self.tiers[0] == Tier::default()
+        )
+    }
+    //==================================================================================================
+    //  locked tokens a batch bid of `_amount` asks for at `token0.price`, interest included
+    //--------------------------------------------------------------------------------------------------
+    pub fn batch_demand_of(&self, _amount: u64) -> u64 {
+        let amount_with_interest = _amount as u128 + _amount as u128 * self.interest_basis_points as u128 / 100;
+        (amount_with_interest / self.token0.price as u128) as u64
+    }
+    //==================================================================================================
+    //  locked tokens and token0 used out of a batch bid of `_amount` once settled, pro rata to
+    //  `batch_filled` over `batch_demand`, the unused token0 is refundable
+    //--------------------------------------------------------------------------------------------------
+    pub fn batch_allocation(&self, _amount: u64) -> (u64, u64) {
+        if self.batch_demand == 0 {
+            return (0, 0);
+        }
+
+        let custom  = self.batch_demand_of(_amount) as u128 * self.batch_filled as u128 / self.batch_demand as u128;
+        let used    = _amount as u128 * self.batch_filled as u128 / self.batch_demand as u128;
+        (custom as u64, used as u64)
+    }
+    //==================================================================================================
+    //  batch auctions are optional, priced at `token0.price` only and settled before unlock, unfilled
+    //  bids are refunded anyway so there is no soft cap
+    //--------------------------------------------------------------------------------------------------
+    pub fn is_batch_valid(&self) -> bool {
+        self.batch_end == 0 || (
+            self.batch_end < self.unlock_timestamp
+            && self.soft_cap == 0
+            && !self.interest_apr
+            && self.auction_end == 0
+            && self.tiers[0] == Tier::default()
+        )
+    }
+    //==================================================================================================
+    //  referrals are paid as a share of the payment or as bonus locked tokens, a payment share is not
+    //  refundable so it needs a pool without soft cap
+    //--------------------------------------------------------------------------------------------------
+    pub fn is_referral_valid(&self) -> bool {
+        self.referral_basis_points <= 10_000 && (self.referral_basis_points == 0 || self.referral_bonus || self.soft_cap == 0)
+    }
+    //==================================================================================================
+    //  tiers are optional, used ones come first with growing `supply_end` and a price set
+    //--------------------------------------------------------------------------------------------------
+    pub fn is_tiers_valid(&self) -> bool {
+        let used = self.tiers.iter().take_while(|tier| tier.supply_end != 0).count();
+
+        self.tiers[used..].iter().all(|tier| *tier == Tier::default())
+            && self.tiers[..used].iter().all(|tier| tier.price != 0 && tier.supply_end <= self.supply_total)
+            && self.tiers[..used].windows(2).all(|pair| pair[0].supply_end < pair[1].supply_end)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl IsInitialized for Settings {
+
+    //==================================================================================================
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl BorshDeserialize for Settings {
+
+    //==================================================================================================
+    fn deserialize(_buf: &mut &[u8]) -> std::io::Result<Self> {
+        if _buf.len() < Settings::BODY_LEN {
+            return Err(invalid_data(ProgramError::InvalidInstructionData));
+        }
+
+        let settings    = Self::unpack_body(array_ref![_buf, 0, 338]).map_err(invalid_data)?;
+        *_buf           = &_buf[Settings::BODY_LEN..];
+
+        Ok(settings)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl BorshSerialize for Settings {
+
+    //==================================================================================================
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let mut body = [0u8; Settings::BODY_LEN];
+        self.pack_body(&mut body);
+        writer.write_all(&body)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Sealed for Settings {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Pack for Settings {
+    const LEN: usize = 340;
+
+    //==================================================================================================
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output                                  = array_mut_ref![output, 0, 340];
+        let (account_type_b, version_b, body_b)     = mut_array_refs![output, 1, 1, 338];
+
+        account_type_b[0]   = AccountType::Settings as u8;
+        version_b[0]        = Self::VERSION;
+        self.pack_body(body_b);
+    }
+    //==================================================================================================
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input                                   = array_ref![input, 0, 340];
+        let (account_type_b, version_b, body_b)     = array_refs![input, 1, 1, 338];
+
+        AccountType::Settings.check(account_type_b[0], body_b[0])?;
+
+        // zeroed accounts are not versioned yet, legacy layouts are read by `unpack_any_version`
+        if account_type_b[0] != AccountType::Uninitialized as u8 && version_b[0] != Self::VERSION {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Self::unpack_body(body_b)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl BorshDeserialize for Token {
+
+    //==================================================================================================
+    #[inline]
+    fn deserialize(_buf: &mut &[u8]) -> std::io::Result<Self> {
+        if _buf.len() < Token::LEN {
+            return Err(invalid_data(ProgramError::InvalidInstructionData));
+        }
+
+        let token   = Token::unpack_from_slice(&_buf[..Token::LEN]).map_err(invalid_data)?;
+        *_buf       = &_buf[Token::LEN..];
+
+        Ok(token)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl BorshSerialize for Token {
+
+    //==================================================================================================
+    #[inline]
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let mut data = [0u8; Token::LEN];
+        self.pack_into_slice(&mut data);
+        writer.write_all(&data)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl fmt::Debug for Token {
+
+    //==================================================================================================
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+         .field("address", &self.address)
+         .field("price", &self.price)
+         .finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Sealed for Token {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Pack for Token {
+    const LEN: usize = 40;
+
+    //==================================================================================================
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output                  = array_mut_ref![output, 0, 40];
+        let (address_b, price_b)    = mut_array_refs![output, 32, 8];
+
+        address_b.copy_from_slice(self.address.as_ref());
+        *price_b = self.price.to_le_bytes();
+    }
+    //==================================================================================================
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input                   = array_ref![input, 0, 40];
+        let (address_b, price_b)    = array_refs![input, 32, 8];
+        Ok(Self {
+            address:    Pubkey::new_from_array(*address_b),
+            price:      u64::from_le_bytes(*price_b),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl SwapAmounts {
+
+    //==================================================================================================
+    //  locked, what the swap takes from the supply, `None` on overflow
+    //--------------------------------------------------------------------------------------------------
+    pub fn locked(&self) -> Option<u64> {
+        self.custom_amount.checked_add(self.interest_reserved)?.checked_add(self.referral_bonus)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Savings {
+    pub const VERSION:  u8      = 1;
+    pub const LEN_V0:   usize   = 17;
+
+    //==================================================================================================
+    //  unpack_any_version, the current layout or the legacy unversioned one (version 0)
+    //--------------------------------------------------------------------------------------------------
+    pub fn unpack_any_version(_input: &[u8]) -> Result<Self, ProgramError> {
+        match _input.len() {
+            Self::LEN       => Self::unpack_unchecked(_input),
+            Self::LEN_V0    => Self::unpack_v0(_input),
+            _               => Err(ProgramError::InvalidAccountData),
+        }
+    }
+    //==================================================================================================
+    //  accrue_interest, the reserve accrues linearly and is fully credited at `unlock_timestamp`
+    //--------------------------------------------------------------------------------------------------
+    pub fn accrue_interest(&mut self, _settings: &Settings, _now: u64) {
+        let until = _now.min(_settings.unlock_timestamp);
+
+        if self.interest_reserved != 0 && self.accrual_timestamp < until {
+            let accrued = (self.interest_reserved as u128 * (until - self.accrual_timestamp) as u128 / (_settings.unlock_timestamp - self.accrual_timestamp) as u128) as u64;
+
+            self.total_technical    += accrued;
+            self.interest_reserved  -= accrued;
+            self.accrual_timestamp  = until;
+        }
+    }
+    //==================================================================================================
+    //  split, takes `_amount` out of the position along with its share of `total_paid`, which is
+    //  returned for the order or stake receiving it. Orders and stakes carry no interest reserve, so
+    //  positions still accruing interest, accrued up to now by the caller, can't be split
+    //--------------------------------------------------------------------------------------------------
+    pub fn split(&mut self, _amount: u64) -> Result<u64, ProgramError> {
+        if self.interest_reserved != 0 || self.total_technical < _amount {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let paid = pro_rata(self.total_paid, _amount, self.total_technical);
+
+        self.total_technical    -= _amount;
+        self.total_paid         -= paid;
+
+        Ok(paid)
+    }
+    //==================================================================================================
+    //  transfer, moves `_amount` into `_savings` at `_now` along with its share of `total_paid`, which
+    //  is returned, and of the interest still reserved for it, which accrues on the receiving side
+    //  from then on
+    //--------------------------------------------------------------------------------------------------
+    pub fn transfer(&mut self, _savings: &mut Savings, _amount: u64, _settings: &Settings, _now: u64) -> Result<u64, ProgramError> {
+        let mut from    = *self;
+        let mut to      = *_savings;
+
+        from.accrue_interest(_settings, _now);
+        to.accrue_interest(_settings, _now);
+
+        if from.total_technical < _amount {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let paid        = pro_rata(from.total_paid, _amount, from.total_technical);
+        let reserved    = pro_rata(from.interest_reserved, _amount, from.total_technical);
+
+        to.total_technical      = to.total_technical.checked_add(_amount).ok_or(ProgramError::InvalidInstructionData)?;
+        to.total_paid           = to.total_paid.checked_add(paid).ok_or(ProgramError::InvalidInstructionData)?;
+        to.interest_reserved    = to.interest_reserved.checked_add(reserved).ok_or(ProgramError::InvalidInstructionData)?;
+
+        // both sides are accrued up to now, the reserve moved is what is left until unlock
+        if reserved != 0 {
+            to.accrual_timestamp = from.accrual_timestamp;
+        }
+
+        from.total_technical    -= _amount;
+        from.total_paid         -= paid;
+        from.interest_reserved  -= reserved;
+
+        *self       = from;
+        *_savings   = to;
+
+        Ok(paid)
+    }
+    //==================================================================================================
+    //  unpack_v0, legacy Savings record neither their wallet nor their pool, `Migrate` sets both
+    //--------------------------------------------------------------------------------------------------
+    fn unpack_v0(_input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![_input, 0, 17];
+        let (
+            is_initialized_b,
+            total_technical_b,
+            total_original_b,
+        ) = array_refs![input, 1, 8, 8];
+
+        Ok(Self {
+            is_initialized: match is_initialized_b {
                 [0] => false,
                 [1] => true,
                 _ => return Err(ProgramError::InvalidAccountData),
             },
             total_technical:    u64::from_le_bytes(*total_technical_b),
-            total_original:     u64::from_le_bytes(*total_original_b)
+            total_original:     u64::from_le_bytes(*total_original_b),
+            ..Self::default()
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl IsInitialized for Savings {
+    
+    //==================================================================================================
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Sealed for Savings {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Pack for Savings {
+    const LEN: usize = 107;
+
+    //==================================================================================================
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 107];
+        let (
+            account_type_b,
+            version_b,
+            is_initialized_b,
+            total_technical_b,
+            total_original_b,
+            total_paid_b,
+            interest_reserved_b,
+            accrual_timestamp_b,
+            owner_b,
+            settings_b,
+        ) = mut_array_refs![output, 1, 1, 1, 8, 8, 8, 8, 8, 32, 32];
+
+        account_type_b[0]   = AccountType::Savings as u8;
+        version_b[0]        = Self::VERSION;
+        is_initialized_b[0] = self.is_initialized as u8;
+        *total_technical_b  = self.total_technical.to_le_bytes();
+        *total_original_b   = self.total_original.to_le_bytes();
+        *total_paid_b       = self.total_paid.to_le_bytes();
+        *interest_reserved_b = self.interest_reserved.to_le_bytes();
+        *accrual_timestamp_b = self.accrual_timestamp.to_le_bytes();
+        owner_b             .copy_from_slice(self.owner.as_ref());
+        settings_b          .copy_from_slice(self.settings.as_ref());
+    }
+    //==================================================================================================
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 107];
+        let (
+            account_type_b,
+            version_b,
+            is_initialized_b,
+            total_technical_b,
+            total_original_b,
+            total_paid_b,
+            interest_reserved_b,
+            accrual_timestamp_b,
+            owner_b,
+            settings_b,
+        ) = array_refs![input, 1, 1, 1, 8, 8, 8, 8, 8, 32, 32];
+
+        AccountType::Savings.check(account_type_b[0], is_initialized_b[0])?;
+
+        // zeroed accounts are not versioned yet, legacy layouts are read by `unpack_any_version`
+        if account_type_b[0] != AccountType::Uninitialized as u8 && version_b[0] != Self::VERSION {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self {
+            is_initialized: match is_initialized_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            total_technical:    u64::from_le_bytes(*total_technical_b),
+            total_original:     u64::from_le_bytes(*total_original_b),
+            total_paid:         u64::from_le_bytes(*total_paid_b),
+            interest_reserved:  u64::from_le_bytes(*interest_reserved_b),
+            accrual_timestamp:  u64::from_le_bytes(*accrual_timestamp_b),
+            owner:              Pubkey::new_from_array(*owner_b),
+            settings:           Pubkey::new_from_array(*settings_b),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Order {
+
+    //==================================================================================================
+    //  split, `_amount` of the order filled along with its share of `paid`
+    //--------------------------------------------------------------------------------------------------
+    pub fn split(&mut self, _amount: u64) -> Result<u64, ProgramError> {
+        if self.amount < _amount {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let paid = pro_rata(self.paid, _amount, self.amount);
+
+        self.amount -= _amount;
+        self.paid   -= paid;
+
+        Ok(paid)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl IsInitialized for Order {
+
+    //==================================================================================================
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Sealed for Order {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Pack for Order {
+    const LEN: usize = 98;
+
+    //==================================================================================================
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 98];
+        let (
+            account_type_b,
+            is_initialized_b,
+            settings_b,
+            seller_b,
+            amount_b,
+            paid_b,
+            price_b,
+            expiry_timestamp_b,
+        ) = mut_array_refs![output, 1, 1, 32, 32, 8, 8, 8, 8];
+
+        account_type_b[0]   = AccountType::Order as u8;
+        is_initialized_b[0] = self.is_initialized as u8;
+        settings_b          .copy_from_slice(self.settings.as_ref());
+        seller_b            .copy_from_slice(self.seller.as_ref());
+        *amount_b           = self.amount.to_le_bytes();
+        *paid_b             = self.paid.to_le_bytes();
+        *price_b            = self.price.to_le_bytes();
+        *expiry_timestamp_b = self.expiry_timestamp.to_le_bytes();
+    }
+    //==================================================================================================
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 98];
+        let (
+            account_type_b,
+            is_initialized_b,
+            settings_b,
+            seller_b,
+            amount_b,
+            paid_b,
+            price_b,
+            expiry_timestamp_b,
+        ) = array_refs![input, 1, 1, 32, 32, 8, 8, 8, 8];
+
+        AccountType::Order.check(account_type_b[0], is_initialized_b[0])?;
+
+        Ok(Self {
+            is_initialized: match is_initialized_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            settings:           Pubkey::new_from_array(*settings_b),
+            seller:             Pubkey::new_from_array(*seller_b),
+            amount:             u64::from_le_bytes(*amount_b),
+            paid:               u64::from_le_bytes(*paid_b),
+            price:              u64::from_le_bytes(*price_b),
+            expiry_timestamp:   u64::from_le_bytes(*expiry_timestamp_b),
+        })
+    }
+}
+
+//**************************************************************************************************
+//  Bid
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Bid {
+    pub is_initialized:     bool,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub settings:           Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub owner:              Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub amount:             u64,
+    pub allocation_claimed: bool,
+    pub refund_claimed:     bool,
+}
+
+//**************************************************************************************************
+//  Referrer
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct Referrer {
+    pub is_initialized:     bool,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub settings:           Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub owner:              Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub volume:             u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub rewards:            u64,
+}
+
+//**************************************************************************************************
+//  StakePool
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct StakePool {
+    pub is_initialized:     bool,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub settings:           Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub authority:          Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub reward_rate:        u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub reward_end:         u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub reward_per_share:   u128,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub last_update:        u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub total_staked:       u64,
+}
+
+//**************************************************************************************************
+//  StakeAccount
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", derive(kaizen_derive::Layout))]
+pub struct StakeAccount {
+    pub is_initialized:     bool,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub stake_pool:         Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub owner:              Pubkey,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub amount:             u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub paid:               u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub reward_debt:        u128,
+    #[cfg_attr(feature = "json", serde(with = "crate::json"))]
+    pub rewards_pending:    u64,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl IsInitialized for Bid {
+
+    //==================================================================================================
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Sealed for Bid {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Pack for Bid {
+    const LEN: usize = 76;
+
+    //==================================================================================================
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 76];
+        let (
+            account_type_b,
+            is_initialized_b,
+            settings_b,
+            owner_b,
+            amount_b,
+            allocation_claimed_b,
+            refund_claimed_b,
+        ) = mut_array_refs![output, 1, 1, 32, 32, 8, 1, 1];
+
+        account_type_b[0]       = AccountType::Bid as u8;
+        is_initialized_b[0]     = self.is_initialized as u8;
+        settings_b              .copy_from_slice(self.settings.as_ref());
+        owner_b                 .copy_from_slice(self.owner.as_ref());
+        *amount_b               = self.amount.to_le_bytes();
+        allocation_claimed_b[0] = self.allocation_claimed as u8;
+        refund_claimed_b[0]     = self.refund_claimed as u8;
+    }
+    //==================================================================================================
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 76];
+        let (
+            account_type_b,
+            is_initialized_b,
+            settings_b,
+            owner_b,
+            amount_b,
+            allocation_claimed_b,
+            refund_claimed_b,
+        ) = array_refs![input, 1, 1, 32, 32, 8, 1, 1];
+
+        AccountType::Bid.check(account_type_b[0], is_initialized_b[0])?;
+
+        Ok(Self {
+            is_initialized: match is_initialized_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            settings:           Pubkey::new_from_array(*settings_b),
+            owner:              Pubkey::new_from_array(*owner_b),
+            amount:             u64::from_le_bytes(*amount_b),
+            allocation_claimed: match allocation_claimed_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            refund_claimed: match refund_claimed_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl IsInitialized for Referrer {
+
+    //==================================================================================================
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Sealed for Referrer {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Pack for Referrer {
+    const LEN: usize = 82;
+
+    //==================================================================================================
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 82];
+        let (
+            account_type_b,
+            is_initialized_b,
+            settings_b,
+            owner_b,
+            volume_b,
+            rewards_b,
+        ) = mut_array_refs![output, 1, 1, 32, 32, 8, 8];
+
+        account_type_b[0]   = AccountType::Referrer as u8;
+        is_initialized_b[0] = self.is_initialized as u8;
+        settings_b          .copy_from_slice(self.settings.as_ref());
+        owner_b             .copy_from_slice(self.owner.as_ref());
+        *volume_b           = self.volume.to_le_bytes();
+        *rewards_b          = self.rewards.to_le_bytes();
+    }
+    //==================================================================================================
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 82];
+        let (
+            account_type_b,
+            is_initialized_b,
+            settings_b,
+            owner_b,
+            volume_b,
+            rewards_b,
+        ) = array_refs![input, 1, 1, 32, 32, 8, 8];
+
+        AccountType::Referrer.check(account_type_b[0], is_initialized_b[0])?;
+
+        Ok(Self {
+            is_initialized: match is_initialized_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            settings:           Pubkey::new_from_array(*settings_b),
+            owner:              Pubkey::new_from_array(*owner_b),
+            volume:             u64::from_le_bytes(*volume_b),
+            rewards:            u64::from_le_bytes(*rewards_b),
         })
     }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl StakePool {
+    pub const PRECISION: u128 = 1_000_000_000_000;
+
+    //==================================================================================================
+    //  update, accrues `reward_rate` per second up to `_now`, clamped to `reward_end`, into
+    //  `reward_per_share`. Nothing accrues while nothing is staked
+    //--------------------------------------------------------------------------------------------------
+    pub fn update(&mut self, _now: u64) -> Result<(), ProgramError> {
+        let until = _now.min(self.reward_end);
+
+        if self.last_update < until {
+            if self.total_staked != 0 {
+                let accrued = (self.reward_rate as u128)
+                    .checked_mul((until - self.last_update) as u128)
+                    .and_then(|rewards| rewards.checked_mul(Self::PRECISION))
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+
+                self.reward_per_share = self.reward_per_share
+                    .checked_add(accrued / self.total_staked as u128)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+            }
+            self.last_update = until;
+        }
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  reward_debt_of, the rewards `_amount` staked since the start would have accrued
+    //--------------------------------------------------------------------------------------------------
+    pub fn reward_debt_of(&self, _amount: u64) -> Result<u128, ProgramError> {
+        (_amount as u128)
+            .checked_mul(self.reward_per_share)
+            .map(|rewards| rewards / Self::PRECISION)
+            .ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl IsInitialized for StakePool {
+
+    //==================================================================================================
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Sealed for StakePool {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Pack for StakePool {
+    const LEN: usize = 114;
+
+    //==================================================================================================
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 114];
+        let (
+            account_type_b,
+            is_initialized_b,
+            settings_b,
+            authority_b,
+            reward_rate_b,
+            reward_end_b,
+            reward_per_share_b,
+            last_update_b,
+            total_staked_b,
+        ) = mut_array_refs![output, 1, 1, 32, 32, 8, 8, 16, 8, 8];
+
+        account_type_b[0]   = AccountType::StakePool as u8;
+        is_initialized_b[0] = self.is_initialized as u8;
+        settings_b          .copy_from_slice(self.settings.as_ref());
+        authority_b         .copy_from_slice(self.authority.as_ref());
+        *reward_rate_b      = self.reward_rate.to_le_bytes();
+        *reward_end_b       = self.reward_end.to_le_bytes();
+        *reward_per_share_b = self.reward_per_share.to_le_bytes();
+        *last_update_b      = self.last_update.to_le_bytes();
+        *total_staked_b     = self.total_staked.to_le_bytes();
+    }
+    //==================================================================================================
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 114];
+        let (
+            account_type_b,
+            is_initialized_b,
+            settings_b,
+            authority_b,
+            reward_rate_b,
+            reward_end_b,
+            reward_per_share_b,
+            last_update_b,
+            total_staked_b,
+        ) = array_refs![input, 1, 1, 32, 32, 8, 8, 16, 8, 8];
+
+        AccountType::StakePool.check(account_type_b[0], is_initialized_b[0])?;
+
+        Ok(Self {
+            is_initialized: match is_initialized_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            settings:           Pubkey::new_from_array(*settings_b),
+            authority:          Pubkey::new_from_array(*authority_b),
+            reward_rate:        u64::from_le_bytes(*reward_rate_b),
+            reward_end:         u64::from_le_bytes(*reward_end_b),
+            reward_per_share:   u128::from_le_bytes(*reward_per_share_b),
+            last_update:        u64::from_le_bytes(*last_update_b),
+            total_staked:       u64::from_le_bytes(*total_staked_b),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl StakeAccount {
+
+    //==================================================================================================
+    //  settle, moves the rewards accrued since the last settlement to `rewards_pending`, `_stake_pool`
+    //  is updated first
+    //--------------------------------------------------------------------------------------------------
+    pub fn settle(&mut self, _stake_pool: &StakePool) -> Result<(), ProgramError> {
+        let accrued = _stake_pool.reward_debt_of(self.amount)?;
+        let rewards = accrued.checked_sub(self.reward_debt).ok_or(ProgramError::InvalidInstructionData)?;
+        let rewards = u64::try_from(rewards).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+        self.rewards_pending    = self.rewards_pending.checked_add(rewards).ok_or(ProgramError::InvalidInstructionData)?;
+        self.reward_debt        = accrued;
+
+        Ok(())
+    }
+    //==================================================================================================
+    //  split, `_amount` unstaked along with its share of `paid`
+    //--------------------------------------------------------------------------------------------------
+    pub fn split(&mut self, _amount: u64) -> Result<u64, ProgramError> {
+        if self.amount < _amount {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let paid = pro_rata(self.paid, _amount, self.amount);
+
+        self.amount -= _amount;
+        self.paid   -= paid;
+
+        Ok(paid)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl IsInitialized for StakeAccount {
+
+    //==================================================================================================
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Sealed for StakeAccount {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Pack for StakeAccount {
+    const LEN: usize = 106;
+
+    //==================================================================================================
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 106];
+        let (
+            account_type_b,
+            is_initialized_b,
+            stake_pool_b,
+            owner_b,
+            amount_b,
+            paid_b,
+            reward_debt_b,
+            rewards_pending_b,
+        ) = mut_array_refs![output, 1, 1, 32, 32, 8, 8, 16, 8];
+
+        account_type_b[0]   = AccountType::StakeAccount as u8;
+        is_initialized_b[0] = self.is_initialized as u8;
+        stake_pool_b        .copy_from_slice(self.stake_pool.as_ref());
+        owner_b             .copy_from_slice(self.owner.as_ref());
+        *amount_b           = self.amount.to_le_bytes();
+        *paid_b             = self.paid.to_le_bytes();
+        *reward_debt_b      = self.reward_debt.to_le_bytes();
+        *rewards_pending_b  = self.rewards_pending.to_le_bytes();
+    }
+    //==================================================================================================
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 106];
+        let (
+            account_type_b,
+            is_initialized_b,
+            stake_pool_b,
+            owner_b,
+            amount_b,
+            paid_b,
+            reward_debt_b,
+            rewards_pending_b,
+        ) = array_refs![input, 1, 1, 32, 32, 8, 8, 16, 8];
+
+        AccountType::StakeAccount.check(account_type_b[0], is_initialized_b[0])?;
+
+        Ok(Self {
+            is_initialized: match is_initialized_b {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            stake_pool:         Pubkey::new_from_array(*stake_pool_b),
+            owner:              Pubkey::new_from_array(*owner_b),
+            amount:             u64::from_le_bytes(*amount_b),
+            paid:               u64::from_le_bytes(*paid_b),
+            reward_debt:        u128::from_le_bytes(*reward_debt_b),
+            rewards_pending:    u64::from_le_bytes(*rewards_pending_b),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Sealed for Tier {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Pack for Tier {
+    const LEN: usize = 16;
+
+    //==================================================================================================
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output                      = array_mut_ref![output, 0, 16];
+        let (supply_end_b, price_b)     = mut_array_refs![output, 8, 8];
+
+        *supply_end_b   = self.supply_end.to_le_bytes();
+        *price_b        = self.price.to_le_bytes();
+    }
+    //==================================================================================================
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input                       = array_ref![input, 0, 16];
+        let (supply_end_b, price_b)     = array_refs![input, 8, 8];
+        Ok(Self {
+            supply_end: u64::from_le_bytes(*supply_end_b),
+            price:      u64::from_le_bytes(*price_b),
+        })
+    }
+}
+
+//==================================================================================================
+//  pro_rata, the part of `_value` that goes with `_amount` out of `_total`, rounded down so the
+//  remainder never runs short
+//--------------------------------------------------------------------------------------------------
+fn pro_rata(_value: u64, _amount: u64, _total: u64) -> u64 {
+    if _total == 0 {
+        return 0;
+    }
+
+    (_value as u128 * _amount as u128 / _total as u128) as u64
+}
+
+//==================================================================================================
+//  invalid_data, `Pack` errors reported through Borsh
+//--------------------------------------------------------------------------------------------------
+fn invalid_data(_error: ProgramError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{:?}", _error))
+}
+
+//**************************************************************************************************
+//  KaizenAccount, any state account owned by the program
+//--------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(tag = "type"))]
+pub enum KaizenAccount {
+    Settings(Settings),
+    Savings(Savings),
+    Order(Order),
+    Bid(Bid),
+    Referrer(Referrer),
+    StakePool(StakePool),
+    StakeAccount(StakeAccount),
+}
+
+//==================================================================================================
+//  decode_account, the account type is read from the first byte of the data, legacy unversioned
+//  accounts by their length
+//--------------------------------------------------------------------------------------------------
+pub fn decode_account(_data: &[u8]) -> Result<KaizenAccount, ProgramError> {
+    match _data.len() {
+        Settings::LEN_V0    => return Ok(KaizenAccount::Settings(Settings::unpack_any_version(_data)?)),
+        Savings::LEN_V0     => return Ok(KaizenAccount::Savings(Savings::unpack_any_version(_data)?)),
+        _                   => {}
+    }
+
+    Ok(match AccountType::of(_data) {
+        Some(AccountType::Settings)     => KaizenAccount::Settings(Settings::unpack_unchecked(_data)?),
+        Some(AccountType::Savings)      => KaizenAccount::Savings(Savings::unpack_unchecked(_data)?),
+        Some(AccountType::Order)        => KaizenAccount::Order(Order::unpack_unchecked(_data)?),
+        Some(AccountType::Bid)          => KaizenAccount::Bid(Bid::unpack_unchecked(_data)?),
+        Some(AccountType::Referrer)     => KaizenAccount::Referrer(Referrer::unpack_unchecked(_data)?),
+        Some(AccountType::StakePool)    => KaizenAccount::StakePool(StakePool::unpack_unchecked(_data)?),
+        Some(AccountType::StakeAccount) => KaizenAccount::StakeAccount(StakeAccount::unpack_unchecked(_data)?),
+        _                               => return Err(ProgramError::InvalidAccountData),
+    })
 }
\ No newline at end of file
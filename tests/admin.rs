@@ -0,0 +1,158 @@
+mod common;
+
+use {
+    solana_program::{
+        instruction::{
+            AccountMeta,
+            InstructionError,
+        },
+        program_error::ProgramError,
+        program_pack::Pack,
+        pubkey::Pubkey,
+    },
+    solana_program_test::tokio,
+    solana_sdk::{
+        signature::{
+            Keypair,
+            Signer,
+        },
+        transport::TransportError,
+    },
+    kaizen::{
+        instruction::KaizenInstruction,
+        state::{
+            PauseArgs,
+            Savings,
+            Settings,
+            Token,
+            TransferSavingsArgs,
+            UpdateSettingsArgs,
+        },
+    },
+    common::*,
+};
+
+//==================================================================================================
+//  start, a pool unlocking in a minute whose locked token owner is returned
+//--------------------------------------------------------------------------------------------------
+async fn start() -> (Pool, Keypair) {
+    let locked_token_owner  = Keypair::new();
+    let mut pool            = Pool::start(Savings::LEN).await;
+    let settings            = pool.pool_settings(1_000_000, 60).await;
+
+    pool.initialize_account(pool.settings, Settings { locked_token_owner: locked_token_owner.pubkey(), ..settings }).await.unwrap();
+
+    (pool, locked_token_owner)
+}
+
+//==================================================================================================
+//  admin, `_instruction` on the pool signed by `_signer`
+//--------------------------------------------------------------------------------------------------
+async fn admin(_pool: &mut Pool, _signer: &Keypair, _instruction: KaizenInstruction) -> Result<(), TransportError> {
+    _pool.process_signed(_instruction, vec![
+        AccountMeta::new(_pool.settings, false),
+        AccountMeta::new_readonly(_signer.pubkey(), true),
+    ],
+    &[_signer]).await
+}
+
+//==================================================================================================
+//  pause
+//--------------------------------------------------------------------------------------------------
+async fn pause(_pool: &mut Pool, _signer: &Keypair, _paused: bool) -> Result<(), TransportError> {
+    admin(_pool, _signer, KaizenInstruction::Pause(PauseArgs { paused: _paused })).await
+}
+
+//**************************************************************************************************
+//  test_pause, new positions and transfers stop, withdrawals go on
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_pause() {
+    let (mut pool, locked_token_owner) = start().await;
+
+    pool.swap(100).await.unwrap();
+
+    // only the locked token owner
+    let other = Keypair::new();
+    assert_rejected(pause(&mut pool, &other, true).await, InstructionError::InvalidAccountData);
+    assert_rejected(pool.process(KaizenInstruction::Pause(PauseArgs { paused: true }), vec![
+        AccountMeta::new(pool.settings, false),
+        AccountMeta::new_readonly(locked_token_owner.pubkey(), false),
+    ]).await, InstructionError::MissingRequiredSignature);
+
+    pause(&mut pool, &locked_token_owner, true).await.unwrap();
+    assert!(pool.unpack::<Settings>(pool.settings).await.paused);
+
+    let owner = pool.context.payer.pubkey();
+    assert_rejected(pool.swap(50).await, InstructionError::InvalidInstructionData);
+    assert_rejected(pool.process(KaizenInstruction::TransferSavings(TransferSavingsArgs { amount: 10 }), vec![
+        AccountMeta::new_readonly(pool.settings, false),
+        AccountMeta::new(pool.savings, false),
+        AccountMeta::new_readonly(owner, true),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+    ]).await, InstructionError::InvalidInstructionData);
+
+    // holders still get out once unlocked
+    let unlock = pool.unpack::<Settings>(pool.settings).await.unlock_timestamp;
+    pool.warp_past(unlock).await;
+    pool.withdraw(55).await.unwrap();
+    assert_eq!(pool.savings().await.total_technical, 0);
+}
+
+//**************************************************************************************************
+//  test_unpause
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_unpause() {
+    let (mut pool, locked_token_owner) = start().await;
+
+    pause(&mut pool, &locked_token_owner, true).await.unwrap();
+    assert_rejected(pool.swap(100).await, InstructionError::InvalidInstructionData);
+
+    pause(&mut pool, &locked_token_owner, false).await.unwrap();
+
+    // on a later bank, the same Swap transaction would be a duplicate otherwise
+    let now = pool.now().await;
+    pool.warp_past(now).await;
+    pool.swap(100).await.unwrap();
+    assert_eq!(pool.savings().await.total_technical, 55);
+}
+
+//**************************************************************************************************
+//  test_update_settings, the new owners take over and the new price applies to later swaps
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_update_settings() {
+    let (mut pool, locked_token_owner) = start().await;
+
+    let new_owner   = Keypair::new();
+    let args        = UpdateSettingsArgs { revenue_owner: Pubkey::new_unique(), locked_token_owner: new_owner.pubkey(), price: 4 };
+
+    assert_rejected(admin(&mut pool, &new_owner, KaizenInstruction::UpdateSettings(args)).await, InstructionError::InvalidAccountData);
+    assert_rejected(admin(&mut pool, &locked_token_owner, KaizenInstruction::UpdateSettings(UpdateSettingsArgs { price: 0, ..args })).await, InstructionError::InvalidInstructionData);
+
+    admin(&mut pool, &locked_token_owner, KaizenInstruction::UpdateSettings(args)).await.unwrap();
+
+    let settings = pool.unpack::<Settings>(pool.settings).await;
+    assert_eq!((settings.revenue_owner, settings.locked_token_owner, settings.token0.price), (args.revenue_owner, new_owner.pubkey(), 4));
+
+    // the previous owner is out
+    assert_rejected(pause(&mut pool, &locked_token_owner, true).await, InstructionError::InvalidAccountData);
+
+    // 100 at a price of 4, plus 10%
+    pool.swap(100).await.unwrap();
+    assert_eq!(pool.savings().await.total_technical, 27);
+}
+
+//**************************************************************************************************
+//  test_batch_price_is_fixed, bids are settled and allocated at the price they were placed at
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_batch_price_is_fixed() {
+    let settings    = Settings { batch_end: 1, token0: Token { address: Pubkey::default(), price: 2 }, ..Settings::default() };
+    let args        = UpdateSettingsArgs { revenue_owner: Pubkey::new_unique(), locked_token_owner: Pubkey::new_unique(), price: 2 };
+
+    assert_eq!(settings.update(&UpdateSettingsArgs { price: 3, ..args }), Err(ProgramError::InvalidInstructionData));
+    assert_eq!(settings.update(&args).map(|settings| settings.revenue_owner), Ok(args.revenue_owner));
+}
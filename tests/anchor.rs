@@ -0,0 +1,149 @@
+mod common;
+
+use {
+    borsh::{
+        BorshDeserialize,
+        BorshSerialize,
+    },
+    solana_program::{
+        hash::hash,
+        instruction::AccountMeta,
+        program_pack::Pack,
+        pubkey::Pubkey,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+    kaizen::{
+        instruction::{KaizenInstruction, SIGHASHES},
+        state::{
+            BidArgs,
+            EarlyWithdrawArgs,
+            FillOrderArgs,
+            PauseArgs,
+            PlaceOrderArgs,
+            Savings,
+            Settings,
+            StakeArgs,
+            StakePoolArgs,
+            SwapArgs,
+            TransferSavingsArgs,
+            UpdateSettingsArgs,
+            WithdrawArgs,
+        },
+    },
+    common::*,
+};
+
+//==================================================================================================
+//  instructions, one per tag with nonzero args
+//--------------------------------------------------------------------------------------------------
+fn instructions() -> Vec<KaizenInstruction> {
+    let key = Pubkey::new_from_array([7; 32]);
+
+    vec![
+        KaizenInstruction::Initialize(Settings { is_initialized: true, revenue_owner: key, supply_total: 1, paused: true, ..Settings::default() }),
+        KaizenInstruction::Swap(SwapArgs { amount: 1 }),
+        KaizenInstruction::Withdraw(WithdrawArgs { amount: 2 }),
+        KaizenInstruction::PlaceOrder(PlaceOrderArgs { amount: 3, price: 4, expiry_timestamp: 5 }),
+        KaizenInstruction::FillOrder(FillOrderArgs { amount: 6 }),
+        KaizenInstruction::CancelOrder,
+        KaizenInstruction::TransferSavings(TransferSavingsArgs { amount: 7 }),
+        KaizenInstruction::CloseSavings,
+        KaizenInstruction::ClosePool,
+        KaizenInstruction::Refund,
+        KaizenInstruction::CollectRevenue,
+        KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs { amount: 8 }),
+        KaizenInstruction::InitializeStakePool(StakePoolArgs { reward_rate: 9, reward_end: 10 }),
+        KaizenInstruction::Stake(StakeArgs { amount: 11 }),
+        KaizenInstruction::Unstake(StakeArgs { amount: 12 }),
+        KaizenInstruction::ClaimRewards,
+        KaizenInstruction::PlaceBid(BidArgs { amount: 13 }),
+        KaizenInstruction::Settle,
+        KaizenInstruction::ClaimAllocation,
+        KaizenInstruction::ClaimRefund,
+        KaizenInstruction::RegisterReferrer,
+        KaizenInstruction::Migrate,
+        KaizenInstruction::UpdateSettings(UpdateSettingsArgs { revenue_owner: key, locked_token_owner: key, price: 14 }),
+        KaizenInstruction::Pause(PauseArgs { paused: true }),
+    ]
+}
+
+//==================================================================================================
+//  anchor_data, the sighash of `_name` followed by the Borsh args of `_instruction`
+//--------------------------------------------------------------------------------------------------
+fn anchor_data(_name: &str, _instruction: &KaizenInstruction) -> Vec<u8> {
+    let (_, sighash, _) = SIGHASHES.iter().find(|(name, _, _)| *name == _name).unwrap();
+    [&sighash[..], &_instruction.try_to_vec().unwrap()[1..]].concat()
+}
+
+//**************************************************************************************************
+//  test_sighashes, Anchor's global namespace, one per name
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_sighashes() {
+    for (name, sighash, _) in SIGHASHES.iter() {
+        assert_eq!(hash(format!("global:{}", name).as_bytes()).to_bytes()[..8], sighash[..], "{}", name);
+    }
+
+    for (index, (name, sighash, _)) in SIGHASHES.iter().enumerate() {
+        assert!(SIGHASHES[index + 1..].iter().all(|(other_name, other_sighash, _)| other_name != name && other_sighash != sighash), "{}", name);
+    }
+}
+
+//**************************************************************************************************
+//  test_unpack, both encodings of every instruction
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_unpack() {
+    let instructions = instructions();
+
+    for (name, _, tag) in SIGHASHES.iter() {
+        let instruction = &instructions[*tag as usize];
+
+        assert_eq!(instruction.try_to_vec().unwrap()[0], *tag, "{}", name);
+        assert_eq!(KaizenInstruction::unpack(&instruction.try_to_vec().unwrap()).as_ref(), Ok(instruction), "{}", name);
+        assert_eq!(KaizenInstruction::unpack(&anchor_data(name, instruction)).as_ref(), Ok(instruction), "{}", name);
+    }
+
+    assert!(KaizenInstruction::unpack(&[]).is_err());
+    assert!(KaizenInstruction::unpack(&[0xff; 8]).is_err());
+}
+
+//**************************************************************************************************
+//  test_unpack_tagged_like_a_sighash, tagged data starting with a sighash is read as tagged
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_unpack_tagged_like_a_sighash() {
+    for (name, sighash, _) in SIGHASHES.iter() {
+        for len in 0..=Settings::LEN {
+            let data = [&sighash[..], &vec![0; len]].concat();
+
+            if let Ok(instruction) = KaizenInstruction::try_from_slice(&data) {
+                assert_eq!(KaizenInstruction::unpack(&data), Ok(instruction), "{} {}", name, len);
+            }
+        }
+    }
+}
+
+//**************************************************************************************************
+//  test_anchor_swap, the same Swap from an Anchor client and from a deployed client
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_anchor_swap() {
+    let mut pool = Pool::start(Savings::LEN).await;
+    pool.initialize(1_000_000, 60).await.unwrap();
+
+    let swap        = KaizenInstruction::Swap(SwapArgs { amount: 1_000 });
+    let accounts    = vec![
+        AccountMeta::new(pool.settings, false),
+        AccountMeta::new(pool.savings, false),
+        AccountMeta::new_readonly(pool.context.payer.pubkey(), true),
+    ];
+
+    pool.process_data(anchor_data("swap", &swap), accounts, &[]).await.unwrap();
+    let anchor_swapped = pool.savings().await.total_technical;
+    assert!(anchor_swapped != 0);
+
+    pool.swap(1_000).await.unwrap();
+    assert_eq!(pool.savings().await.total_technical, 2 * anchor_swapped);
+}
@@ -0,0 +1,294 @@
+mod common;
+
+use {
+    solana_program::{
+        instruction::{
+            AccountMeta,
+            InstructionError,
+        },
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_program_test::tokio,
+    solana_sdk::{
+        signature::{
+            Keypair,
+            Signer,
+        },
+        transport::TransportError,
+    },
+    kaizen::{
+        instruction::KaizenInstruction,
+        state::{
+            Bid,
+            BidArgs,
+            Savings,
+            Settings,
+        },
+    },
+    common::*,
+};
+
+//**************************************************************************************************
+//  Bidder, a wallet with its Savings and Bid accounts, and a spare empty Savings account
+//--------------------------------------------------------------------------------------------------
+struct Bidder {
+    owner:      Keypair,
+    savings:    Pubkey,
+    bid:        Pubkey,
+    spare:      Pubkey,
+}
+
+//**************************************************************************************************
+//  Batch, a batch auction of 30 locked tokens at a price of 2 plus 10% interest, bidding for a
+//  minute and unlocking in an hour
+//--------------------------------------------------------------------------------------------------
+struct Batch {
+    pool:           Pool,
+    revenue_owner:  Keypair,
+    bidders:        Vec<Bidder>,
+    batch_end:      u64,
+    unlock:         u64,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Batch {
+
+    //==================================================================================================
+    //  start, with `_bidders` wallets
+    //--------------------------------------------------------------------------------------------------
+    async fn start(_bidders: usize) -> Self {
+        let revenue_owner   = Keypair::new();
+        let bidders: Vec<_> = (0.._bidders).map(|_| Bidder { owner: Keypair::new(), savings: Pubkey::new_unique(), bid: Pubkey::new_unique(), spare: Pubkey::new_unique() }).collect();
+        let mut accounts    = vec![(revenue_owner.pubkey(), wallet_account())];
+
+        for bidder in bidders.iter() {
+            accounts.push((bidder.owner.pubkey(),   wallet_account()));
+            accounts.push((bidder.savings,          program_account(Savings::LEN, SAVINGS_LAMPORTS)));
+            accounts.push((bidder.bid,              program_account(Bid::LEN, Rent::default().minimum_balance(Bid::LEN))));
+            accounts.push((bidder.spare,            program_account(Savings::LEN, Rent::default().minimum_balance(Savings::LEN))));
+        }
+
+        let mut pool    = Pool::start_with(Savings::LEN, &accounts).await;
+        let settings    = pool.pool_settings(30, 3_600).await;
+        let batch_end   = settings.unlock_timestamp - 3_540;
+
+        pool.initialize_account(pool.settings, Settings {
+            revenue_owner:  revenue_owner.pubkey(),
+            batch_end,
+            ..settings
+        })
+        .await
+        .unwrap();
+
+        Self { pool, revenue_owner, bidders, batch_end, unlock: settings.unlock_timestamp }
+    }
+    //==================================================================================================
+    //  bidder_instruction, `_instruction` on the Bid and Savings accounts of bidder `_bidder`
+    //--------------------------------------------------------------------------------------------------
+    async fn bidder_instruction(&mut self, _bidder: usize, _instruction: KaizenInstruction) -> Result<(), TransportError> {
+        let savings = self.bidders[_bidder].savings;
+        self.bidder_instruction_to(_bidder, savings, _instruction).await
+    }
+    //==================================================================================================
+    //  bidder_instruction_to, `_instruction` on the Bid account of bidder `_bidder` and the Savings
+    //  account `_savings`
+    //--------------------------------------------------------------------------------------------------
+    async fn bidder_instruction_to(&mut self, _bidder: usize, _savings: Pubkey, _instruction: KaizenInstruction) -> Result<(), TransportError> {
+        let bidder  = &self.bidders[_bidder];
+        let owner   = Keypair::from_bytes(&bidder.owner.to_bytes()).unwrap();
+        let bid     = bidder.bid;
+
+        self.pool.process_signed(_instruction, vec![
+            AccountMeta::new(self.pool.settings, false),
+            AccountMeta::new(bid, false),
+            AccountMeta::new(_savings, false),
+            AccountMeta::new_readonly(owner.pubkey(), true),
+        ],
+        &[&owner]).await
+    }
+    //==================================================================================================
+    //  place_bid
+    //--------------------------------------------------------------------------------------------------
+    async fn place_bid(&mut self, _bidder: usize, _amount: u64) -> Result<(), TransportError> {
+        self.bidder_instruction(_bidder, KaizenInstruction::PlaceBid(BidArgs { amount: _amount })).await
+    }
+    //==================================================================================================
+    //  settle
+    //--------------------------------------------------------------------------------------------------
+    async fn settle(&mut self) -> Result<(), TransportError> {
+        self.pool.process(KaizenInstruction::Settle, vec![
+            AccountMeta::new(self.pool.settings, false),
+        ]).await
+    }
+    //==================================================================================================
+    //  collect_revenue, returns the token0 collected
+    //--------------------------------------------------------------------------------------------------
+    async fn collect_revenue(&mut self) -> Result<u64, TransportError> {
+        let revenue_owner   = self.revenue_owner.pubkey();
+        let before          = self.pool.lamports(revenue_owner).await;
+        let signer          = Keypair::from_bytes(&self.revenue_owner.to_bytes()).unwrap();
+
+        self.pool.process_signed(KaizenInstruction::CollectRevenue, vec![
+            AccountMeta::new(self.pool.settings, false),
+            AccountMeta::new(revenue_owner, true),
+        ],
+        &[&signer]).await?;
+
+        Ok(self.pool.lamports(revenue_owner).await - before)
+    }
+    //==================================================================================================
+    //  settings
+    //--------------------------------------------------------------------------------------------------
+    async fn settings(&mut self) -> Settings {
+        self.pool.unpack(self.pool.settings).await
+    }
+    //==================================================================================================
+    //  savings, of bidder `_bidder`
+    //--------------------------------------------------------------------------------------------------
+    async fn savings(&mut self, _bidder: usize) -> Savings {
+        let savings = self.bidders[_bidder].savings;
+        self.pool.unpack(savings).await
+    }
+    //==================================================================================================
+    //  lamports, of the Savings account of bidder `_bidder`
+    //--------------------------------------------------------------------------------------------------
+    async fn lamports(&mut self, _bidder: usize) -> u64 {
+        let savings = self.bidders[_bidder].savings;
+        self.pool.lamports(savings).await
+    }
+}
+
+//**************************************************************************************************
+//  test_settle, demand and fill are fixed once bidding is over
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_settle() {
+    let mut batch = Batch::start(2).await;
+
+    batch.place_bid(0, 40).await.unwrap();
+    batch.place_bid(1, 20).await.unwrap();
+    assert_rejected(batch.settle().await, InstructionError::InvalidInstructionData);
+
+    let batch_end = batch.batch_end;
+    batch.pool.warp_past(batch_end).await;
+
+    assert_rejected(batch.place_bid(1, 10).await, InstructionError::InvalidInstructionData);
+    batch.settle().await.unwrap();
+
+    // 60 paid for 66 tokens with interest, 30 on sale
+    let settings = batch.settings().await;
+    assert_eq!((settings.batch_bids, settings.batch_demand, settings.batch_filled, settings.batch_settled), (60, 33, 30, true));
+}
+
+//**************************************************************************************************
+//  test_claims, allocations and refunds are paid once each, pro rata to the fill
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_claims() {
+    let mut batch = Batch::start(2).await;
+
+    batch.place_bid(0, 40).await.unwrap();
+    batch.place_bid(1, 20).await.unwrap();
+    assert_rejected(batch.bidder_instruction(0, KaizenInstruction::ClaimAllocation).await, InstructionError::InvalidInstructionData);
+    assert_rejected(batch.bidder_instruction(0, KaizenInstruction::ClaimRefund).await, InstructionError::InvalidInstructionData);
+
+    let batch_end = batch.batch_end;
+    batch.pool.warp_past(batch_end).await;
+    batch.settle().await.unwrap();
+
+    // 20 of the 22 tokens asked for, 36 of the 40 paid used
+    batch.bidder_instruction(0, KaizenInstruction::ClaimAllocation).await.unwrap();
+
+    let savings = batch.savings(0).await;
+    assert_eq!((savings.total_technical, savings.total_paid), (20, 36));
+
+    let lamports = batch.lamports(0).await;
+    batch.bidder_instruction(0, KaizenInstruction::ClaimRefund).await.unwrap();
+    assert_eq!(batch.lamports(0).await, lamports + 4);
+
+    // the refund first, 18 of the 20 paid used
+    let lamports = batch.lamports(1).await;
+    batch.bidder_instruction(1, KaizenInstruction::ClaimRefund).await.unwrap();
+    batch.bidder_instruction(1, KaizenInstruction::ClaimAllocation).await.unwrap();
+    assert_eq!(batch.lamports(1).await, lamports + 2);
+
+    let settings = batch.settings().await;
+    assert_eq!((settings.supply_locked, settings.total_raised, settings.batch_bids), (30, 54, 0));
+
+    // on a later bank, so the same claims are not duplicate transactions
+    let now = batch.pool.now().await;
+    batch.pool.warp_past(now).await;
+
+    assert_rejected(batch.bidder_instruction(0, KaizenInstruction::ClaimAllocation).await, InstructionError::InvalidInstructionData);
+    assert_rejected(batch.bidder_instruction(0, KaizenInstruction::ClaimRefund).await, InstructionError::InvalidInstructionData);
+}
+
+//**************************************************************************************************
+//  test_claims_after_close_savings, a bidder who closed the Savings account the bid was paid from
+//  claims to an empty one
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_claims_after_close_savings() {
+    let mut batch = Batch::start(2).await;
+
+    batch.place_bid(0, 40).await.unwrap();
+    batch.place_bid(1, 20).await.unwrap();
+
+    let bidder  = &batch.bidders[0];
+    let spare   = bidder.spare;
+    batch.pool.process_signed(KaizenInstruction::CloseSavings, vec![
+        AccountMeta::new(bidder.savings, false),
+        AccountMeta::new(bidder.owner.pubkey(), true),
+    ],
+    &[&bidder.owner]).await.unwrap();
+
+    let batch_end = batch.batch_end;
+    batch.pool.warp_past(batch_end).await;
+    batch.settle().await.unwrap();
+
+    // not to the other bidder's position
+    let other_savings = batch.bidders[1].savings;
+    assert_rejected(batch.bidder_instruction_to(0, other_savings, KaizenInstruction::ClaimAllocation).await, InstructionError::InvalidAccountData);
+    assert_rejected(batch.bidder_instruction_to(0, other_savings, KaizenInstruction::ClaimRefund).await, InstructionError::InvalidAccountData);
+
+    let lamports = batch.pool.lamports(spare).await;
+    batch.bidder_instruction_to(0, spare, KaizenInstruction::ClaimRefund).await.unwrap();
+    batch.bidder_instruction_to(0, spare, KaizenInstruction::ClaimAllocation).await.unwrap();
+
+    let savings: Savings = batch.pool.unpack(spare).await;
+    assert_eq!((savings.total_technical, savings.total_paid), (20, 36));
+    assert_eq!((savings.owner, savings.settings), (batch.bidders[0].owner.pubkey(), batch.pool.settings));
+    assert_eq!(batch.pool.lamports(spare).await, lamports + 4);
+}
+
+//**************************************************************************************************
+//  test_collect_revenue_holds_back_bids, bids not claimed yet stay in the pool, before and after
+//  settlement
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_collect_revenue_holds_back_bids() {
+    let mut batch = Batch::start(2).await;
+
+    batch.place_bid(0, 40).await.unwrap();
+    batch.place_bid(1, 20).await.unwrap();
+    assert_eq!(batch.collect_revenue().await.unwrap(), 0);
+
+    let unlock = batch.unlock;
+    batch.pool.warp_past(unlock).await;
+    batch.settle().await.unwrap();
+
+    // only the first bidder has claimed, its 36 used are revenue once unlocked
+    batch.bidder_instruction(0, KaizenInstruction::ClaimAllocation).await.unwrap();
+    assert_eq!(batch.collect_revenue().await.unwrap(), 36);
+
+    batch.bidder_instruction(0, KaizenInstruction::ClaimRefund).await.unwrap();
+    batch.bidder_instruction(1, KaizenInstruction::ClaimRefund).await.unwrap();
+    batch.bidder_instruction(1, KaizenInstruction::ClaimAllocation).await.unwrap();
+
+    let now = batch.pool.now().await;
+    batch.pool.warp_past(now).await;
+    assert_eq!(batch.collect_revenue().await.unwrap(), 18);
+}
@@ -0,0 +1,198 @@
+#![cfg(all(feature = "client", feature = "json"))]
+
+mod common;
+
+use {
+    borsh::BorshSerialize,
+    solana_program::{
+        hash::hash,
+        instruction::Instruction,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::{
+        Keypair,
+        Signer,
+    },
+    kaizen::{
+        client,
+        id,
+        instruction::KaizenInstruction,
+        interface::interface,
+        state::{
+            BidArgs,
+            EarlyWithdrawArgs,
+            FillOrderArgs,
+            PauseArgs,
+            PlaceOrderArgs,
+            Referrer,
+            Savings,
+            Settings,
+            StakeArgs,
+            StakePoolArgs,
+            SwapArgs,
+            TransferSavingsArgs,
+            UpdateSettingsArgs,
+            WithdrawArgs,
+        },
+    },
+    common::*,
+};
+
+//==================================================================================================
+//  key, the account named `_name` in the interface description
+//--------------------------------------------------------------------------------------------------
+fn key(_name: &str) -> Pubkey {
+    Pubkey::new_from_array(hash(_name.as_bytes()).to_bytes())
+}
+
+//==================================================================================================
+//  built, every builder called with the accounts named as in the interface description, along
+//  with its interface name and the instruction it should encode
+//--------------------------------------------------------------------------------------------------
+fn built() -> Vec<(&'static str, Instruction, KaizenInstruction)> {
+    let p           = id();
+    let k           = key;
+    let settings    = Settings { is_initialized: true, supply_total: 1, ..Settings::default() };
+    let place_order = PlaceOrderArgs { amount: 3, price: 4, expiry_timestamp: 5 };
+    let stake_pool  = StakePoolArgs { reward_rate: 9, reward_end: 10 };
+    let update      = UpdateSettingsArgs { revenue_owner: k("revenue_owner"), locked_token_owner: k("locked_token_owner"), price: 14 };
+
+    vec![
+        ("Initialize",          client::initialize(&p, &k("settings"), &settings),                                                                          KaizenInstruction::Initialize(settings)),
+        ("Swap",                client::swap(&p, &k("settings"), &k("savings"), &k("owner"), 1, None),                                                      KaizenInstruction::Swap(SwapArgs { amount: 1 })),
+        ("Swap",                client::swap(&p, &k("settings"), &k("savings"), &k("owner"), 1, Some((&k("referrer"), &k("referrer_to")))),                KaizenInstruction::Swap(SwapArgs { amount: 1 })),
+        ("Withdraw",            client::withdraw(&p, &k("settings"), &k("savings"), 2),                                                                     KaizenInstruction::Withdraw(WithdrawArgs { amount: 2 })),
+        ("PlaceOrder",          client::place_order(&p, &k("settings"), &k("order"), &k("savings"), &k("owner"), place_order),                              KaizenInstruction::PlaceOrder(place_order)),
+        ("FillOrder",           client::fill_order(&p, &k("settings"), &k("order"), &k("savings"), &k("owner"), &k("seller"), 6),                           KaizenInstruction::FillOrder(FillOrderArgs { amount: 6 })),
+        ("CancelOrder",         client::cancel_order(&p, &k("settings"), &k("order"), &k("savings"), &k("seller"), false),                                  KaizenInstruction::CancelOrder),
+        ("TransferSavings",     client::transfer_savings(&p, &k("settings"), &k("savings_from"), &k("owner"), &k("savings_to"), &k("receiver"), 7),         KaizenInstruction::TransferSavings(TransferSavingsArgs { amount: 7 })),
+        ("CloseSavings",        client::close_savings(&p, &k("savings"), &k("owner")),                                                                      KaizenInstruction::CloseSavings),
+        ("ClosePool",           client::close_pool(&p, &k("settings"), &k("locked_token_owner")),                                                           KaizenInstruction::ClosePool),
+        ("Refund",              client::refund(&p, &k("settings"), &k("savings")),                                                                          KaizenInstruction::Refund),
+        ("CollectRevenue",      client::collect_revenue(&p, &k("settings"), &k("revenue_owner")),                                                           KaizenInstruction::CollectRevenue),
+        ("EarlyWithdraw",       client::early_withdraw(&p, &k("settings"), &k("savings"), &k("owner"), 8),                                                  KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs { amount: 8 })),
+        ("InitializeStakePool", client::initialize_stake_pool(&p, &k("settings"), &k("stake_pool"), &k("authority"), stake_pool),                           KaizenInstruction::InitializeStakePool(stake_pool)),
+        ("Stake",               client::stake(&p, &k("settings"), &k("stake_pool"), &k("stake_account"), &k("savings"), &k("owner"), 11),                   KaizenInstruction::Stake(StakeArgs { amount: 11 })),
+        ("Unstake",             client::unstake(&p, &k("settings"), &k("stake_pool"), &k("stake_account"), &k("savings"), &k("owner"), 12),                 KaizenInstruction::Unstake(StakeArgs { amount: 12 })),
+        ("ClaimRewards",        client::claim_rewards(&p, &k("settings"), &k("stake_pool"), &k("stake_account"), &k("owner")),                              KaizenInstruction::ClaimRewards),
+        ("PlaceBid",            client::place_bid(&p, &k("settings"), &k("bid"), &k("savings"), &k("owner"), 13),                                          KaizenInstruction::PlaceBid(BidArgs { amount: 13 })),
+        ("Settle",              client::settle(&p, &k("settings")),                                                                                         KaizenInstruction::Settle),
+        ("ClaimAllocation",     client::claim_allocation(&p, &k("settings"), &k("bid"), &k("savings"), &k("owner")),                                        KaizenInstruction::ClaimAllocation),
+        ("ClaimRefund",         client::claim_refund(&p, &k("settings"), &k("bid"), &k("savings"), &k("owner")),                                            KaizenInstruction::ClaimRefund),
+        ("RegisterReferrer",    client::register_referrer(&p, &k("settings"), &k("referrer"), &k("owner")),                                                 KaizenInstruction::RegisterReferrer),
+        ("MigrateSettings",     client::migrate_settings(&p, &k("legacy_settings"), &k("settings"), &k("locked_token_owner")),                              KaizenInstruction::Migrate),
+        ("MigrateSavings",      client::migrate_savings(&p, &k("legacy_savings"), &k("savings"), &k("owner"), &k("settings"), &k("locked_token_owner")),    KaizenInstruction::Migrate),
+        ("UpdateSettings",      client::update_settings(&p, &k("settings"), &k("locked_token_owner"), update),                                              KaizenInstruction::UpdateSettings(update)),
+        ("Pause",               client::pause(&p, &k("settings"), &k("locked_token_owner"), true),                                                          KaizenInstruction::Pause(PauseArgs { paused: true })),
+    ]
+    .into_iter()
+    .map(|(name, instruction, expected)| (name, instruction.unwrap(), expected))
+    .collect()
+}
+
+//**************************************************************************************************
+//  test_builders_match_interface, accounts in the described order with the described flags, the
+//  optional ones all or none, and data the processor decodes to the expected instruction
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_builders_match_interface() {
+    let interface   = interface();
+    let built       = built();
+
+    for (name, instruction, expected) in built.iter() {
+        let definition  = interface.instructions.iter().find(|definition| definition.name == *name).unwrap();
+        let required    = definition.accounts.iter().filter(|account| !account.optional).count();
+
+        assert_eq!(instruction.program_id, id(), "{}", name);
+        assert_eq!(instruction.data, expected.try_to_vec().unwrap(), "{}", name);
+        assert_eq!(instruction.data[0], definition.tag, "{}", name);
+        assert_eq!(KaizenInstruction::unpack(&instruction.data).as_ref(), Ok(expected), "{}", name);
+
+        assert!(instruction.accounts.len() == required || instruction.accounts.len() == definition.accounts.len(), "{}", name);
+
+        for (meta, account) in instruction.accounts.iter().zip(definition.accounts.iter()) {
+            assert_eq!(meta.pubkey, key(account.name), "{} {}", name, account.name);
+            assert_eq!(meta.is_writable, account.writable, "{} {}", name, account.name);
+            assert_eq!(meta.is_signer, account.signer, "{} {}", name, account.name);
+        }
+    }
+
+    // every described instruction has a builder
+    for definition in interface.instructions.iter() {
+        assert!(built.iter().any(|(name, _, _)| *name == definition.name), "{}", definition.name);
+    }
+}
+
+//**************************************************************************************************
+//  test_cancel_order_seller_signs, the seller's signature is the only difference
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_cancel_order_seller_signs() {
+    let unsigned    = client::cancel_order(&id(), &key("settings"), &key("order"), &key("savings"), &key("seller"), false).unwrap();
+    let mut signed  = client::cancel_order(&id(), &key("settings"), &key("order"), &key("savings"), &key("seller"), true).unwrap();
+
+    assert!(signed.accounts[3].is_signer);
+    signed.accounts[3].is_signer = false;
+    assert_eq!(signed, unsigned);
+}
+
+//**************************************************************************************************
+//  test_builders_drive_processor, a pool set up, bought into with and without a referrer, exited
+//  and administered through the builders only
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_builders_drive_processor() {
+    let locked_token_owner  = Keypair::new();
+    let referrer_owner      = Keypair::new();
+    let referrer            = Pubkey::new_unique();
+    let referrer_savings    = Pubkey::new_unique();
+    let mut pool            = Pool::start_with(Savings::LEN, &[
+        (referrer,          program_account(Referrer::LEN, Rent::default().minimum_balance(Referrer::LEN))),
+        (referrer_savings,  program_account(Savings::LEN, Rent::default().minimum_balance(Savings::LEN))),
+    ]).await;
+
+    let program_id  = id();
+    let (settings, savings, owner) = (pool.settings, pool.savings, pool.context.payer.pubkey());
+    let pool_settings = pool.pool_settings(1_000_000, 3_600).await;
+
+    let run = |instruction: Instruction| (instruction.data, instruction.accounts);
+
+    let (data, accounts) = run(client::initialize(&program_id, &settings, &Settings {
+        locked_token_owner:     locked_token_owner.pubkey(),
+        penalty_basis_points:   1_000,
+        referral_basis_points:  1_000,
+        referral_bonus:         true,
+        ..pool_settings
+    }).unwrap());
+    pool.process_data(data, accounts, &[]).await.unwrap();
+
+    let (data, accounts) = run(client::swap(&program_id, &settings, &savings, &owner, 100, None).unwrap());
+    pool.process_data(data, accounts, &[]).await.unwrap();
+    assert_eq!(pool.savings().await.total_technical, 55);
+
+    let (data, accounts) = run(client::register_referrer(&program_id, &settings, &referrer, &referrer_owner.pubkey()).unwrap());
+    pool.process_data(data, accounts, &[&referrer_owner]).await.unwrap();
+
+    let (data, accounts) = run(client::swap(&program_id, &settings, &savings, &owner, 100, Some((&referrer, &referrer_savings))).unwrap());
+    pool.process_data(data, accounts, &[]).await.unwrap();
+    assert_eq!(pool.savings().await.total_technical, 110);
+    assert_eq!(pool.unpack::<Savings>(referrer_savings).await.total_technical, 5);
+
+    let (data, accounts) = run(client::early_withdraw(&program_id, &settings, &savings, &owner, 55).unwrap());
+    pool.process_data(data, accounts, &[]).await.unwrap();
+    assert_eq!(pool.savings().await.total_technical, 55);
+
+    let (data, accounts) = run(client::pause(&program_id, &settings, &locked_token_owner.pubkey(), true).unwrap());
+    pool.process_data(data, accounts, &[&locked_token_owner]).await.unwrap();
+    assert!(pool.unpack::<Settings>(settings).await.paused);
+
+    let update = UpdateSettingsArgs { revenue_owner: Pubkey::new_unique(), locked_token_owner: locked_token_owner.pubkey(), price: 3 };
+    let (data, accounts) = run(client::update_settings(&program_id, &settings, &locked_token_owner.pubkey(), update).unwrap());
+    pool.process_data(data, accounts, &[&locked_token_owner]).await.unwrap();
+
+    let updated: Settings = pool.unpack(settings).await;
+    assert_eq!((updated.revenue_owner, updated.token0.price), (update.revenue_owner, 3));
+}
@@ -0,0 +1,256 @@
+// every test file uses its own part of the harness
+#![allow(dead_code)]
+
+use {
+    solana_program::{
+        clock::Clock,
+        instruction::{
+            AccountMeta,
+            Instruction as SolanaProgramInstruction,
+            InstructionError,
+        },
+        program_pack::{
+            IsInitialized,
+            Pack,
+        },
+        pubkey::Pubkey,
+        rent::Rent,
+        system_program,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        signature::{
+            Keypair,
+            Signer,
+        },
+        transaction::{
+            Transaction,
+            TransactionError,
+        },
+        transport::TransportError,
+    },
+    kaizen::{
+        *,
+        instruction::KaizenInstruction,
+        processor::Processor,
+        state::{
+            Savings,
+            Settings,
+            SwapArgs,
+            Token,
+            WithdrawArgs,
+        },
+    },
+    borsh::BorshSerialize,
+};
+
+pub const SAVINGS_LAMPORTS:  u64 = 1_000_000_000;
+pub const WALLET_LAMPORTS:   u64 = 1_000_000_000;
+
+//**************************************************************************************************
+//  Pool, a program test with an empty Settings account and one funded Savings account, run with the
+//  native processor so plain `cargo test` covers it
+//--------------------------------------------------------------------------------------------------
+pub struct Pool {
+    pub context:    ProgramTestContext,
+    pub settings:   Pubkey,
+    pub savings:    Pubkey,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Pool {
+
+    //==================================================================================================
+    //  start, `_savings_len` is the size of the Savings account
+    //--------------------------------------------------------------------------------------------------
+    pub async fn start(_savings_len: usize) -> Self {
+        Self::start_with(_savings_len, &[]).await
+    }
+    //==================================================================================================
+    //  start_with, `_accounts` are added next to the Settings and Savings accounts
+    //--------------------------------------------------------------------------------------------------
+    pub async fn start_with(_savings_len: usize, _accounts: &[(Pubkey, Account)]) -> Self {
+        let settings            = Pubkey::new_unique();
+        let savings             = Pubkey::new_unique();
+        let mut program_test    = ProgramTest::new(
+            "kaizen",
+            id(),
+            processor!(Processor::process_instruction),
+        );
+
+        program_test.add_account(settings, program_account(Settings::LEN, Rent::default().minimum_balance(Settings::LEN)));
+        program_test.add_account(savings, program_account(_savings_len, SAVINGS_LAMPORTS));
+
+        for (pubkey, account) in _accounts.iter() {
+            program_test.add_account(*pubkey, account.clone());
+        }
+
+        Self { context: program_test.start_with_context().await, settings, savings }
+    }
+    //==================================================================================================
+    //  now
+    //--------------------------------------------------------------------------------------------------
+    pub async fn now(&mut self) -> u64 {
+        self.context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64
+    }
+    //==================================================================================================
+    //  warp_past, moves the bank forward until its clock is past `_timestamp`
+    //--------------------------------------------------------------------------------------------------
+    pub async fn warp_past(&mut self, _timestamp: u64) {
+        let mut slot = self.context.banks_client.get_root_slot().await.unwrap();
+
+        while self.now().await <= _timestamp {
+            slot += 1_000;
+            self.context.warp_to_slot(slot).unwrap();
+        }
+    }
+    //==================================================================================================
+    //  process, one instruction signed by the payer
+    //--------------------------------------------------------------------------------------------------
+    pub async fn process(&mut self, _instruction: KaizenInstruction, _accounts: Vec<AccountMeta>) -> Result<(), TransportError> {
+        self.process_signed(_instruction, _accounts, &[]).await
+    }
+    //==================================================================================================
+    //  process_signed, one instruction signed by the payer and `_signers`
+    //--------------------------------------------------------------------------------------------------
+    pub async fn process_signed(&mut self, _instruction: KaizenInstruction, _accounts: Vec<AccountMeta>, _signers: &[&Keypair]) -> Result<(), TransportError> {
+        self.process_data(_instruction.try_to_vec().unwrap(), _accounts, _signers).await
+    }
+    //==================================================================================================
+    //  process_data, one instruction given by its encoded data, signed by the payer and `_signers`
+    //--------------------------------------------------------------------------------------------------
+    pub async fn process_data(&mut self, _data: Vec<u8>, _accounts: Vec<AccountMeta>, _signers: &[&Keypair]) -> Result<(), TransportError> {
+        let last_blockhash  = self.context.banks_client.get_recent_blockhash().await.unwrap();
+        let mut transaction = Transaction::new_with_payer(
+            &[SolanaProgramInstruction {
+                program_id: id(),
+                accounts:   _accounts,
+                data:       _data,
+            }],
+            Some(&self.context.payer.pubkey()),
+        );
+
+        let mut signers = vec![&self.context.payer];
+        signers.extend_from_slice(_signers);
+
+        transaction.sign(&signers, last_blockhash);
+        self.context.banks_client.process_transaction(transaction).await
+    }
+    //==================================================================================================
+    //  pool_settings, the settings `initialize` uses, unlocking `_unlock_in` seconds from now
+    //--------------------------------------------------------------------------------------------------
+    pub async fn pool_settings(&mut self, _supply_total: u64, _unlock_in: u64) -> Settings {
+        Settings {
+            is_initialized:         true,
+            revenue_owner:          Pubkey::new_unique(),
+            interest_basis_points:  10,
+            locked_token:           Pubkey::new_unique(),
+            locked_token_owner:     Pubkey::new_unique(),
+            unlock_timestamp:       self.now().await + _unlock_in,
+            supply_total:           _supply_total,
+            token0:                 Token { address: Pubkey::new_unique(), price: 2 },
+            ..Settings::default()
+        }
+    }
+    //==================================================================================================
+    //  initialize, `_unlock_in` seconds from now
+    //--------------------------------------------------------------------------------------------------
+    pub async fn initialize(&mut self, _supply_total: u64, _unlock_in: u64) -> Result<(), TransportError> {
+        let settings = self.pool_settings(_supply_total, _unlock_in).await;
+        self.initialize_account(self.settings, settings).await
+    }
+    //==================================================================================================
+    //  initialize_account, `_settings` written to the Settings account `_account`
+    //--------------------------------------------------------------------------------------------------
+    pub async fn initialize_account(&mut self, _account: Pubkey, _settings: Settings) -> Result<(), TransportError> {
+        self.process(KaizenInstruction::Initialize(_settings), vec![
+            AccountMeta::new(_account, false),
+        ]).await
+    }
+    //==================================================================================================
+    //  swap
+    //--------------------------------------------------------------------------------------------------
+    pub async fn swap(&mut self, _amount: u64) -> Result<(), TransportError> {
+        let owner = self.context.payer.pubkey();
+
+        self.process(KaizenInstruction::Swap(SwapArgs { amount: _amount }), vec![
+            AccountMeta::new(self.settings, false),
+            AccountMeta::new(self.savings, false),
+            AccountMeta::new_readonly(owner, true),
+        ]).await
+    }
+    //==================================================================================================
+    //  swap_from, `_savings` owned by the `_owner` wallet
+    //--------------------------------------------------------------------------------------------------
+    pub async fn swap_from(&mut self, _savings: Pubkey, _owner: &Keypair, _amount: u64) -> Result<(), TransportError> {
+        self.process_signed(KaizenInstruction::Swap(SwapArgs { amount: _amount }), vec![
+            AccountMeta::new(self.settings, false),
+            AccountMeta::new(_savings, false),
+            AccountMeta::new_readonly(_owner.pubkey(), true),
+        ],
+        &[_owner]).await
+    }
+    //==================================================================================================
+    //  withdraw
+    //--------------------------------------------------------------------------------------------------
+    pub async fn withdraw(&mut self, _amount: u64) -> Result<(), TransportError> {
+        self.process(KaizenInstruction::Withdraw(WithdrawArgs { amount: _amount }), vec![
+            AccountMeta::new(self.settings, false),
+            AccountMeta::new(self.savings, false),
+        ]).await
+    }
+    //==================================================================================================
+    //  savings
+    //--------------------------------------------------------------------------------------------------
+    pub async fn savings(&mut self) -> Savings {
+        self.unpack(self.savings).await
+    }
+    //==================================================================================================
+    //  unpack, the state of `_account`
+    //--------------------------------------------------------------------------------------------------
+    pub async fn unpack<T: Pack + IsInitialized>(&mut self, _account: Pubkey) -> T {
+        let account = self.context.banks_client.get_account(_account).await.unwrap().unwrap();
+        T::unpack(&account.data).unwrap()
+    }
+    //==================================================================================================
+    //  lamports, zero for accounts that do not exist
+    //--------------------------------------------------------------------------------------------------
+    pub async fn lamports(&mut self, _account: Pubkey) -> u64 {
+        self.context.banks_client.get_account(_account).await.unwrap().map_or(0, |account| account.lamports)
+    }
+}
+
+//==================================================================================================
+//  program_account, zeroed data owned by the program
+//--------------------------------------------------------------------------------------------------
+pub fn program_account(_len: usize, _lamports: u64) -> Account {
+    Account {
+        owner:      id(),
+        lamports:   _lamports,
+        data:       vec![0; _len],
+        ..Account::default()
+    }
+}
+
+//==================================================================================================
+//  wallet_account, a funded system account, so it can be paid less than its rent
+//--------------------------------------------------------------------------------------------------
+pub fn wallet_account() -> Account {
+    Account {
+        owner:      system_program::id(),
+        lamports:   WALLET_LAMPORTS,
+        ..Account::default()
+    }
+}
+
+//==================================================================================================
+//  assert_rejected, the only instruction of the transaction failed with `_error`
+//--------------------------------------------------------------------------------------------------
+pub fn assert_rejected<T: std::fmt::Debug>(_result: Result<T, TransportError>, _error: InstructionError) {
+    match _result {
+        Err(TransportError::TransactionError(TransactionError::InstructionError(0, error))) => assert_eq!(error, _error),
+        result => panic!("expected {:?}, got {:?}", _error, result),
+    }
+}
@@ -0,0 +1,89 @@
+use {
+    anchor_lang::{AnchorSerialize, Discriminator},
+    solana_program::pubkey::Pubkey,
+    kaizen::event::*,
+};
+
+//==================================================================================================
+//  key, every byte `_byte`
+//--------------------------------------------------------------------------------------------------
+fn key(_byte: u8) -> Pubkey {
+    Pubkey::new_from_array([_byte; 32])
+}
+
+//==================================================================================================
+//  encode, what `emit!` logs before base64
+//--------------------------------------------------------------------------------------------------
+fn encode<T: Discriminator + AnchorSerialize>(_event: &T) -> Vec<u8> {
+    let mut data = T::discriminator().to_vec();
+    data.extend(AnchorSerialize::try_to_vec(_event).unwrap());
+    data
+}
+
+//==================================================================================================
+//  round_trip, decodes back to `_event` and to nothing once truncated or extended, returns the
+//  discriminator
+//--------------------------------------------------------------------------------------------------
+fn round_trip<T: Discriminator + AnchorSerialize + Clone>(_event: T, _variant: fn(T) -> KaizenEvent) -> [u8; 8] {
+    let data = encode(&_event);
+
+    assert_eq!(KaizenEvent::decode(&data), Some(_variant(_event.clone())));
+    assert_eq!(KaizenEvent::decode(&data[..data.len() - 1]), None);
+    assert_eq!(KaizenEvent::decode(&[&data[..], &[0]].concat()), None);
+
+    T::discriminator()
+}
+
+//**************************************************************************************************
+//  test_decode_round_trip, every event the program emits
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_decode_round_trip() {
+    let v = EVENT_VERSION;
+    let mut discriminators = vec![
+        round_trip(Initialized { version: v, settings: key(1), revenue_owner: key(2), locked_token: key(3), locked_token_owner: key(4), unlock_timestamp: 5, supply_total: 6 }, KaizenEvent::Initialized),
+        round_trip(Swapped { version: v, settings: key(1), savings: key(2), owner: key(3), amount: 4, custom_amount: 5, timestamp: 6 }, KaizenEvent::Swapped),
+        round_trip(Withdrawn { version: v, settings: key(1), savings: key(2), amount: 3, penalty_share: 4, timestamp: 5 }, KaizenEvent::Withdrawn),
+        round_trip(EarlyWithdrawn { version: v, settings: key(1), savings: key(2), amount: 3, payout: 4, timestamp: 5 }, KaizenEvent::EarlyWithdrawn),
+        round_trip(Refunded { version: v, settings: key(1), savings: key(2), amount: 3, timestamp: 4 }, KaizenEvent::Refunded),
+        round_trip(ReferrerRegistered { version: v, settings: key(1), referrer: key(2), owner: key(3) }, KaizenEvent::ReferrerRegistered),
+        round_trip(ReferrerCredited { version: v, settings: key(1), referrer: key(2), volume: 3, payment: 4, custom_bonus: 5 }, KaizenEvent::ReferrerCredited),
+        round_trip(OrderPlaced { version: v, settings: key(1), order: key(2), savings: key(3), seller: key(4), amount: 5, paid: 6, price: 7, expiry_timestamp: 8 }, KaizenEvent::OrderPlaced),
+        round_trip(OrderFilled { version: v, settings: key(1), order: key(2), savings: key(3), buyer: key(4), amount: 5, paid: 6, cost: 7 }, KaizenEvent::OrderFilled),
+        round_trip(OrderCancelled { version: v, settings: key(1), order: key(2), savings: key(3), amount: 4, paid: 5 }, KaizenEvent::OrderCancelled),
+        round_trip(SavingsTransferred { version: v, settings: key(1), from: key(2), to: key(3), receiver: key(4), amount: 5, paid: 6 }, KaizenEvent::SavingsTransferred),
+        round_trip(SavingsClosed { version: v, settings: key(1), savings: key(2), owner: key(3) }, KaizenEvent::SavingsClosed),
+        round_trip(RevenueCollected { version: v, settings: key(1), revenue_owner: key(2), amount: 3 }, KaizenEvent::RevenueCollected),
+        round_trip(PoolClosed { version: v, settings: key(1), locked_token_owner: key(2) }, KaizenEvent::PoolClosed),
+        round_trip(StakePoolInitialized { version: v, settings: key(1), stake_pool: key(2), reward_rate: 3, reward_end: 4 }, KaizenEvent::StakePoolInitialized),
+        round_trip(Staked { version: v, stake_pool: key(1), stake: key(2), savings: key(3), amount: 4, paid: 5 }, KaizenEvent::Staked),
+        round_trip(Unstaked { version: v, stake_pool: key(1), stake: key(2), savings: key(3), amount: 4, paid: 5 }, KaizenEvent::Unstaked),
+        round_trip(RewardsClaimed { version: v, stake_pool: key(1), stake: key(2), owner: key(3), amount: 4 }, KaizenEvent::RewardsClaimed),
+        round_trip(BidPlaced { version: v, settings: key(1), bid: key(2), savings: key(3), owner: key(4), amount: 5 }, KaizenEvent::BidPlaced),
+        round_trip(BatchSettled { version: v, settings: key(1), demand: 2, filled: 3 }, KaizenEvent::BatchSettled),
+        round_trip(AllocationClaimed { version: v, settings: key(1), bid: key(2), savings: key(3), custom_amount: 4, used: 5 }, KaizenEvent::AllocationClaimed),
+        round_trip(BidRefunded { version: v, settings: key(1), bid: key(2), savings: key(3), amount: 4 }, KaizenEvent::BidRefunded),
+        round_trip(Migrated { version: v, legacy: key(1), migrated: key(2) }, KaizenEvent::Migrated),
+        round_trip(SettingsUpdated { version: v, settings: key(1), revenue_owner: key(2), locked_token_owner: key(3), price: 4, paused: true }, KaizenEvent::SettingsUpdated),
+    ];
+
+    // one event per discriminator
+    let count = discriminators.len();
+    discriminators.sort_unstable();
+    discriminators.dedup();
+    assert_eq!(discriminators.len(), count);
+}
+
+//**************************************************************************************************
+//  test_decode_other_logs, short data and unknown discriminators
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_decode_other_logs() {
+    let mut data = encode(&PoolClosed { version: EVENT_VERSION, settings: key(1), locked_token_owner: key(2) });
+
+    assert_eq!(KaizenEvent::decode(&[]), None);
+    assert_eq!(KaizenEvent::decode(&data[..7]), None);
+
+    data[0] ^= 0xff;
+    assert_eq!(KaizenEvent::decode(&data), None);
+}
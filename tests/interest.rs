@@ -0,0 +1,141 @@
+use {
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    kaizen::state::{
+        Savings,
+        Settings,
+        Token,
+    },
+};
+
+const START:    u64 = 1_600_000_000;
+const LOCK:     u64 = Settings::SECONDS_PER_YEAR;
+const UNLOCK:   u64 = START + LOCK;
+
+//==================================================================================================
+//  swapped, a pool paying 10% a year locking for a year at a price of 1, and a position of
+//  1_000_000 bought at `START`, with 100_000 interest reserved
+//--------------------------------------------------------------------------------------------------
+fn swapped() -> (Settings, Savings) {
+    let mut settings    = Settings {
+        is_initialized:         true,
+        interest_basis_points:  10,
+        interest_apr:           true,
+        unlock_timestamp:       UNLOCK,
+        supply_total:           10_000_000,
+        token0:                 Token { address: Pubkey::new_unique(), price: 1 },
+        ..Settings::default()
+    };
+    let mut savings     = Savings::default();
+
+    settings.swap(&mut savings, 1_000_000, START, false).unwrap();
+
+    assert_eq!((savings.total_technical, savings.interest_reserved), (1_000_000, 100_000));
+    assert_eq!(settings.supply_locked, 1_100_000);
+    (settings, savings)
+}
+
+//**************************************************************************************************
+//  test_accrue_interest, pro rata to the time elapsed out of the lock, all of it by unlock
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_accrue_interest() {
+    let (settings, mut savings) = swapped();
+
+    savings.accrue_interest(&settings, START + LOCK / 4);
+    assert_eq!((savings.total_technical, savings.interest_reserved, savings.accrual_timestamp), (1_025_000, 75_000, START + LOCK / 4));
+
+    savings.accrue_interest(&settings, UNLOCK);
+    assert_eq!((savings.total_technical, savings.interest_reserved, savings.accrual_timestamp), (1_100_000, 0, UNLOCK));
+
+    // nothing accrues past unlock
+    savings.accrue_interest(&settings, UNLOCK + LOCK);
+    assert_eq!((savings.total_technical, savings.interest_reserved), (1_100_000, 0));
+}
+
+//**************************************************************************************************
+//  test_accrue_interest_twice, accruing again at the same time adds nothing, accruing in steps
+//  adds up to accruing once
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_accrue_interest_twice() {
+    let (settings, mut savings) = swapped();
+    let mut at_once             = savings;
+
+    savings.accrue_interest(&settings, START + LOCK / 4);
+    savings.accrue_interest(&settings, START + LOCK / 4);
+    assert_eq!((savings.total_technical, savings.interest_reserved), (1_025_000, 75_000));
+
+    // the first quarter is not accrued again
+    savings.accrue_interest(&settings, START + LOCK / 2);
+    assert_eq!((savings.total_technical, savings.interest_reserved), (1_050_000, 50_000));
+
+    // nor is the time before the last accrual
+    savings.accrue_interest(&settings, START + LOCK / 4);
+    assert_eq!((savings.total_technical, savings.interest_reserved), (1_050_000, 50_000));
+
+    savings.accrue_interest(&settings, UNLOCK);
+    at_once.accrue_interest(&settings, UNLOCK);
+    assert_eq!(savings, at_once);
+}
+
+//**************************************************************************************************
+//  test_withdraw_after_partial_accrual, an early exit a quarter into the lock gives back its share
+//  of the interest not accrued yet, the rest of the position accrues the remainder by unlock
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_withdraw_after_partial_accrual() {
+    let (mut settings, mut savings) = swapped();
+    settings.penalty_basis_points   = 1_000;
+
+    // half of the 1_025_000 accrued so far, paid for with half of the 1_000_000, less the 10% penalty
+    assert_eq!(settings.early_withdraw(&mut savings, 512_500, START + LOCK / 4), Ok(450_000));
+    assert_eq!((savings.total_technical, savings.interest_reserved, savings.total_paid), (512_500, 37_500, 500_000));
+    assert_eq!(settings.supply_locked, 550_000);
+
+    assert_eq!(settings.withdraw(&mut savings, 550_001, UNLOCK), Err(ProgramError::InvalidInstructionData));
+    assert_eq!(settings.withdraw(&mut savings, 550_000, UNLOCK), Ok(0));
+    assert_eq!((savings.total_technical, savings.interest_reserved, savings.total_original), (0, 0, 550_000));
+    assert_eq!(settings.supply_locked, 0);
+}
+
+//**************************************************************************************************
+//  test_transfer_halfway, half of a position transferred halfway through the lock takes half of the
+//  interest left with it, both halves end up with the same interest at unlock
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_transfer_halfway() {
+    let (settings, mut savings) = swapped();
+    let mut received            = Savings::default();
+
+    // half of the 1_050_000 accrued so far, paid for with half of the 1_000_000
+    assert_eq!(savings.transfer(&mut received, 525_000, &settings, START + LOCK / 2), Ok(500_000));
+    assert_eq!((savings.total_technical, savings.interest_reserved, savings.total_paid), (525_000, 25_000, 500_000));
+    assert_eq!((received.total_technical, received.interest_reserved, received.total_paid), (525_000, 25_000, 500_000));
+    assert_eq!(received.accrual_timestamp, START + LOCK / 2);
+
+    savings.accrue_interest(&settings, UNLOCK);
+    received.accrue_interest(&settings, UNLOCK);
+    assert_eq!((savings.total_technical, savings.interest_reserved), (550_000, 0));
+    assert_eq!((received.total_technical, received.interest_reserved), (550_000, 0));
+    assert_eq!(savings.total_technical + received.total_technical, settings.supply_locked);
+}
+
+//**************************************************************************************************
+//  test_split_after_accrual, orders and stakes carry no interest reserve, a position is split once
+//  its interest has accrued
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_split_after_accrual() {
+    let (settings, mut savings) = swapped();
+
+    savings.accrue_interest(&settings, START + LOCK / 2);
+    assert_eq!(savings.split(1), Err(ProgramError::InvalidInstructionData));
+    assert_eq!((savings.total_technical, savings.total_paid), (1_050_000, 1_000_000));
+
+    savings.accrue_interest(&settings, UNLOCK);
+    assert_eq!(savings.split(550_000), Ok(500_000));
+    assert_eq!((savings.total_technical, savings.total_paid), (550_000, 500_000));
+}
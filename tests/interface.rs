@@ -0,0 +1,243 @@
+#![cfg(feature = "json")]
+
+use {
+    anchor_lang::AnchorSerialize,
+    borsh::BorshSerialize,
+    serde_json::json,
+    solana_program::{
+        program_pack::Pack,
+        pubkey::Pubkey,
+    },
+    kaizen::{
+        event::{Migrated, OrderPlaced, Swapped, Withdrawn},
+        idl::{camel_case, idl},
+        instruction::{KaizenInstruction, SIGHASHES},
+        interface::{interface, Field},
+        state::{
+            BidArgs,
+            EarlyWithdrawArgs,
+            FillOrderArgs,
+            PauseArgs,
+            PlaceOrderArgs,
+            Settings,
+            StakeArgs,
+            StakePoolArgs,
+            SwapArgs,
+            Tier,
+            Token,
+            TransferSavingsArgs,
+            UpdateSettingsArgs,
+            WithdrawArgs,
+        },
+    },
+};
+
+//==================================================================================================
+//  end, the offset right after the last field
+//--------------------------------------------------------------------------------------------------
+fn end(_fields: &[Field]) -> usize {
+    _fields.last().map_or(0, |field| field.offset + field.size)
+}
+
+//==================================================================================================
+//  with_field, default Settings with only the field `_name` set, every byte of it nonzero
+//--------------------------------------------------------------------------------------------------
+fn with_field(_name: &str) -> Settings {
+    let key     = Pubkey::new_from_array([0xff; 32]);
+    let tier    = Tier { supply_end: u64::MAX, price: u64::MAX };
+    let base    = Settings::default();
+
+    match _name {
+        "is_initialized"        => Settings { is_initialized: true, ..base },
+        "revenue_owner"         => Settings { revenue_owner: key, ..base },
+        "interest_basis_points" => Settings { interest_basis_points: u32::MAX, ..base },
+        "locked_token"          => Settings { locked_token: key, ..base },
+        "locked_token_owner"    => Settings { locked_token_owner: key, ..base },
+        "unlock_timestamp"      => Settings { unlock_timestamp: u64::MAX, ..base },
+        "supply_total"          => Settings { supply_total: u64::MAX, ..base },
+        "supply_locked"         => Settings { supply_locked: u64::MAX, ..base },
+        "token0"                => Settings { token0: Token { address: key, price: u64::MAX }, ..base },
+        "soft_cap"              => Settings { soft_cap: u64::MAX, ..base },
+        "sale_end"              => Settings { sale_end: u64::MAX, ..base },
+        "total_raised"          => Settings { total_raised: u64::MAX, ..base },
+        "penalty_basis_points"  => Settings { penalty_basis_points: u32::MAX, ..base },
+        "penalty_to_holders"    => Settings { penalty_to_holders: true, ..base },
+        "penalty_pool"          => Settings { penalty_pool: u64::MAX, ..base },
+        "interest_apr"          => Settings { interest_apr: true, ..base },
+        "tiers"                 => Settings { tiers: [tier; 4], ..base },
+        "auction_start_price"   => Settings { auction_start_price: u64::MAX, ..base },
+        "auction_floor_price"   => Settings { auction_floor_price: u64::MAX, ..base },
+        "auction_start"         => Settings { auction_start: u64::MAX, ..base },
+        "auction_end"           => Settings { auction_end: u64::MAX, ..base },
+        "batch_end"             => Settings { batch_end: u64::MAX, ..base },
+        "batch_bids"            => Settings { batch_bids: u64::MAX, ..base },
+        "batch_demand"          => Settings { batch_demand: u64::MAX, ..base },
+        "batch_filled"          => Settings { batch_filled: u64::MAX, ..base },
+        "batch_settled"         => Settings { batch_settled: true, ..base },
+        "referral_basis_points" => Settings { referral_basis_points: u32::MAX, ..base },
+        "referral_bonus"        => Settings { referral_bonus: true, ..base },
+        "paused"                => Settings { paused: true, ..base },
+        _                       => panic!("no test value for Settings field {}", _name),
+    }
+}
+
+#[test]
+fn test_account_layouts() {
+    let interface = interface();
+
+    for account in interface.accounts.iter() {
+        assert_eq!(end(&account.fields), account.len, "{}", account.name);
+    }
+
+    for ty in interface.types.iter() {
+        assert_eq!(end(&ty.fields), ty.len, "{}", ty.name);
+    }
+
+    // the lengths derived from the fields are the ones `Pack` uses
+    let ty = |name: &str| interface.types.iter().find(|ty| ty.name == name).unwrap().len;
+    assert_eq!((ty("Settings"), ty("Token"), ty("Tier")), (Settings::BODY_LEN, Token::LEN, Tier::LEN));
+
+    // fields land where the description puts them
+    let settings    = Settings { supply_total: 0x0102_0304_0506_0708, referral_bonus: true, ..Settings::default() };
+    let mut data    = vec![0; Settings::LEN];
+    Settings::pack(settings, &mut data).unwrap();
+
+    let account = interface.accounts.iter().find(|account| account.name == "Settings").unwrap();
+    let field   = |name: &str| account.fields.iter().find(|field| field.name == name).unwrap().offset;
+
+    assert_eq!(data[field("account_type")], account.tag);
+    assert_eq!(Some(data[field("version")]), account.version);
+    assert_eq!(data[field("supply_total")..field("supply_total") + 8], settings.supply_total.to_le_bytes());
+    assert_eq!(data[field("referral_bonus")], 1);
+}
+
+#[test]
+fn test_settings_field_offsets() {
+    let interface   = interface();
+    let account     = interface.accounts.iter().find(|account| account.name == "Settings").unwrap();
+    let mut base    = vec![0; Settings::LEN];
+    Settings::pack(Settings::default(), &mut base).unwrap();
+
+    // each field packs into exactly the bytes the description gives it
+    for field in account.fields.iter().skip(2) {
+        let mut data = vec![0; Settings::LEN];
+        Settings::pack(with_field(field.name), &mut data).unwrap();
+
+        let changed: Vec<usize> = (0..Settings::LEN).filter(|&i| data[i] != base[i]).collect();
+        let expected: Vec<usize> = if field.ty == "bool" { vec![field.offset] } else { (field.offset..field.offset + field.size).collect() };
+
+        assert_eq!(changed, expected, "{}", field.name);
+    }
+}
+
+#[test]
+fn test_errors() {
+    let interface   = interface();
+    let names: Vec<&str> = interface.errors.iter().map(|error| error.name).collect();
+
+    // the signer and account count checks of the accounts structs, and the unpacking of zeroed accounts
+    for name in ["MissingRequiredSignature", "NotEnoughAccountKeys", "UninitializedAccount", "InvalidInstructionData"].iter() {
+        assert!(names.contains(name), "{}", name);
+    }
+
+    for error in interface.errors.iter() {
+        assert!(!error.when.is_empty(), "{}", error.name);
+        assert_ne!(error.code, 0, "{}", error.name);
+    }
+}
+
+#[test]
+fn test_instruction_tags_and_args() {
+    let interface       = interface();
+    let instructions    = [
+        ("Initialize",          KaizenInstruction::Initialize(Settings::default())),
+        ("Swap",                KaizenInstruction::Swap(SwapArgs::default())),
+        ("Withdraw",            KaizenInstruction::Withdraw(WithdrawArgs::default())),
+        ("PlaceOrder",          KaizenInstruction::PlaceOrder(PlaceOrderArgs::default())),
+        ("FillOrder",           KaizenInstruction::FillOrder(FillOrderArgs::default())),
+        ("CancelOrder",         KaizenInstruction::CancelOrder),
+        ("TransferSavings",     KaizenInstruction::TransferSavings(TransferSavingsArgs::default())),
+        ("CloseSavings",        KaizenInstruction::CloseSavings),
+        ("ClosePool",           KaizenInstruction::ClosePool),
+        ("Refund",              KaizenInstruction::Refund),
+        ("CollectRevenue",      KaizenInstruction::CollectRevenue),
+        ("EarlyWithdraw",       KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs::default())),
+        ("InitializeStakePool", KaizenInstruction::InitializeStakePool(StakePoolArgs::default())),
+        ("Stake",               KaizenInstruction::Stake(StakeArgs::default())),
+        ("Unstake",             KaizenInstruction::Unstake(StakeArgs::default())),
+        ("ClaimRewards",        KaizenInstruction::ClaimRewards),
+        ("PlaceBid",            KaizenInstruction::PlaceBid(BidArgs::default())),
+        ("Settle",              KaizenInstruction::Settle),
+        ("ClaimAllocation",     KaizenInstruction::ClaimAllocation),
+        ("ClaimRefund",         KaizenInstruction::ClaimRefund),
+        ("RegisterReferrer",    KaizenInstruction::RegisterReferrer),
+        ("MigrateSettings",     KaizenInstruction::Migrate),
+        ("MigrateSavings",      KaizenInstruction::Migrate),
+        ("UpdateSettings",      KaizenInstruction::UpdateSettings(UpdateSettingsArgs::default())),
+        ("Pause",               KaizenInstruction::Pause(PauseArgs::default())),
+    ];
+
+    assert_eq!(interface.instructions.len(), instructions.len());
+
+    for (name, instruction) in instructions.iter() {
+        let data        = BorshSerialize::try_to_vec(instruction).unwrap();
+        let description = interface.instructions.iter().find(|description| description.name == *name).unwrap();
+
+        assert_eq!(data[0], description.tag, "{}", name);
+        assert_eq!(data.len() - 1, end(&description.args), "{}", name);
+    }
+}
+
+#[test]
+fn test_event_layouts() {
+    let interface   = interface();
+    let event       = |name: &str| interface.events.iter().find(|event| event.name == name).unwrap().clone();
+
+    let swapped     = Swapped { version: 1, settings: Pubkey::default(), savings: Pubkey::default(), owner: Pubkey::default(), amount: 0, custom_amount: 0, timestamp: 0 };
+    let migrated    = Migrated { version: 1, legacy: Pubkey::default(), migrated: Pubkey::default() };
+    let withdrawn   = Withdrawn { version: 1, settings: Pubkey::default(), savings: Pubkey::default(), amount: 0, penalty_share: 0, timestamp: 0 };
+    let placed      = OrderPlaced {
+        version: 1, settings: Pubkey::default(), order: Pubkey::default(), savings: Pubkey::default(), seller: Pubkey::default(),
+        amount: 0, paid: 0, price: 0, expiry_timestamp: 0,
+    };
+
+    assert_eq!(AnchorSerialize::try_to_vec(&swapped).unwrap().len(), end(&event("Swapped").fields));
+    assert_eq!(AnchorSerialize::try_to_vec(&migrated).unwrap().len(), end(&event("Migrated").fields));
+    assert_eq!(AnchorSerialize::try_to_vec(&withdrawn).unwrap().len(), end(&event("Withdrawn").fields));
+    assert_eq!(AnchorSerialize::try_to_vec(&placed).unwrap().len(), end(&event("OrderPlaced").fields));
+}
+
+#[test]
+fn test_anchor_idl() {
+    let interface   = interface();
+    let idl         = serde_json::to_value(idl()).unwrap();
+
+    // every instruction is called by the camel case of the name its sighash is of
+    for (instruction, anchor_instruction) in interface.instructions.iter().zip(idl["instructions"].as_array().unwrap()) {
+        let (_, _, tag) = SIGHASHES.iter().find(|(name, _, _)| json!(camel_case(name)) == anchor_instruction["name"]).unwrap();
+        assert_eq!(*tag, instruction.tag, "{}", instruction.name);
+    }
+
+    assert_eq!(idl["instructions"][3], json!({
+        "name":     "placeOrder",
+        "accounts": [
+            { "name": "settings",   "isMut": false, "isSigner": false },
+            { "name": "order",      "isMut": true,  "isSigner": false },
+            { "name": "savings",    "isMut": true,  "isSigner": false },
+            { "name": "owner",      "isMut": false, "isSigner": true },
+        ],
+        "args":     [
+            { "name": "amount",             "type": "u64" },
+            { "name": "price",              "type": "u64" },
+            { "name": "expiryTimestamp",    "type": "u64" },
+        ],
+    }));
+    assert_eq!(idl["instructions"][1]["accounts"][3], json!({ "name": "referrer", "isMut": true, "isSigner": false, "isOptional": true }));
+
+    let settings = idl["types"].as_array().unwrap().iter().find(|ty| ty["name"] == "Settings").unwrap();
+    assert_eq!(settings["type"]["fields"][8], json!({ "name": "token0", "type": { "defined": "Token" } }));
+    assert_eq!(settings["type"]["fields"][16], json!({ "name": "tiers", "type": { "array": [{ "defined": "Tier" }, 4] } }));
+
+    assert_eq!(idl["events"][0]["fields"][0], json!({ "name": "version", "type": "u8", "index": false }));
+    assert_eq!(idl["metadata"]["address"], json!(kaizen::id().to_string()));
+}
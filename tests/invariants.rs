@@ -0,0 +1,170 @@
+use {
+    proptest::prelude::*,
+    solana_program::pubkey::Pubkey,
+    kaizen::state::{
+        Savings,
+        Settings,
+        Tier,
+        Token,
+    },
+};
+
+const START:    u64 = 1_600_000_000;
+const UNLOCK:   u64 = START + 10_000_000;
+const HOLDERS:  usize = 4;
+
+//**************************************************************************************************
+//  pool, fixed price or one early bird tier, instant or APR interest, early exits allowed
+//--------------------------------------------------------------------------------------------------
+fn pool() -> impl Strategy<Value = Settings> {
+    (1..u64::MAX, 1..1_000_000u64, 0..10_000u32, any::<bool>(), any::<bool>(), 1..=10_000u32)
+        .prop_map(|(supply_total, price, interest_basis_points, interest_apr, tiered, penalty_basis_points)| Settings {
+            is_initialized:         true,
+            unlock_timestamp:       UNLOCK,
+            supply_total,
+            token0:                 Token { address: Pubkey::default(), price },
+            interest_basis_points,
+            interest_apr,
+            penalty_basis_points,
+            tiers:                  if tiered && 5 <= supply_total {
+                    [Tier { supply_end: supply_total / 5, price: (price * 7 / 10).max(1) }, Tier::default(), Tier::default(), Tier::default()]
+                } else {
+                    [Tier::default(); 4]
+                },
+            ..Settings::default()
+        })
+}
+
+//**************************************************************************************************
+//  swaps, holder, amount and seconds since the previous swap
+//--------------------------------------------------------------------------------------------------
+fn swaps() -> impl Strategy<Value = Vec<(usize, u64, u64)>> {
+    prop::collection::vec((0..HOLDERS, any::<u64>(), 0..1_000_000u64), 0..32)
+}
+
+//==================================================================================================
+//  assert_accounting, every locked token is held by a position, now or once its interest accrues
+//--------------------------------------------------------------------------------------------------
+fn assert_accounting(_settings: &Settings, _savings: &[Savings]) {
+    let held: u128  = _savings.iter().map(|savings| savings.total_technical as u128 + savings.interest_reserved as u128).sum();
+    let paid: u128  = _savings.iter().map(|savings| savings.total_paid as u128).sum();
+
+    assert!(_settings.supply_locked <= _settings.supply_total);
+    assert_eq!(held, _settings.supply_locked as u128);
+    assert_eq!(paid, _settings.total_raised as u128);
+}
+
+proptest! {
+    //**********************************************************************************************
+    //  swaps and withdrawals keep the pool and the positions in step and never overflow
+    //----------------------------------------------------------------------------------------------
+    #[test]
+    fn test_swap_and_withdraw_invariants(mut settings in pool(), swaps in swaps(), withdrawals in prop::collection::vec((0..HOLDERS, any::<u64>()), 0..16)) {
+        let mut savings = [Savings::default(); HOLDERS];
+        let mut now     = START;
+
+        for (holder, amount, elapsed) in swaps {
+            now = now.saturating_add(elapsed);
+
+            let (settings_before, savings_before) = (settings, savings[holder]);
+
+            match settings.swap(&mut savings[holder], amount, now, false) {
+                Ok(swap_amounts) => {
+                    prop_assert!(now < UNLOCK);
+                    prop_assert_eq!(swap_amounts.locked(), Some(settings.supply_locked - settings_before.supply_locked));
+                }
+                Err(_) => {
+                    prop_assert_eq!(settings, settings_before);
+                    prop_assert_eq!(savings[holder], savings_before);
+                }
+            }
+
+            assert_accounting(&settings, &savings);
+        }
+
+        for (holder, amount) in withdrawals {
+            let amount                              = amount % (savings[holder].total_technical + savings[holder].interest_reserved + 1);
+            let (settings_before, savings_before)   = (settings, savings[holder]);
+
+            match settings.withdraw(&mut savings[holder], amount, UNLOCK) {
+                Ok(penalty_share) => {
+                    prop_assert_eq!(penalty_share, 0);
+                    prop_assert_eq!(savings[holder].interest_reserved, 0);
+                    prop_assert_eq!(settings.supply_locked, settings_before.supply_locked - amount);
+                }
+                Err(_) => {
+                    prop_assert_eq!(settings, settings_before);
+                    prop_assert_eq!(savings[holder], savings_before);
+                }
+            }
+
+            assert_accounting(&settings, &savings);
+        }
+    }
+
+    //**********************************************************************************************
+    //  transfers move what was paid and the interest reserved with the tokens, so early exits never
+    //  pay back more than was raised
+    //----------------------------------------------------------------------------------------------
+    #[test]
+    fn test_transfer_invariants(mut settings in pool(), swaps in swaps(), transfers in prop::collection::vec((0..HOLDERS, 0..HOLDERS, any::<u64>()), 0..16)) {
+        let mut savings = [Savings::default(); HOLDERS];
+        let now         = START;
+
+        for (holder, amount, _) in swaps {
+            settings.swap(&mut savings[holder], amount, now, false).ok();
+        }
+
+        let total_raised = settings.total_raised;
+
+        for (from, to, amount) in transfers {
+            let amount      = amount % (savings[from].total_technical + 1);
+            let mut sender  = savings[from];
+
+            if from != to {
+                sender.transfer(&mut savings[to], amount, &settings, now).unwrap();
+                savings[from] = sender;
+            }
+
+            assert_accounting(&settings, &savings);
+        }
+
+        let mut paid_back = 0;
+
+        for holder in savings.iter_mut() {
+            holder.accrue_interest(&settings, now);
+
+            if holder.total_technical != 0 {
+                let amount = holder.total_technical;
+                paid_back += settings.early_withdraw(holder, amount, now).unwrap();
+            }
+        }
+
+        assert_accounting(&settings, &savings);
+        prop_assert!(paid_back <= total_raised);
+    }
+
+    //**********************************************************************************************
+    //  nothing can be withdrawn before unlock
+    //----------------------------------------------------------------------------------------------
+    #[test]
+    fn test_withdraw_before_unlock(mut settings in pool(), amount in any::<u64>(), elapsed in 0..UNLOCK - START) {
+        let mut savings = Savings::default();
+
+        if settings.swap(&mut savings, amount, START, false).is_ok() {
+            let total_technical = savings.total_technical;
+            prop_assert!(settings.withdraw(&mut savings, total_technical, START + elapsed).is_err());
+        }
+    }
+
+    //**********************************************************************************************
+    //  tiers price a purchase no worse than the fixed price
+    //----------------------------------------------------------------------------------------------
+    #[test]
+    fn test_custom_amount_tiers(settings in pool(), amount in any::<u64>()) {
+        let fixed = Settings { tiers: [Tier::default(); 4], ..settings };
+
+        prop_assert!(fixed.custom_amount(amount, START) <= settings.custom_amount(amount, START));
+        prop_assert!(settings.custom_amount(amount, START) <= amount);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,147 @@
+#![cfg(feature = "json")]
+
+use {
+    serde_json::json,
+    solana_program::{
+        program_error::ProgramError,
+        program_pack::Pack,
+        pubkey::Pubkey,
+    },
+    kaizen::state::{
+        decode_account,
+        KaizenAccount,
+        Savings,
+        Settings,
+        Token,
+    },
+};
+
+//**************************************************************************************************
+//  test_savings_json, pubkeys in base58, amounts as decimal strings and the account type as `type`
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_savings_json() {
+    let owner       = Pubkey::new_unique();
+    let settings    = Pubkey::new_unique();
+    let account     = KaizenAccount::Savings(Savings {
+        is_initialized:     true,
+        total_technical:    u64::MAX,
+        total_original:     55,
+        total_paid:         100,
+        owner,
+        settings,
+        ..Savings::default()
+    });
+
+    let value = serde_json::to_value(account).unwrap();
+
+    assert_eq!(value, json!({
+        "type":                 "Savings",
+        "is_initialized":       true,
+        "total_technical":      "18446744073709551615",
+        "total_original":       "55",
+        "total_paid":           "100",
+        "interest_reserved":    "0",
+        "accrual_timestamp":    "0",
+        "owner":                owner.to_string(),
+        "settings":             settings.to_string(),
+    }));
+    assert_eq!(serde_json::from_value::<KaizenAccount>(value).unwrap(), account);
+}
+
+//**************************************************************************************************
+//  test_settings_json_round_trip, nested tokens and tiers included
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_settings_json_round_trip() {
+    let account = KaizenAccount::Settings(Settings {
+        is_initialized:     true,
+        revenue_owner:      Pubkey::new_unique(),
+        supply_total:       1_000_000,
+        token0:             Token { address: Pubkey::new_unique(), price: 2 },
+        ..Settings::default()
+    });
+
+    let value = serde_json::to_value(account).unwrap();
+
+    assert_eq!(value["type"], "Settings");
+    assert_eq!(value["supply_total"], "1000000");
+    assert_eq!(value["token0"]["price"], "2");
+    assert_eq!(value["tiers"][0]["supply_end"], "0");
+    assert_eq!(serde_json::from_value::<KaizenAccount>(value).unwrap(), account);
+
+    // numbers are not accepted for amounts
+    let mut value = serde_json::to_value(account).unwrap();
+    value["supply_total"] = json!(1_000_000);
+    assert!(serde_json::from_value::<KaizenAccount>(value).is_err());
+}
+
+//**************************************************************************************************
+//  test_decode_current, by the type tag
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_decode_current() {
+    let savings     = Savings { is_initialized: true, total_technical: 55, total_paid: 100, ..Savings::default() };
+    let mut data    = vec![0; Savings::LEN];
+    Savings::pack(savings, &mut data).unwrap();
+
+    assert_eq!(decode_account(&data).unwrap(), KaizenAccount::Savings(savings));
+
+    // tagged as Settings, but not a Settings account
+    data[0] = 1;
+    assert_eq!(decode_account(&data).err(), Some(ProgramError::InvalidAccountData));
+}
+
+//**************************************************************************************************
+//  test_decode_legacy, unversioned accounts by their length, later fields left at zero
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_decode_legacy() {
+    let revenue_owner   = Pubkey::new_unique();
+    let mut data        = vec![0; Settings::LEN_V0];
+    data[0]             = 1;
+    data[1..33]         .copy_from_slice(revenue_owner.as_ref());
+    data[33..37]        .copy_from_slice(&10u32.to_le_bytes());
+    data[109..117]      .copy_from_slice(&1_000_000u64.to_le_bytes());
+    data[157..165]      .copy_from_slice(&2u64.to_le_bytes());
+
+    assert_eq!(decode_account(&data).unwrap(), KaizenAccount::Settings(Settings {
+        is_initialized:         true,
+        revenue_owner,
+        interest_basis_points:  10,
+        supply_total:           1_000_000,
+        token0:                 Token { address: Pubkey::default(), price: 2 },
+        ..Settings::default()
+    }));
+
+    let mut data    = vec![0; Savings::LEN_V0];
+    data[0]         = 1;
+    data[1..9]      .copy_from_slice(&55u64.to_le_bytes());
+    data[9..17]     .copy_from_slice(&50u64.to_le_bytes());
+
+    assert_eq!(decode_account(&data).unwrap(), KaizenAccount::Savings(Savings {
+        is_initialized:     true,
+        total_technical:    55,
+        total_original:     50,
+        ..Savings::default()
+    }));
+
+    // is_initialized is a bool in the legacy layouts too
+    data[0] = 2;
+    assert_eq!(decode_account(&data).err(), Some(ProgramError::InvalidAccountData));
+}
+
+//**************************************************************************************************
+//  test_decode_unknown, empty data, zeroed accounts and tags past the last account type
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_decode_unknown() {
+    assert_eq!(decode_account(&[]).err(), Some(ProgramError::InvalidAccountData));
+    assert_eq!(decode_account(&[0; Savings::LEN]).err(), Some(ProgramError::InvalidAccountData));
+
+    for tag in [8, 0xff].iter() {
+        let mut data    = vec![0; Savings::LEN];
+        data[0]         = *tag;
+        assert_eq!(decode_account(&data).err(), Some(ProgramError::InvalidAccountData), "tag {}", tag);
+    }
+}
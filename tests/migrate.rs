@@ -0,0 +1,202 @@
+mod common;
+
+use {
+    solana_program::{
+        instruction::{
+            AccountMeta,
+            InstructionError,
+        },
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::Account,
+        signature::{
+            Keypair,
+            Signer,
+        },
+        transport::TransportError,
+    },
+    kaizen::{
+        id,
+        instruction::KaizenInstruction,
+        state::{
+            Savings,
+            Settings,
+            Token,
+        },
+    },
+    common::*,
+};
+
+//==================================================================================================
+//  legacy_account, `_data` in the unversioned layout, owned by the program
+//--------------------------------------------------------------------------------------------------
+fn legacy_account(_data: Vec<u8>) -> Account {
+    Account {
+        owner:      id(),
+        lamports:   Rent::default().minimum_balance(_data.len()),
+        data:       _data,
+        ..Account::default()
+    }
+}
+
+//==================================================================================================
+//  legacy_settings, an initialized pool of 1_000_000 tokens at a price of `_price`
+//--------------------------------------------------------------------------------------------------
+fn legacy_settings(_locked_token_owner: &Pubkey, _price: u64) -> Vec<u8> {
+    let mut data    = vec![0; Settings::LEN_V0];
+    data[0]         = 1;
+    data[69..101]   .copy_from_slice(_locked_token_owner.as_ref());
+    data[109..117]  .copy_from_slice(&1_000_000u64.to_le_bytes());
+    data[157..165]  .copy_from_slice(&_price.to_le_bytes());
+    data
+}
+
+//==================================================================================================
+//  legacy_savings, an initialized position of 55 tokens
+//--------------------------------------------------------------------------------------------------
+fn legacy_savings() -> Vec<u8> {
+    let mut data    = vec![0; Savings::LEN_V0];
+    data[0]         = 1;
+    data[1..9]      .copy_from_slice(&55u64.to_le_bytes());
+    data[9..17]     .copy_from_slice(&50u64.to_le_bytes());
+    data
+}
+
+//==================================================================================================
+//  migrate_savings, `_legacy` into the pool's Savings account for `_owner`, signed by `_legacy` and
+//  `_signers`
+//--------------------------------------------------------------------------------------------------
+async fn migrate_savings(_pool: &mut Pool, _legacy: &Keypair, _owner: &Keypair, _locked_token_owner: &Keypair, _signers: &[&Keypair]) -> Result<(), TransportError> {
+    let signed   = |_key: &Keypair| _signers.iter().any(|signer| signer.pubkey() == _key.pubkey());
+    let accounts = vec![
+        AccountMeta::new(_legacy.pubkey(), true),
+        AccountMeta::new(_pool.savings, false),
+        AccountMeta::new_readonly(_owner.pubkey(), signed(_owner)),
+        AccountMeta::new_readonly(_pool.settings, false),
+        AccountMeta::new_readonly(_locked_token_owner.pubkey(), signed(_locked_token_owner)),
+    ];
+
+    let mut signers = vec![_legacy];
+    signers.extend_from_slice(_signers);
+    _pool.process_signed(KaizenInstruction::Migrate, accounts, &signers).await
+}
+
+//**************************************************************************************************
+//  test_migrate_settings, signed by the pool's locked token owner
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_migrate_settings() {
+    let locked_token_owner  = Keypair::new();
+    let legacy              = Pubkey::new_unique();
+    let mut pool            = Pool::start_with(Savings::LEN, &[
+        (legacy, legacy_account(legacy_settings(&locked_token_owner.pubkey(), 2))),
+    ]).await;
+
+    let settings    = pool.settings;
+    let migrate     = |signer: &Keypair| vec![
+        AccountMeta::new(legacy, false),
+        AccountMeta::new(settings, false),
+        AccountMeta::new_readonly(signer.pubkey(), true),
+    ];
+
+    let other = Keypair::new();
+    assert_rejected(pool.process_signed(KaizenInstruction::Migrate, migrate(&other), &[&other]).await, InstructionError::InvalidAccountData);
+
+    let settings_lamports = pool.lamports(pool.settings).await;
+    let legacy_lamports   = pool.lamports(legacy).await;
+
+    pool.process_signed(KaizenInstruction::Migrate, migrate(&locked_token_owner), &[&locked_token_owner]).await.unwrap();
+
+    assert_eq!(pool.unpack::<Settings>(pool.settings).await, Settings {
+        is_initialized:     true,
+        locked_token_owner: locked_token_owner.pubkey(),
+        supply_total:       1_000_000,
+        token0:             Token { address: Pubkey::default(), price: 2 },
+        ..Settings::default()
+    });
+    assert_eq!(pool.lamports(legacy).await, 0);
+    assert_eq!(pool.lamports(pool.settings).await, settings_lamports + legacy_lamports);
+}
+
+//**************************************************************************************************
+//  test_migrate_settings_invalid, a legacy pool `Initialize` would reject stays where it is
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_migrate_settings_invalid() {
+    let locked_token_owner  = Keypair::new();
+    let legacy              = Pubkey::new_unique();
+    let mut pool            = Pool::start_with(Savings::LEN, &[
+        (legacy, legacy_account(legacy_settings(&locked_token_owner.pubkey(), 0))),
+    ]).await;
+
+    let accounts = vec![
+        AccountMeta::new(legacy, false),
+        AccountMeta::new(pool.settings, false),
+        AccountMeta::new_readonly(locked_token_owner.pubkey(), true),
+    ];
+
+    assert_rejected(pool.process_signed(KaizenInstruction::Migrate, accounts, &[&locked_token_owner]).await, InstructionError::InvalidAccountData);
+    assert_eq!(pool.lamports(legacy).await, Rent::default().minimum_balance(Settings::LEN_V0));
+    assert_eq!(pool.context.banks_client.get_account(pool.settings).await.unwrap().unwrap().data, vec![0; Settings::LEN]);
+}
+
+//**************************************************************************************************
+//  test_migrate_savings, signed by the legacy Savings key, the wallet owning the migrated Savings
+//  and the pool's locked token owner
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_migrate_savings() {
+    let locked_token_owner  = Keypair::new();
+    let legacy              = Keypair::new();
+    let owner               = Keypair::new();
+    let mut pool            = Pool::start_with(Savings::LEN, &[
+        (legacy.pubkey(), legacy_account(legacy_savings())),
+    ]).await;
+
+    let settings = pool.pool_settings(1_000_000, 3_600).await;
+    pool.initialize_account(pool.settings, Settings { locked_token_owner: locked_token_owner.pubkey(), ..settings }).await.unwrap();
+
+    // the new owner has to be a wallet that signs
+    assert_rejected(migrate_savings(&mut pool, &legacy, &owner, &locked_token_owner, &[&locked_token_owner]).await, InstructionError::MissingRequiredSignature);
+
+    // the pool's locked token owner has to vouch that the position belongs to this pool
+    let other = Keypair::new();
+    assert_rejected(migrate_savings(&mut pool, &legacy, &owner, &locked_token_owner, &[&owner]).await, InstructionError::MissingRequiredSignature);
+    assert_rejected(migrate_savings(&mut pool, &legacy, &owner, &other, &[&owner, &other]).await, InstructionError::InvalidAccountData);
+
+    migrate_savings(&mut pool, &legacy, &owner, &locked_token_owner, &[&owner, &locked_token_owner]).await.unwrap();
+
+    assert_eq!(pool.savings().await, Savings {
+        is_initialized:     true,
+        total_technical:    55,
+        total_original:     50,
+        owner:              owner.pubkey(),
+        settings:           pool.settings,
+        ..Savings::default()
+    });
+    assert_eq!(pool.lamports(legacy.pubkey()).await, 0);
+}
+
+//**************************************************************************************************
+//  test_migrate_into_initialized, a migrated account is never overwritten
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_migrate_into_initialized() {
+    let locked_token_owner  = Keypair::new();
+    let legacy              = Keypair::new();
+    let owner               = Keypair::new();
+    let mut pool            = Pool::start_with(Savings::LEN, &[
+        (legacy.pubkey(), legacy_account(legacy_savings())),
+    ]).await;
+
+    let settings = pool.pool_settings(1_000_000, 3_600).await;
+    pool.initialize_account(pool.settings, Settings { locked_token_owner: locked_token_owner.pubkey(), ..settings }).await.unwrap();
+    pool.swap(100).await.unwrap();
+
+    assert_rejected(migrate_savings(&mut pool, &legacy, &owner, &locked_token_owner, &[&owner, &locked_token_owner]).await, InstructionError::AccountAlreadyInitialized);
+    assert_eq!(pool.savings().await.owner, pool.context.payer.pubkey());
+}
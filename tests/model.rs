@@ -0,0 +1,203 @@
+mod common;
+
+use {
+    solana_program::{
+        instruction::{
+            AccountMeta,
+            InstructionError,
+        },
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::{
+        Keypair,
+        Signer,
+    },
+    kaizen::{
+        instruction::KaizenInstruction,
+        model::{PoolEvent, PoolModel},
+        state::{
+            EarlyWithdrawArgs,
+            Savings,
+            Settings,
+            SwapArgs,
+            Token,
+            WithdrawArgs,
+        },
+    },
+    common::*,
+};
+
+const START:    u64 = 1_600_000_000;
+const UNLOCK:   u64 = START + 1_000;
+
+//**************************************************************************************************
+//  pool, 1:1 price, 10% interest, 5% early withdraw penalty, in a Settings account holding `_rent`
+//--------------------------------------------------------------------------------------------------
+fn pool(_rent: u64) -> PoolModel {
+    PoolModel::new(&Settings {
+        unlock_timestamp:       UNLOCK,
+        supply_total:           1_000,
+        token0:                 Token { address: Pubkey::default(), price: 1 },
+        interest_basis_points:  10,
+        penalty_basis_points:   500,
+        ..Settings::default()
+    }, _rent, START)
+    .unwrap()
+}
+
+#[test]
+fn test_replay() {
+    let alice   = Pubkey::new_unique();
+    let bob     = Pubkey::new_unique();
+    let mut pool = pool(0);
+
+    let steps = pool.replay(&[
+        PoolEvent::Swap             { owner: alice, amount: 400, timestamp: START + 1 },
+        PoolEvent::Swap             { owner: bob,   amount: 500, timestamp: START + 2 },
+        PoolEvent::Withdraw         { owner: alice, amount: 440, timestamp: START + 3 },
+        PoolEvent::EarlyWithdraw    { owner: bob,   amount: 275, timestamp: START + 4 },
+        PoolEvent::Withdraw         { owner: alice, amount: 440, timestamp: UNLOCK },
+    ]);
+
+    assert_eq!(steps[0].result, Ok(400));
+    assert_eq!(steps[1].result, Ok(500));
+    assert_eq!(steps[1].supply_locked, 990);
+    // still locked, nothing changes
+    assert_eq!(steps[2].result, Err(ProgramError::InvalidInstructionData));
+    assert_eq!(steps[2].supply_locked, 990);
+    // half of bob's position, what was paid for it minus the penalty
+    assert_eq!(steps[3].result, Ok(238));
+    assert_eq!(steps[3].pool_lamports, 900 - 238);
+    assert_eq!(steps[4].result, Ok(0));
+
+    assert_eq!(pool.holders(), 1);
+    assert_eq!(pool.settings.total_raised, 650);
+    assert!(!pool.is_sold_out());
+}
+
+#[test]
+fn test_rejected_event_leaves_pool_untouched() {
+    let alice   = Pubkey::new_unique();
+    let mut pool = pool(0);
+
+    pool.apply(&PoolEvent::Swap { owner: alice, amount: 900, timestamp: START }).unwrap();
+    let before = pool.clone();
+
+    // oversubscribed
+    assert_eq!(pool.apply(&PoolEvent::Swap { owner: alice, amount: 100, timestamp: START }), Err(ProgramError::InvalidInstructionData));
+    // no position
+    assert_eq!(pool.apply(&PoolEvent::Refund { owner: Pubkey::new_unique(), timestamp: START }), Err(ProgramError::UninitializedAccount));
+    assert_eq!(pool, before);
+}
+#[test]
+fn test_early_withdraw_keeps_rent() {
+    let alice   = Pubkey::new_unique();
+    let mut pool = pool(100);
+
+    pool.apply(&PoolEvent::Swap { owner: alice, amount: 400, timestamp: START }).unwrap();
+    // token0 the model does not track, a claimed bid say, has left the pool
+    pool.pool_lamports -= 300;
+
+    assert_eq!(pool.apply(&PoolEvent::EarlyWithdraw { owner: alice, amount: 440, timestamp: START + 1 }), Err(ProgramError::InsufficientFunds));
+    assert_eq!(pool.apply(&PoolEvent::EarlyWithdraw { owner: alice, amount: 110, timestamp: START + 1 }), Ok(95));
+    assert_eq!(pool.pool_lamports, 100 + 5);
+}
+
+//==================================================================================================
+//  process, `_event` run by the program and replayed by `_model`, both have to agree on its outcome,
+//  the token0 left in the pool and the payer's position
+//--------------------------------------------------------------------------------------------------
+async fn process(_pool: &mut Pool, _model: &mut PoolModel, _revenue_owner: &Keypair, _event: PoolEvent) {
+    let settings    = _pool.settings;
+    let savings     = _pool.savings;
+    let owner       = _pool.context.payer.pubkey();
+
+    let (instruction, accounts, signers) = match _event {
+        PoolEvent::Swap { amount, .. } => (KaizenInstruction::Swap(SwapArgs { amount }), vec![
+            AccountMeta::new(settings, false),
+            AccountMeta::new(savings, false),
+            AccountMeta::new_readonly(owner, true),
+        ],
+        vec![]),
+        PoolEvent::Withdraw { amount, .. } => (KaizenInstruction::Withdraw(WithdrawArgs { amount }), vec![
+            AccountMeta::new(settings, false),
+            AccountMeta::new(savings, false),
+        ],
+        vec![]),
+        PoolEvent::EarlyWithdraw { amount, .. } => (KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs { amount }), vec![
+            AccountMeta::new(settings, false),
+            AccountMeta::new(savings, false),
+            AccountMeta::new_readonly(owner, true),
+        ],
+        vec![]),
+        PoolEvent::Refund { .. } => (KaizenInstruction::Refund, vec![
+            AccountMeta::new(settings, false),
+            AccountMeta::new(savings, false),
+        ],
+        vec![]),
+        PoolEvent::CollectRevenue { .. } => (KaizenInstruction::CollectRevenue, vec![
+            AccountMeta::new(settings, false),
+            AccountMeta::new(_revenue_owner.pubkey(), true),
+        ],
+        vec![_revenue_owner]),
+    };
+
+    let result = _pool.process_signed(instruction, accounts, &signers).await;
+
+    match _model.apply(&_event) {
+        Ok(_)       => result.unwrap(),
+        Err(error)  => assert_rejected(result, InstructionError::from(u64::from(error))),
+    }
+
+    assert_eq!(_pool.lamports(settings).await, _model.pool_lamports);
+
+    if let Some(position) = _model.savings.get(&owner) {
+        let user_savings = _pool.savings().await;
+
+        assert_eq!(user_savings.total_technical, position.total_technical);
+        assert_eq!(user_savings.total_paid, position.total_paid);
+    }
+}
+
+//**************************************************************************************************
+//  test_model_matches_processor, early withdraws and revenue collections of a pool with a 10% early
+//  exit penalty, run by the program and replayed by the model
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_model_matches_processor() {
+    let revenue_owner   = Keypair::new();
+    let mut pool        = Pool::start_with(Savings::LEN, &[
+        (revenue_owner.pubkey(), wallet_account()),
+    ]).await;
+
+    let settings = Settings {
+        revenue_owner:          revenue_owner.pubkey(),
+        penalty_basis_points:   1_000,
+        ..pool.pool_settings(1_000_000, 3_600).await
+    };
+    pool.initialize_account(pool.settings, settings).await.unwrap();
+
+    let rent        = Rent::default().minimum_balance(Settings::LEN);
+    let owner       = pool.context.payer.pubkey();
+    let now         = pool.now().await;
+    let mut model   = PoolModel::new(&settings, rent, now).unwrap();
+
+    process(&mut pool, &mut model, &revenue_owner, PoolEvent::Swap { owner, amount: 100, timestamp: now }).await;
+    process(&mut pool, &mut model, &revenue_owner, PoolEvent::EarlyWithdraw { owner, amount: 11, timestamp: now }).await;
+    // only the penalty, what was paid for the rest may still be paid back
+    process(&mut pool, &mut model, &revenue_owner, PoolEvent::CollectRevenue { timestamp: now }).await;
+    assert_eq!(model.pool_lamports, rent + 80);
+
+    process(&mut pool, &mut model, &revenue_owner, PoolEvent::EarlyWithdraw { owner, amount: 100, timestamp: now }).await;
+    process(&mut pool, &mut model, &revenue_owner, PoolEvent::EarlyWithdraw { owner, amount: 44, timestamp: now }).await;
+
+    let unlock = settings.unlock_timestamp;
+    pool.warp_past(unlock).await;
+
+    let now = pool.now().await;
+    process(&mut pool, &mut model, &revenue_owner, PoolEvent::CollectRevenue { timestamp: now }).await;
+    assert_eq!(model.pool_lamports, rent);
+}
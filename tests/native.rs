@@ -0,0 +1,234 @@
+mod common;
+
+use {
+    solana_program::{
+        instruction::{
+            AccountMeta,
+            InstructionError,
+        },
+        program_pack::Pack,
+        pubkey::Pubkey,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::{
+        Keypair,
+        Signer,
+    },
+    kaizen::{
+        instruction::KaizenInstruction,
+        state::{
+            Savings,
+            TransferSavingsArgs,
+        },
+    },
+    common::*,
+};
+
+//**************************************************************************************************
+//  test_swap_and_withdraw
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_swap_and_withdraw() {
+    let mut pool = Pool::start(Savings::LEN).await;
+
+    pool.initialize(1_000_000, 60).await.unwrap();
+    pool.swap(100).await.unwrap();
+    // 100 lamports at a price of 2, plus 10% interest
+    assert_eq!(pool.savings().await.total_technical, 55);
+
+    let unlock_timestamp = pool.now().await + 60;
+    pool.warp_past(unlock_timestamp).await;
+    pool.withdraw(55).await.unwrap();
+
+    let savings = pool.savings().await;
+    assert_eq!((savings.total_technical, savings.total_original), (0, 55));
+}
+
+//**************************************************************************************************
+//  test_reinitialize
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_reinitialize() {
+    let mut pool = Pool::start(Savings::LEN).await;
+
+    pool.initialize(1_000_000, 60).await.unwrap();
+    assert_rejected(pool.initialize(1_000_000, 120).await, InstructionError::AccountAlreadyInitialized);
+}
+
+//**************************************************************************************************
+//  test_swap_after_unlock
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_swap_after_unlock() {
+    let mut pool = Pool::start(Savings::LEN).await;
+
+    pool.initialize(1_000_000, 60).await.unwrap();
+    let unlock_timestamp = pool.now().await + 60;
+    pool.warp_past(unlock_timestamp).await;
+
+    assert_rejected(pool.swap(100).await, InstructionError::InvalidInstructionData);
+}
+
+//**************************************************************************************************
+//  test_withdraw_before_unlock
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_withdraw_before_unlock() {
+    let mut pool = Pool::start(Savings::LEN).await;
+
+    pool.initialize(1_000_000, 3_600).await.unwrap();
+    pool.swap(100).await.unwrap();
+
+    assert_rejected(pool.withdraw(55).await, InstructionError::InvalidInstructionData);
+}
+
+//**************************************************************************************************
+//  test_oversubscription
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_oversubscription() {
+    let mut pool = Pool::start(Savings::LEN).await;
+
+    pool.initialize(50, 60).await.unwrap();
+
+    assert_rejected(pool.swap(100).await, InstructionError::InvalidInstructionData);
+    pool.swap(90).await.unwrap();
+    assert_rejected(pool.swap(10).await, InstructionError::InvalidInstructionData);
+}
+
+//**************************************************************************************************
+//  test_wrong_account_size
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_wrong_account_size() {
+    let mut pool = Pool::start(Savings::LEN_V0).await;
+
+    pool.initialize(1_000_000, 60).await.unwrap();
+
+    assert_rejected(pool.swap(100).await, InstructionError::InvalidAccountData);
+}
+
+//**************************************************************************************************
+//  test_insufficient_savings
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_insufficient_savings() {
+    let mut pool = Pool::start(Savings::LEN).await;
+
+    pool.initialize(SAVINGS_LAMPORTS, 60).await.unwrap();
+    pool.swap(100).await.unwrap();
+    assert_rejected(pool.swap(SAVINGS_LAMPORTS).await, InstructionError::InsufficientFunds);
+
+    let unlock_timestamp = pool.now().await + 60;
+    pool.warp_past(unlock_timestamp).await;
+
+    assert_rejected(pool.withdraw(56).await, InstructionError::InvalidInstructionData);
+}
+
+//**************************************************************************************************
+//  test_transfer_moves_paid, the receiver gets the share of what was paid for the tokens it gets
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_transfer_moves_paid() {
+    let receiver            = Pubkey::new_unique();
+    let receiver_savings    = Pubkey::new_unique();
+    let mut pool            = Pool::start_with(Savings::LEN, &[
+        (receiver_savings, program_account(Savings::LEN, SAVINGS_LAMPORTS)),
+    ]).await;
+
+    pool.initialize(1_000_000, 60).await.unwrap();
+    pool.swap(100).await.unwrap();
+
+    let owner = pool.context.payer.pubkey();
+    pool.process(KaizenInstruction::TransferSavings(TransferSavingsArgs { amount: 11 }), vec![
+        AccountMeta::new_readonly(pool.settings, false),
+        AccountMeta::new(pool.savings, false),
+        AccountMeta::new_readonly(owner, true),
+        AccountMeta::new(receiver_savings, false),
+        AccountMeta::new_readonly(receiver, false),
+    ]).await.unwrap();
+
+    let savings: Savings = pool.savings().await;
+    let received: Savings = pool.unpack(receiver_savings).await;
+
+    // 100 * 11 / 55
+    assert_eq!((savings.total_technical, savings.total_paid), (44, 80));
+    assert_eq!((received.total_technical, received.total_paid, received.owner), (11, 20, receiver));
+}
+
+//**************************************************************************************************
+//  test_transfer_savings, into the receiver's existing Savings account, only by the owner, within the
+//  position and never into another wallet's Savings
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_transfer_savings() {
+    let receiver            = Keypair::new();
+    let receiver_savings    = Pubkey::new_unique();
+    let mut pool            = Pool::start_with(Savings::LEN, &[
+        (receiver_savings, program_account(Savings::LEN, SAVINGS_LAMPORTS)),
+    ]).await;
+
+    pool.initialize(1_000_000, 60).await.unwrap();
+    pool.swap(100).await.unwrap();
+    pool.swap_from(receiver_savings, &receiver, 20).await.unwrap();
+
+    let owner       = pool.context.payer.pubkey();
+    let settings    = pool.settings;
+    let savings     = pool.savings;
+    let transfer    = |owner: &Pubkey, signed: bool, receiver: &Pubkey| vec![
+        AccountMeta::new_readonly(settings, false),
+        AccountMeta::new(savings, false),
+        AccountMeta::new_readonly(*owner, signed),
+        AccountMeta::new(receiver_savings, false),
+        AccountMeta::new_readonly(*receiver, false),
+    ];
+    let amount      = |amount: u64| KaizenInstruction::TransferSavings(TransferSavingsArgs { amount });
+
+    // not the owner, or not signed
+    let other = Keypair::new();
+    assert_rejected(pool.process_signed(amount(11), transfer(&other.pubkey(), true, &receiver.pubkey()), &[&other]).await, InstructionError::InvalidAccountData);
+    assert_rejected(pool.process(amount(11), transfer(&other.pubkey(), false, &receiver.pubkey())).await, InstructionError::MissingRequiredSignature);
+
+    // nothing, or more than the position
+    assert_rejected(pool.process(amount(0), transfer(&owner, true, &receiver.pubkey())).await, InstructionError::InvalidInstructionData);
+    assert_rejected(pool.process(amount(56), transfer(&owner, true, &receiver.pubkey())).await, InstructionError::InvalidInstructionData);
+
+    // the receiving Savings belongs to the receiver
+    assert_rejected(pool.process(amount(11), transfer(&owner, true, &other.pubkey())).await, InstructionError::InvalidAccountData);
+
+    pool.process(amount(11), transfer(&owner, true, &receiver.pubkey())).await.unwrap();
+
+    let savings: Savings    = pool.savings().await;
+    let received: Savings   = pool.unpack(receiver_savings).await;
+
+    // 20 lamports at a price of 2, plus 10% interest, and 11 more
+    assert_eq!((savings.total_technical, savings.total_paid), (44, 80));
+    assert_eq!((received.total_technical, received.total_paid, received.owner), (22, 40, receiver.pubkey()));
+}
+
+//**************************************************************************************************
+//  test_close_savings_with_position, only an emptied Savings account can be closed
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_close_savings_with_position() {
+    let mut pool = Pool::start(Savings::LEN).await;
+
+    pool.initialize(1_000_000, 60).await.unwrap();
+    pool.swap(100).await.unwrap();
+
+    let owner           = pool.context.payer.pubkey();
+    let close_savings   = vec![
+        AccountMeta::new(pool.savings, false),
+        AccountMeta::new(owner, true),
+    ];
+
+    assert_rejected(pool.process(KaizenInstruction::CloseSavings, close_savings.clone()).await, InstructionError::InvalidInstructionData);
+    assert_eq!(pool.savings().await.total_technical, 55);
+
+    let unlock_timestamp = pool.now().await + 60;
+    pool.warp_past(unlock_timestamp).await;
+    pool.withdraw(55).await.unwrap();
+
+    pool.process(KaizenInstruction::CloseSavings, close_savings).await.unwrap();
+    assert_eq!(pool.lamports(pool.savings).await, 0);
+}
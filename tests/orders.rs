@@ -0,0 +1,257 @@
+mod common;
+
+use {
+    solana_program::{
+        instruction::{
+            AccountMeta,
+            InstructionError,
+        },
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_program_test::tokio,
+    solana_sdk::{
+        signature::{
+            Keypair,
+            Signer,
+        },
+        transport::TransportError,
+    },
+    kaizen::{
+        instruction::KaizenInstruction,
+        state::{
+            FillOrderArgs,
+            Order,
+            PlaceOrderArgs,
+            Savings,
+            Settings,
+        },
+    },
+    common::*,
+};
+
+//**************************************************************************************************
+//  Market, a pool where `seller` holds the pool's Savings account and `buyer` an empty one, with an
+//  empty Order account and a second pool next to it
+//--------------------------------------------------------------------------------------------------
+struct Market {
+    pool:           Pool,
+    seller:         Keypair,
+    buyer:          Keypair,
+    buyer_savings:  Pubkey,
+    order:          Pubkey,
+    other_settings: Pubkey,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Market {
+
+    //==================================================================================================
+    //  start, the seller has paid 100 lamports for 55 locked tokens
+    //--------------------------------------------------------------------------------------------------
+    async fn start() -> Self {
+        let seller          = Keypair::new();
+        let buyer           = Keypair::new();
+        let buyer_savings   = Pubkey::new_unique();
+        let order           = Pubkey::new_unique();
+        let other_settings  = Pubkey::new_unique();
+
+        let mut pool = Pool::start_with(Savings::LEN, &[
+            (seller.pubkey(),   wallet_account()),
+            (buyer.pubkey(),    wallet_account()),
+            (buyer_savings,     program_account(Savings::LEN, SAVINGS_LAMPORTS)),
+            (order,             program_account(Order::LEN, Rent::default().minimum_balance(Order::LEN))),
+            (other_settings,    program_account(Settings::LEN, Rent::default().minimum_balance(Settings::LEN))),
+        ]).await;
+
+        pool.initialize(1_000_000, 3_600).await.unwrap();
+        let other_pool_settings = pool.pool_settings(1_000_000, 3_600).await;
+        pool.initialize_account(other_settings, other_pool_settings).await.unwrap();
+
+        let savings = pool.savings;
+        pool.swap_from(savings, &seller, 100).await.unwrap();
+
+        Self { pool, seller, buyer, buyer_savings, order, other_settings }
+    }
+    //==================================================================================================
+    //  place_order, signed by the seller
+    //--------------------------------------------------------------------------------------------------
+    async fn place_order(&mut self, _settings: Pubkey, _amount: u64, _price: u64, _expiry_timestamp: u64) -> Result<(), TransportError> {
+        let args = PlaceOrderArgs { amount: _amount, price: _price, expiry_timestamp: _expiry_timestamp };
+
+        self.pool.process_signed(KaizenInstruction::PlaceOrder(args), vec![
+            AccountMeta::new_readonly(_settings, false),
+            AccountMeta::new(self.order, false),
+            AccountMeta::new(self.pool.savings, false),
+            AccountMeta::new_readonly(self.seller.pubkey(), true),
+        ],
+        &[&self.seller]).await
+    }
+    //==================================================================================================
+    //  fill_order, signed by the buyer, paid to `_seller`
+    //--------------------------------------------------------------------------------------------------
+    async fn fill_order(&mut self, _settings: Pubkey, _seller: Pubkey, _amount: u64) -> Result<(), TransportError> {
+        self.pool.process_signed(KaizenInstruction::FillOrder(FillOrderArgs { amount: _amount }), vec![
+            AccountMeta::new_readonly(_settings, false),
+            AccountMeta::new(self.order, false),
+            AccountMeta::new(self.buyer_savings, false),
+            AccountMeta::new_readonly(self.buyer.pubkey(), true),
+            AccountMeta::new(_seller, false),
+        ],
+        &[&self.buyer]).await
+    }
+    //==================================================================================================
+    //  cancel_order, signed by `_seller` when `_signed`, only the fee payer signs otherwise
+    //--------------------------------------------------------------------------------------------------
+    async fn cancel_order(&mut self, _seller: &Keypair, _signed: bool) -> Result<(), TransportError> {
+        let accounts = vec![
+            AccountMeta::new_readonly(self.pool.settings, false),
+            AccountMeta::new(self.order, false),
+            AccountMeta::new(self.pool.savings, false),
+            AccountMeta::new(_seller.pubkey(), _signed),
+        ];
+
+        if _signed {
+            self.pool.process_signed(KaizenInstruction::CancelOrder, accounts, &[_seller]).await
+        } else {
+            self.pool.process(KaizenInstruction::CancelOrder, accounts).await
+        }
+    }
+}
+
+//**************************************************************************************************
+//  test_partial_fill
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_partial_fill() {
+    let mut market  = Market::start().await;
+    let settings    = market.pool.settings;
+    let seller      = market.seller.pubkey();
+    let expiry      = market.pool.now().await + 3_600;
+
+    market.place_order(settings, 50, 3, expiry).await.unwrap();
+
+    // what was paid for the tokens goes with them, 100 * 50 / 55
+    let seller_savings = market.pool.savings().await;
+    assert_eq!((seller_savings.total_technical, seller_savings.total_paid), (5, 10));
+    assert_eq!(market.pool.unpack::<Order>(market.order).await.paid, 90);
+
+    let seller_lamports         = market.pool.lamports(seller).await;
+    let buyer_savings_lamports  = market.pool.lamports(market.buyer_savings).await;
+
+    market.fill_order(settings, seller, 20).await.unwrap();
+
+    let buyer_savings: Savings = market.pool.unpack(market.buyer_savings).await;
+    let order: Order = market.pool.unpack(market.order).await;
+
+    assert_eq!((buyer_savings.total_technical, buyer_savings.owner, buyer_savings.settings), (20, market.buyer.pubkey(), settings));
+    assert_eq!(buyer_savings.total_paid, 36);
+    assert_eq!((order.amount, order.paid), (30, 54));
+    assert_eq!(market.pool.lamports(seller).await, seller_lamports + 60);
+    assert_eq!(market.pool.lamports(market.buyer_savings).await, buyer_savings_lamports - 60);
+
+    // the rest of the order, and nothing past it
+    market.fill_order(settings, seller, 30).await.unwrap();
+    assert_rejected(market.fill_order(settings, seller, 1).await, InstructionError::InvalidInstructionData);
+
+    let buyer_savings: Savings = market.pool.unpack(market.buyer_savings).await;
+    assert_eq!((buyer_savings.total_technical, buyer_savings.total_paid), (50, 90));
+    assert_eq!(market.pool.unpack::<Order>(market.order).await.amount, 0);
+}
+
+//**************************************************************************************************
+//  test_fill_after_expiry
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_fill_after_expiry() {
+    let mut market  = Market::start().await;
+    let settings    = market.pool.settings;
+    let seller      = market.seller.pubkey();
+    let expiry      = market.pool.now().await + 60;
+
+    assert_rejected(market.place_order(settings, 50, 3, expiry - 60).await, InstructionError::InvalidInstructionData);
+    market.place_order(settings, 50, 3, expiry).await.unwrap();
+    market.pool.warp_past(expiry).await;
+
+    assert_rejected(market.fill_order(settings, seller, 10).await, InstructionError::InvalidInstructionData);
+    assert_eq!(market.pool.unpack::<Order>(market.order).await.amount, 50);
+}
+
+//**************************************************************************************************
+//  test_cancel_after_expiry, anyone may cancel an expired order, the remainder goes back to the
+//  seller and the order rent to the seller wallet
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_cancel_after_expiry() {
+    let mut market  = Market::start().await;
+    let settings    = market.pool.settings;
+    let seller      = market.seller.pubkey();
+    let expiry      = market.pool.now().await + 60;
+
+    market.place_order(settings, 50, 3, expiry).await.unwrap();
+    market.fill_order(settings, seller, 20).await.unwrap();
+
+    // the seller only
+    let seller_keypair = Keypair::from_bytes(&market.seller.to_bytes()).unwrap();
+    assert_rejected(market.cancel_order(&seller_keypair, false).await, InstructionError::MissingRequiredSignature);
+
+    market.pool.warp_past(expiry).await;
+
+    let seller_lamports = market.pool.lamports(seller).await;
+    let order_lamports  = market.pool.lamports(market.order).await;
+
+    market.cancel_order(&seller_keypair, false).await.unwrap();
+
+    let seller_savings = market.pool.savings().await;
+    assert_eq!((seller_savings.total_technical, seller_savings.total_paid), (5 + 30, 10 + 54));
+    assert_eq!(market.pool.lamports(market.order).await, 0);
+    assert_eq!(market.pool.lamports(seller).await, seller_lamports + order_lamports);
+}
+
+//**************************************************************************************************
+//  test_cancel_by_seller
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_cancel_by_seller() {
+    let mut market  = Market::start().await;
+    let settings    = market.pool.settings;
+    let expiry      = market.pool.now().await + 3_600;
+
+    market.place_order(settings, 50, 3, expiry).await.unwrap();
+
+    let seller_keypair = Keypair::from_bytes(&market.seller.to_bytes()).unwrap();
+    market.cancel_order(&seller_keypair, true).await.unwrap();
+
+    let seller_savings = market.pool.savings().await;
+    assert_eq!((seller_savings.total_technical, seller_savings.total_paid), (55, 100));
+}
+
+//**************************************************************************************************
+//  test_wrong_seller_or_pool
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_wrong_seller_or_pool() {
+    let mut market  = Market::start().await;
+    let settings    = market.pool.settings;
+    let seller      = market.seller.pubkey();
+    let buyer       = market.buyer.pubkey();
+    let other       = market.other_settings;
+    let expiry      = market.pool.now().await + 3_600;
+
+    // the seller's position belongs to the first pool
+    assert_rejected(market.place_order(other, 50, 3, expiry).await, InstructionError::InvalidAccountData);
+    market.place_order(settings, 50, 3, expiry).await.unwrap();
+
+    // paid to someone else, or filled through the other pool
+    assert_rejected(market.fill_order(settings, buyer, 10).await, InstructionError::InvalidAccountData);
+    assert_rejected(market.fill_order(other, seller, 10).await, InstructionError::InvalidAccountData);
+
+    // cancelled by a wallet that is not the seller
+    let buyer_keypair = Keypair::from_bytes(&market.buyer.to_bytes()).unwrap();
+    assert_rejected(market.cancel_order(&buyer_keypair, true).await, InstructionError::InvalidAccountData);
+
+    assert_eq!(market.pool.unpack::<Order>(market.order).await.amount, 50);
+    assert_eq!(market.pool.lamports(market.buyer_savings).await, SAVINGS_LAMPORTS);
+}
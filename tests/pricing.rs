@@ -0,0 +1,155 @@
+mod common;
+
+use {
+    solana_program::{
+        program_pack::Pack,
+        pubkey::Pubkey,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::Keypair,
+    kaizen::state::{
+        Savings,
+        Settings,
+        Tier,
+        Token,
+    },
+    common::*,
+};
+
+const START: u64 = 1_600_000_000;
+
+//==================================================================================================
+//  tiered, 1_000 tokens at 1, then up to 3_000 at 2, then 4 past the tiers, `_supply_locked` sold
+//--------------------------------------------------------------------------------------------------
+fn tiered(_supply_locked: u64) -> Settings {
+    Settings {
+        is_initialized:     true,
+        unlock_timestamp:   START + 1_000,
+        supply_total:       1_000_000,
+        supply_locked:      _supply_locked,
+        token0:             Token { address: Pubkey::new_unique(), price: 4 },
+        tiers:              [Tier { supply_end: 1_000, price: 1 }, Tier { supply_end: 3_000, price: 2 }, Tier::default(), Tier::default()],
+        ..Settings::default()
+    }
+}
+
+//==================================================================================================
+//  auction, decaying from 10 at `START` to 2 at `START + 800`
+//--------------------------------------------------------------------------------------------------
+fn auction() -> Settings {
+    Settings {
+        is_initialized:         true,
+        unlock_timestamp:       START + 1_000,
+        supply_total:           1_000_000,
+        token0:                 Token { address: Pubkey::new_unique(), price: 4 },
+        auction_start_price:    10,
+        auction_floor_price:    2,
+        auction_start:          START,
+        auction_end:            START + 800,
+        ..Settings::default()
+    }
+}
+
+//**************************************************************************************************
+//  test_custom_amount_across_tiers, a purchase crossing a boundary buys what is left of the tier
+//  at its price and the rest at the next price
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_custom_amount_across_tiers() {
+    // 200 left at 1 for 200, the other 400 buy 200 at 2
+    assert_eq!(tiered(800).custom_amount(600, START), 200 + 200);
+    // what can't buy a whole token in the tier it ends in is not spent
+    assert_eq!(tiered(800).custom_amount(601, START), 200 + 200);
+    // 100 left at 2 for 200, the other 800 buy 200 past the tiers at 4
+    assert_eq!(tiered(2_900).custom_amount(1_000, START), 100 + 200);
+    // from the boundary on, the next tier only
+    assert_eq!(tiered(1_000).custom_amount(600, START), 300);
+    // all the way across
+    assert_eq!(tiered(0).custom_amount(1_000 + 4_000 + 400, START), 1_000 + 2_000 + 100);
+}
+
+//**************************************************************************************************
+//  test_swap_across_tiers, the pool and the position get the amounts of both tiers
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_swap_across_tiers() {
+    let mut settings    = tiered(800);
+    let mut savings     = Savings::default();
+
+    let swap_amounts = settings.swap(&mut savings, 600, START, false).unwrap();
+    assert_eq!(swap_amounts.custom_amount, 400);
+    assert_eq!((savings.total_technical, savings.total_paid), (400, 600));
+    assert_eq!((settings.supply_locked, settings.total_raised), (1_200, 600));
+
+    // the next purchase starts in the second tier
+    settings.swap(&mut savings, 600, START, false).unwrap();
+    assert_eq!((savings.total_technical, settings.supply_locked), (700, 1_500));
+}
+
+//**************************************************************************************************
+//  test_auction_price, the start price until the auction starts, the floor price from its end on
+//  and linear in between
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_auction_price() {
+    let settings = auction();
+
+    assert!(settings.is_auction_valid());
+    assert_eq!(settings.price(START - 100), 10);
+    assert_eq!(settings.price(START), 10);
+    assert_eq!(settings.price(START + 100), 9);
+    assert_eq!(settings.price(START + 400), 6);
+    // rounded up to the next whole price
+    assert_eq!(settings.price(START + 450), 6);
+    assert_eq!(settings.price(START + 800), 2);
+    assert_eq!(settings.price(START + 900), 2);
+
+    // 600 token0 buy 60 at the start, 100 midway and 300 from the end on
+    assert_eq!(settings.custom_amount(600, START), 60);
+    assert_eq!(settings.custom_amount(600, START + 400), 100);
+    assert_eq!(settings.custom_amount(600, START + 900), 300);
+
+    // no auction, `token0.price`
+    assert_eq!(Settings { auction_end: 0, ..settings }.price(START + 400), 4);
+}
+
+//**************************************************************************************************
+//  test_auction_swaps, two swaps of the same amount at different times of the auction buy at the
+//  price of their time
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_auction_swaps() {
+    let later_owner     = Keypair::new();
+    let later_savings   = Pubkey::new_unique();
+    let mut pool        = Pool::start_with(Savings::LEN, &[(later_savings, program_account(Savings::LEN, SAVINGS_LAMPORTS))]).await;
+    let settings        = pool.pool_settings(1_000_000_000, 36_000).await;
+    let now             = pool.now().await;
+
+    pool.initialize_account(pool.settings, Settings {
+        interest_basis_points:  0,
+        auction_start_price:    1_000,
+        auction_floor_price:    100,
+        auction_start:          now,
+        auction_end:            now + 36_000,
+        ..settings
+    })
+    .await
+    .unwrap();
+
+    pool.swap(1_000_000).await.unwrap();
+
+    let now = pool.now().await;
+    pool.warp_past(now + 1_000).await;
+    pool.swap_from(later_savings, &later_owner, 1_000_000).await.unwrap();
+
+    // each Savings records when it swapped
+    let settings: Settings  = pool.unpack(pool.settings).await;
+    let early               = pool.savings().await;
+    let later: Savings      = pool.unpack(later_savings).await;
+
+    assert!(early.accrual_timestamp < later.accrual_timestamp);
+    assert!(settings.price(later.accrual_timestamp) < settings.price(early.accrual_timestamp));
+    assert_eq!(early.total_technical, 1_000_000 / settings.price(early.accrual_timestamp));
+    assert_eq!(later.total_technical, 1_000_000 / settings.price(later.accrual_timestamp));
+    assert!(early.total_technical < later.total_technical);
+}
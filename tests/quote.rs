@@ -0,0 +1,65 @@
+use {
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    kaizen::{
+        model::{PoolEvent, PoolModel},
+        quote::{quote_swap, quote_withdraw, QuoteError},
+        state::{Settings, Token},
+    },
+};
+
+const START:    u64 = 1_600_000_000;
+const UNLOCK:   u64 = START + 1_000;
+
+//**************************************************************************************************
+//  pool, 1:1 price and 10% interest reserved up to unlock
+//--------------------------------------------------------------------------------------------------
+fn pool() -> PoolModel {
+    PoolModel::new(&Settings {
+        unlock_timestamp:       UNLOCK,
+        supply_total:           1_000,
+        token0:                 Token { address: Pubkey::default(), price: 1 },
+        interest_basis_points:  10,
+        interest_apr:           true,
+        ..Settings::default()
+    }, 0, START)
+    .unwrap()
+}
+
+#[test]
+fn test_quotes_match_processed_amounts() {
+    let alice   = Pubkey::new_unique();
+    let mut pool = pool();
+
+    let swap_quote = quote_swap(&pool.settings, 400, START).unwrap();
+    pool.apply(&PoolEvent::Swap { owner: alice, amount: 400, timestamp: START }).unwrap();
+
+    assert_eq!(swap_quote.custom_amount, pool.savings[&alice].total_technical);
+    assert_eq!(swap_quote.interest_reserved, pool.savings[&alice].interest_reserved);
+    assert_eq!(swap_quote.unlock_timestamp, UNLOCK);
+
+    let withdraw_quote = quote_withdraw(&pool.settings, &pool.savings[&alice], UNLOCK).unwrap();
+    assert_eq!(withdraw_quote.amount, swap_quote.receive);
+    assert_eq!(pool.apply(&PoolEvent::Withdraw { owner: alice, amount: withdraw_quote.amount, timestamp: UNLOCK }), Ok(withdraw_quote.penalty_share));
+}
+
+#[test]
+fn test_quote_errors() {
+    let alice   = Pubkey::new_unique();
+    let mut pool = pool();
+
+    assert_eq!(quote_swap(&pool.settings, 1_001, START), Err(QuoteError::Oversubscribed));
+    // interest on top of the amount past u64, with or without the supply for it
+    let overflowing = Settings { interest_apr: false, ..pool.settings };
+    assert_eq!(quote_swap(&overflowing, u64::MAX, START), Err(QuoteError::Rejected(ProgramError::InvalidInstructionData)));
+    assert_eq!(quote_swap(&Settings { supply_total: u64::MAX, ..overflowing }, u64::MAX, START), Err(QuoteError::Rejected(ProgramError::InvalidInstructionData)));
+    assert_eq!(quote_swap(&pool.settings, 1, UNLOCK), Err(QuoteError::SaleClosed));
+    assert_eq!(quote_swap(&Settings::default(), 1, START), Err(QuoteError::NotInitialized));
+    assert_eq!(quote_swap(&Settings { paused: true, ..pool.settings }, 1, START), Err(QuoteError::Paused));
+
+    pool.apply(&PoolEvent::Swap { owner: alice, amount: 400, timestamp: START }).unwrap();
+    assert_eq!(quote_withdraw(&pool.settings, &pool.savings[&alice], START), Err(QuoteError::Locked { unlock_timestamp: UNLOCK }));
+    assert_eq!(ProgramError::from(QuoteError::Locked { unlock_timestamp: UNLOCK }), ProgramError::InvalidInstructionData);
+}
\ No newline at end of file
@@ -0,0 +1,232 @@
+mod common;
+
+use {
+    solana_program::{
+        instruction::{
+            AccountMeta,
+            InstructionError,
+        },
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_program_test::tokio,
+    solana_sdk::{
+        signature::{
+            Keypair,
+            Signer,
+        },
+        transport::TransportError,
+    },
+    kaizen::{
+        instruction::KaizenInstruction,
+        state::{
+            EarlyWithdrawArgs,
+            Referrer,
+            Savings,
+            Settings,
+            SwapArgs,
+        },
+    },
+    common::*,
+};
+
+//**************************************************************************************************
+//  Referral, a pool paying referrers 10% with a 10% early exit penalty, a Referrer account registered
+//  by `referrer_owner` and an empty Savings account for its bonus
+//--------------------------------------------------------------------------------------------------
+struct Referral {
+    pool:               Pool,
+    referrer:           Pubkey,
+    referrer_owner:     Keypair,
+    referrer_savings:   Pubkey,
+    rent:               u64,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Referral {
+
+    //==================================================================================================
+    //  start, referrers get bonus locked tokens when `_bonus`, a share of the payment otherwise.
+    //  `_extra` are more Referrer accounts, not registered
+    //--------------------------------------------------------------------------------------------------
+    async fn start(_bonus: bool, _extra: &[Pubkey]) -> Self {
+        let referrer            = Pubkey::new_unique();
+        let referrer_owner      = Keypair::new();
+        let referrer_savings    = Pubkey::new_unique();
+        let referrer_rent       = Rent::default().minimum_balance(Referrer::LEN);
+        let mut accounts        = vec![
+            (referrer,                  program_account(Referrer::LEN, referrer_rent)),
+            (referrer_owner.pubkey(),   wallet_account()),
+            (referrer_savings,          program_account(Savings::LEN, Rent::default().minimum_balance(Savings::LEN))),
+        ];
+
+        for extra in _extra.iter() {
+            accounts.push((*extra, program_account(Referrer::LEN, referrer_rent)));
+        }
+
+        let mut pool = Pool::start_with(Savings::LEN, &accounts).await;
+        let settings = pool.pool_settings(1_000_000, 3_600).await;
+        let rent     = pool.lamports(pool.settings).await;
+
+        pool.initialize_account(pool.settings, Settings {
+            referral_basis_points:  1_000,
+            referral_bonus:         _bonus,
+            penalty_basis_points:   1_000,
+            ..settings
+        })
+        .await
+        .unwrap();
+
+        let mut referral = Self { pool, referrer, referrer_owner, referrer_savings, rent };
+        let owner        = Keypair::from_bytes(&referral.referrer_owner.to_bytes()).unwrap();
+        referral.register(referrer, &owner).await.unwrap();
+        referral
+    }
+    //==================================================================================================
+    //  register, `_referrer` for `_owner`
+    //--------------------------------------------------------------------------------------------------
+    async fn register(&mut self, _referrer: Pubkey, _owner: &Keypair) -> Result<(), TransportError> {
+        self.pool.process_signed(KaizenInstruction::RegisterReferrer, vec![
+            AccountMeta::new_readonly(self.pool.settings, false),
+            AccountMeta::new(_referrer, false),
+            AccountMeta::new_readonly(_owner.pubkey(), true),
+        ],
+        &[_owner]).await
+    }
+    //==================================================================================================
+    //  swap, from the pool's Savings referred by `_referrer`, credited to `_referrer_to`
+    //--------------------------------------------------------------------------------------------------
+    async fn swap(&mut self, _amount: u64, _referrer: Pubkey, _referrer_to: Pubkey) -> Result<(), TransportError> {
+        let owner = self.pool.context.payer.pubkey();
+
+        self.pool.process(KaizenInstruction::Swap(SwapArgs { amount: _amount }), vec![
+            AccountMeta::new(self.pool.settings, false),
+            AccountMeta::new(self.pool.savings, false),
+            AccountMeta::new_readonly(owner, true),
+            AccountMeta::new(_referrer, false),
+            AccountMeta::new(_referrer_to, false),
+        ]).await
+    }
+    //==================================================================================================
+    //  referrer
+    //--------------------------------------------------------------------------------------------------
+    async fn referrer(&mut self) -> Referrer {
+        self.pool.unpack(self.referrer).await
+    }
+    //==================================================================================================
+    //  settings
+    //--------------------------------------------------------------------------------------------------
+    async fn settings(&mut self) -> Settings {
+        self.pool.unpack(self.pool.settings).await
+    }
+}
+
+//**************************************************************************************************
+//  test_register_referrer, once per account, and never for the buyer's own swaps
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_register_referrer() {
+    let unregistered    = Pubkey::new_unique();
+    let own             = Pubkey::new_unique();
+    let mut referral    = Referral::start(true, &[unregistered, own]).await;
+
+    let referrer = referral.referrer().await;
+    assert_eq!(referrer, Referrer {
+        is_initialized: true,
+        settings:       referral.pool.settings,
+        owner:          referral.referrer_owner.pubkey(),
+        volume:         0,
+        rewards:        0,
+    });
+
+    // on a later bank, the same registration would be a duplicate transaction otherwise
+    let now = referral.pool.now().await;
+    referral.pool.warp_past(now).await;
+
+    let (referrer, owner) = (referral.referrer, Keypair::from_bytes(&referral.referrer_owner.to_bytes()).unwrap());
+    assert_rejected(referral.register(referrer, &owner).await, InstructionError::AccountAlreadyInitialized);
+
+    let referrer_savings = referral.referrer_savings;
+    assert_rejected(referral.swap(100, unregistered, referrer_savings).await, InstructionError::UninitializedAccount);
+
+    // the buyer referring itself
+    let buyer = Keypair::from_bytes(&referral.pool.context.payer.to_bytes()).unwrap();
+    referral.register(own, &buyer).await.unwrap();
+    assert_rejected(referral.swap(100, own, referrer_savings).await, InstructionError::InvalidAccountData);
+}
+
+//**************************************************************************************************
+//  test_referral_bonus, the referrer's Savings is credited 10% of the locked tokens bought on top
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_referral_bonus() {
+    let mut referral                    = Referral::start(true, &[]).await;
+    let (referrer, referrer_savings)    = (referral.referrer, referral.referrer_savings);
+
+    // 100 lamports at a price of 2, plus 10% interest, and 5 more for the referrer
+    referral.swap(100, referrer, referrer_savings).await.unwrap();
+
+    let savings = referral.pool.savings().await;
+    assert_eq!((savings.total_technical, savings.total_paid), (55, 100));
+
+    let bonus: Savings = referral.pool.unpack(referrer_savings).await;
+    assert_eq!((bonus.total_technical, bonus.total_paid), (5, 0));
+    assert_eq!((bonus.owner, bonus.settings), (referral.referrer_owner.pubkey(), referral.pool.settings));
+
+    let referrer_state = referral.referrer().await;
+    assert_eq!((referrer_state.volume, referrer_state.rewards), (100, 5));
+
+    let settings = referral.settings().await;
+    assert_eq!((settings.supply_locked, settings.total_raised), (60, 100));
+    assert_eq!(referral.pool.lamports(referral.pool.settings).await, referral.rent + 100);
+
+    // the bonus goes to the referrer's own position only
+    let now = referral.pool.now().await;
+    referral.pool.warp_past(now).await;
+
+    let buyer_savings = referral.pool.savings;
+    assert_rejected(referral.swap(100, referrer, buyer_savings).await, InstructionError::InvalidAccountData);
+}
+
+//**************************************************************************************************
+//  test_referral_payment, the referrer's wallet is paid 10% of the payment, which is not paid back
+//  on early exit
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_referral_payment() {
+    let mut referral    = Referral::start(false, &[]).await;
+    let referrer        = referral.referrer;
+    let referrer_wallet = referral.referrer_owner.pubkey();
+
+    // to the referrer's wallet only
+    let referrer_savings = referral.referrer_savings;
+    assert_rejected(referral.swap(100, referrer, referrer_savings).await, InstructionError::InvalidAccountData);
+
+    let wallet_lamports = referral.pool.lamports(referrer_wallet).await;
+    referral.swap(100, referrer, referrer_wallet).await.unwrap();
+
+    assert_eq!(referral.pool.lamports(referrer_wallet).await, wallet_lamports + 10);
+    assert_eq!(referral.pool.lamports(referral.pool.settings).await, referral.rent + 90);
+
+    let savings = referral.pool.savings().await;
+    assert_eq!((savings.total_technical, savings.total_paid), (55, 90));
+
+    let referrer_state = referral.referrer().await;
+    assert_eq!((referrer_state.volume, referrer_state.rewards), (100, 10));
+    assert_eq!(referral.settings().await.total_raised, 90);
+
+    // the whole position exits, 90 paid less the 10% penalty, which the pool keeps with its rent
+    let owner = referral.pool.context.payer.pubkey();
+    referral.pool.process(KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs { amount: 55 }), vec![
+        AccountMeta::new(referral.pool.settings, false),
+        AccountMeta::new(referral.pool.savings, false),
+        AccountMeta::new_readonly(owner, true),
+    ]).await.unwrap();
+
+    assert_eq!(referral.pool.lamports(referral.pool.settings).await, referral.rent + 9);
+
+    let settings = referral.settings().await;
+    assert_eq!((settings.supply_locked, settings.total_raised), (0, 0));
+}
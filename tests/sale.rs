@@ -0,0 +1,594 @@
+mod common;
+
+use {
+    solana_program::{
+        instruction::{
+            AccountMeta,
+            InstructionError,
+        },
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::Account,
+        signature::{
+            Keypair,
+            Signer,
+        },
+        transport::TransportError,
+    },
+    kaizen::{
+        id,
+        instruction::KaizenInstruction,
+        state::{
+            EarlyWithdrawArgs,
+            Savings,
+            Settings,
+            Token,
+        },
+    },
+    common::*,
+};
+
+//**************************************************************************************************
+//  Sale, a pool selling until `sale_end`, a minute from now, and unlocking in an hour, with a 10%
+//  early exit penalty
+//--------------------------------------------------------------------------------------------------
+struct Sale {
+    pool:               Pool,
+    revenue_owner:      Keypair,
+    locked_token_owner: Keypair,
+    sale_end:           u64,
+    unlock:             u64,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Sale {
+
+    //==================================================================================================
+    //  start, penalties go to the treasury
+    //--------------------------------------------------------------------------------------------------
+    async fn start(_soft_cap: u64) -> Self {
+        Self::start_with(_soft_cap, false).await
+    }
+    //==================================================================================================
+    //  start_with, penalties go to the remaining holders if `_penalty_to_holders`
+    //--------------------------------------------------------------------------------------------------
+    async fn start_with(_soft_cap: u64, _penalty_to_holders: bool) -> Self {
+        let revenue_owner       = Keypair::new();
+        let locked_token_owner  = Keypair::new();
+        let mut pool            = Pool::start_with(Savings::LEN, &[
+            (revenue_owner.pubkey(),        wallet_account()),
+            (locked_token_owner.pubkey(),   wallet_account()),
+        ]).await;
+        let settings            = pool.pool_settings(1_000_000, 3_600).await;
+        let sale_end            = settings.unlock_timestamp - 3_540;
+
+        pool.initialize_account(pool.settings, Settings {
+            revenue_owner:          revenue_owner.pubkey(),
+            locked_token_owner:     locked_token_owner.pubkey(),
+            soft_cap:               _soft_cap,
+            sale_end:               if _soft_cap != 0 { sale_end } else { 0 },
+            penalty_basis_points:   1_000,
+            penalty_to_holders:     _penalty_to_holders,
+            ..settings
+        })
+        .await
+        .unwrap();
+
+        Self { pool, revenue_owner, locked_token_owner, sale_end, unlock: settings.unlock_timestamp }
+    }
+    //==================================================================================================
+    //  early_withdraw
+    //--------------------------------------------------------------------------------------------------
+    async fn early_withdraw(&mut self, _amount: u64) -> Result<(), TransportError> {
+        let owner = self.pool.context.payer.pubkey();
+
+        self.pool.process(KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs { amount: _amount }), vec![
+            AccountMeta::new(self.pool.settings, false),
+            AccountMeta::new(self.pool.savings, false),
+            AccountMeta::new_readonly(owner, true),
+        ]).await
+    }
+    //==================================================================================================
+    //  refund
+    //--------------------------------------------------------------------------------------------------
+    async fn refund(&mut self) -> Result<(), TransportError> {
+        self.pool.process(KaizenInstruction::Refund, vec![
+            AccountMeta::new(self.pool.settings, false),
+            AccountMeta::new(self.pool.savings, false),
+        ]).await
+    }
+    //==================================================================================================
+    //  collect_revenue, returns the token0 collected
+    //--------------------------------------------------------------------------------------------------
+    async fn collect_revenue(&mut self) -> Result<u64, TransportError> {
+        let revenue_owner   = self.revenue_owner.pubkey();
+        let before          = self.pool.lamports(revenue_owner).await;
+        let signer          = Keypair::from_bytes(&self.revenue_owner.to_bytes()).unwrap();
+
+        self.pool.process_signed(KaizenInstruction::CollectRevenue, vec![
+            AccountMeta::new(self.pool.settings, false),
+            AccountMeta::new(revenue_owner, true),
+        ],
+        &[&signer]).await?;
+
+        Ok(self.pool.lamports(revenue_owner).await - before)
+    }
+    //==================================================================================================
+    //  close_pool
+    //--------------------------------------------------------------------------------------------------
+    async fn close_pool(&mut self) -> Result<(), TransportError> {
+        let signer = Keypair::from_bytes(&self.locked_token_owner.to_bytes()).unwrap();
+
+        self.pool.process_signed(KaizenInstruction::ClosePool, vec![
+            AccountMeta::new(self.pool.settings, false),
+            AccountMeta::new(signer.pubkey(), true),
+        ],
+        &[&signer]).await
+    }
+    //==================================================================================================
+    //  settings
+    //--------------------------------------------------------------------------------------------------
+    async fn settings(&mut self) -> Settings {
+        self.pool.unpack(self.pool.settings).await
+    }
+}
+
+//**************************************************************************************************
+//  test_soft_cap_needs_sale_end
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_soft_cap_needs_sale_end() {
+    let mut pool = Pool::start(Savings::LEN).await;
+    let settings = pool.pool_settings(1_000_000, 3_600).await;
+
+    assert_rejected(pool.initialize_account(pool.settings, Settings { soft_cap: 100, ..settings }).await, InstructionError::InvalidInstructionData);
+}
+
+//**************************************************************************************************
+//  test_early_exit_after_sale_end, exits once the sale succeeded don't fail it
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_early_exit_after_sale_end() {
+    let mut sale = Sale::start(100).await;
+
+    sale.pool.swap(100).await.unwrap();
+    assert_rejected(sale.collect_revenue().await, InstructionError::InvalidInstructionData);
+
+    let sale_end = sale.sale_end;
+    sale.pool.warp_past(sale_end).await;
+
+    // 49 of the 100 paid for 27 of 55 tokens, less the 10% penalty
+    let savings_lamports = sale.pool.lamports(sale.pool.savings).await;
+    sale.early_withdraw(27).await.unwrap();
+    assert_eq!(sale.pool.lamports(sale.pool.savings).await, savings_lamports + 45);
+
+    assert_eq!(sale.settings().await.total_raised, 100);
+    assert_rejected(sale.refund().await, InstructionError::InvalidInstructionData);
+
+    // the rest of the position can still exit until unlock
+    assert_eq!(sale.collect_revenue().await.unwrap(), 0);
+    sale.early_withdraw(28).await.unwrap();
+
+    let unlock = sale.unlock;
+    sale.pool.warp_past(unlock).await;
+
+    // the penalties, once nothing can exit anymore
+    assert_eq!(sale.collect_revenue().await.unwrap(), 100 - 45 - 46);
+}
+
+//**************************************************************************************************
+//  test_early_exit_needs_penalty, pools without a penalty keep the lock until unlock
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_early_exit_needs_penalty() {
+    let mut pool    = Pool::start(Savings::LEN).await;
+    let owner       = pool.context.payer.pubkey();
+
+    pool.initialize(1_000_000, 3_600).await.unwrap();
+    pool.swap(100).await.unwrap();
+    let savings = pool.savings().await;
+
+    assert_rejected(pool.process(KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs { amount: 27 }), vec![
+        AccountMeta::new(pool.settings, false),
+        AccountMeta::new(pool.savings, false),
+        AccountMeta::new_readonly(owner, true),
+    ]).await, InstructionError::InvalidInstructionData);
+    assert_eq!(pool.savings().await, savings);
+}
+
+//**************************************************************************************************
+//  test_failed_sale, contributions are refunded and can't be collected
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_failed_sale() {
+    let mut sale = Sale::start(1_000).await;
+
+    sale.pool.swap(100).await.unwrap();
+    assert_rejected(sale.refund().await, InstructionError::InvalidInstructionData);
+
+    let sale_end = sale.sale_end;
+    sale.pool.warp_past(sale_end).await;
+
+    // the contributions are held back for their refunds
+    assert_eq!(sale.collect_revenue().await.unwrap(), 0);
+    assert_rejected(sale.early_withdraw(10).await, InstructionError::InvalidInstructionData);
+
+    let savings_lamports = sale.pool.lamports(sale.pool.savings).await;
+    sale.refund().await.unwrap();
+
+    let savings = sale.pool.savings().await;
+    assert_eq!(sale.pool.lamports(sale.pool.savings).await, savings_lamports + 100);
+    assert_eq!((savings.total_technical, savings.total_paid), (0, 0));
+
+    // the sale stays failed
+    let settings = sale.settings().await;
+    assert_eq!((settings.supply_locked, settings.total_raised), (0, 100));
+    assert!(settings.is_sale_failed(sale_end + 1));
+}
+
+//**************************************************************************************************
+//  test_refund_once, a refunded position has nothing left to refund and a failed sale sells nothing
+//  more
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_refund_once() {
+    let mut sale = Sale::start(1_000).await;
+
+    sale.pool.swap(100).await.unwrap();
+
+    let sale_end = sale.sale_end;
+    sale.pool.warp_past(sale_end).await;
+    assert_rejected(sale.pool.swap(100).await, InstructionError::InvalidInstructionData);
+
+    sale.refund().await.unwrap();
+
+    // on a later bank, the same Refund transaction would be a duplicate otherwise
+    let now                 = sale.pool.now().await;
+    let savings_lamports    = sale.pool.lamports(sale.pool.savings).await;
+    let settings_lamports   = sale.pool.lamports(sale.pool.settings).await;
+    sale.pool.warp_past(now).await;
+    sale.refund().await.unwrap();
+
+    assert_eq!(sale.pool.lamports(sale.pool.savings).await, savings_lamports);
+    assert_eq!(sale.pool.lamports(sale.pool.settings).await, settings_lamports);
+}
+
+//**************************************************************************************************
+//  test_refund_needs_failed_sale, not on pools without a soft cap nor once the soft cap is reached
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_refund_needs_failed_sale() {
+    let mut sale = Sale::start(0).await;
+
+    sale.pool.swap(100).await.unwrap();
+
+    let unlock = sale.unlock;
+    sale.pool.warp_past(unlock).await;
+    assert_rejected(sale.refund().await, InstructionError::InvalidInstructionData);
+
+    let mut sale = Sale::start(100).await;
+
+    sale.pool.swap(100).await.unwrap();
+
+    let sale_end = sale.sale_end;
+    sale.pool.warp_past(sale_end).await;
+    assert_rejected(sale.refund().await, InstructionError::InvalidInstructionData);
+    assert_eq!(sale.pool.savings().await.total_paid, 100);
+}
+
+//**************************************************************************************************
+//  test_refund_other_pool, a position is only refunded by its own pool
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_refund_other_pool() {
+    let savings = Pubkey::new_unique();
+
+    let mut savings_data = vec![0; Savings::LEN];
+    Savings::pack(Savings {
+        is_initialized:     true,
+        total_technical:    55,
+        total_paid:         100,
+        owner:              Pubkey::new_unique(),
+        settings:           Pubkey::new_unique(),
+        ..Savings::default()
+    }, &mut savings_data).unwrap();
+
+    let mut pool = Pool::start_with(Savings::LEN, &[
+        (savings, Account { owner: id(), lamports: SAVINGS_LAMPORTS, data: savings_data, ..Account::default() }),
+    ]).await;
+
+    let settings = pool.pool_settings(1_000_000, 3_600).await;
+    pool.initialize_account(pool.settings, Settings { soft_cap: 1_000, sale_end: settings.unlock_timestamp - 3_540, ..settings }).await.unwrap();
+    pool.swap(100).await.unwrap();
+
+    let sale_end = settings.unlock_timestamp - 3_540;
+    pool.warp_past(sale_end).await;
+
+    assert_rejected(pool.process(KaizenInstruction::Refund, vec![
+        AccountMeta::new(pool.settings, false),
+        AccountMeta::new(savings, false),
+    ]).await, InstructionError::InvalidAccountData);
+    assert_eq!(pool.lamports(savings).await, SAVINGS_LAMPORTS);
+}
+
+//**************************************************************************************************
+//  test_collect_revenue_holds_back_early_exits
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_collect_revenue_holds_back_early_exits() {
+    let mut sale = Sale::start(0).await;
+
+    sale.pool.swap(100).await.unwrap();
+    assert_eq!(sale.collect_revenue().await.unwrap(), 0);
+
+    // the whole position exits, its penalty is revenue
+    sale.early_withdraw(55).await.unwrap();
+
+    // on a later bank, the same CollectRevenue transaction would be a duplicate otherwise
+    let now = sale.pool.now().await;
+    sale.pool.warp_past(now).await;
+    assert_eq!(sale.collect_revenue().await.unwrap(), 10);
+    assert_eq!(sale.settings().await.total_raised, 0);
+}
+
+//**************************************************************************************************
+//  test_collect_revenue_without_exits, a pool that neither allows early exits nor refunds pays its
+//  revenue out before unlock
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_collect_revenue_without_exits() {
+    let revenue_owner   = Keypair::new();
+    let mut pool        = Pool::start_with(Savings::LEN, &[
+        (revenue_owner.pubkey(), wallet_account()),
+    ]).await;
+
+    let settings = pool.pool_settings(1_000_000, 3_600).await;
+    pool.initialize_account(pool.settings, Settings { revenue_owner: revenue_owner.pubkey(), ..settings }).await.unwrap();
+    pool.swap(100).await.unwrap();
+
+    let before = pool.lamports(revenue_owner.pubkey()).await;
+    pool.process_signed(KaizenInstruction::CollectRevenue, vec![
+        AccountMeta::new(pool.settings, false),
+        AccountMeta::new(revenue_owner.pubkey(), true),
+    ],
+    &[&revenue_owner]).await.unwrap();
+
+    assert_eq!(pool.lamports(revenue_owner.pubkey()).await, before + 100);
+    assert_eq!(pool.lamports(pool.settings).await, Rent::default().minimum_balance(Settings::LEN));
+}
+
+//**************************************************************************************************
+//  test_close_pool_after_revenue, the locked token owner only gets the rent back
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_close_pool_after_revenue() {
+    let mut sale = Sale::start(0).await;
+
+    sale.pool.swap(100).await.unwrap();
+
+    let unlock = sale.unlock;
+    sale.pool.warp_past(unlock).await;
+    sale.pool.withdraw(55).await.unwrap();
+
+    assert_rejected(sale.close_pool().await, InstructionError::InvalidInstructionData);
+    assert_eq!(sale.collect_revenue().await.unwrap(), 100);
+
+    let locked_token_owner  = sale.locked_token_owner.pubkey();
+    let owner_lamports      = sale.pool.lamports(locked_token_owner).await;
+    let rent                = sale.pool.lamports(sale.pool.settings).await;
+
+    sale.close_pool().await.unwrap();
+
+    assert_eq!(sale.pool.lamports(sale.pool.settings).await, 0);
+    assert_eq!(sale.pool.lamports(locked_token_owner).await, owner_lamports + rent);
+}
+
+//**************************************************************************************************
+//  test_close_pool_before_unlock, an empty pool stays open until unlock
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_close_pool_before_unlock() {
+    let mut sale = Sale::start(0).await;
+
+    assert_rejected(sale.close_pool().await, InstructionError::InvalidInstructionData);
+    assert!(sale.settings().await.is_initialized);
+
+    let unlock = sale.unlock;
+    sale.pool.warp_past(unlock).await;
+    sale.close_pool().await.unwrap();
+    assert_eq!(sale.pool.lamports(sale.pool.settings).await, 0);
+}
+
+//**************************************************************************************************
+//  test_close_pool_with_holders, a pool stays open while locked tokens are left to withdraw
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_close_pool_with_holders() {
+    let mut sale = Sale::start(0).await;
+
+    sale.pool.swap(100).await.unwrap();
+
+    let unlock = sale.unlock;
+    sale.pool.warp_past(unlock).await;
+    assert_eq!(sale.collect_revenue().await.unwrap(), 100);
+
+    assert_rejected(sale.close_pool().await, InstructionError::InvalidInstructionData);
+    assert_eq!(sale.settings().await.supply_locked, 55);
+
+    sale.pool.withdraw(55).await.unwrap();
+
+    // on a later bank, the same ClosePool transaction would be a duplicate otherwise
+    let now = sale.pool.now().await;
+    sale.pool.warp_past(now).await;
+    sale.close_pool().await.unwrap();
+    assert_eq!(sale.pool.lamports(sale.pool.settings).await, 0);
+}
+
+//**************************************************************************************************
+//  test_close_pool_with_penalties, penalties are collected before the pool can close
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_close_pool_with_penalties() {
+    let mut sale = Sale::start_with(0, true).await;
+
+    sale.pool.swap(100).await.unwrap();
+    sale.early_withdraw(55).await.unwrap();
+
+    let unlock = sale.unlock;
+    sale.pool.warp_past(unlock).await;
+
+    let rent = Rent::default().minimum_balance(Settings::LEN);
+    assert_rejected(sale.close_pool().await, InstructionError::InvalidInstructionData);
+    assert_eq!(sale.pool.lamports(sale.pool.settings).await, rent + 10);
+
+    assert_eq!(sale.collect_revenue().await.unwrap(), 10);
+
+    let now = sale.pool.now().await;
+    sale.pool.warp_past(now).await;
+    sale.close_pool().await.unwrap();
+    assert_eq!(sale.pool.lamports(sale.pool.settings).await, 0);
+}
+
+//**************************************************************************************************
+//  test_early_exit_keeps_rent, a pool holding its rent and 50 more pays out no more than 50
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_early_exit_keeps_rent() {
+    let owner       = Keypair::new();
+    let settings    = Pubkey::new_unique();
+    let savings     = Pubkey::new_unique();
+    let rent        = Rent::default().minimum_balance(Settings::LEN);
+
+    let mut settings_data = vec![0; Settings::LEN];
+    Settings::pack(Settings {
+        is_initialized:         true,
+        penalty_basis_points:   1_000,
+        unlock_timestamp:       4_000_000_000,
+        supply_total:           1_000_000,
+        supply_locked:          55,
+        total_raised:           100,
+        token0:                 Token { address: Pubkey::new_unique(), price: 2 },
+        ..Settings::default()
+    }, &mut settings_data).unwrap();
+
+    let mut savings_data = vec![0; Savings::LEN];
+    Savings::pack(Savings {
+        is_initialized:     true,
+        total_technical:    55,
+        total_paid:         100,
+        owner:              owner.pubkey(),
+        settings,
+        ..Savings::default()
+    }, &mut savings_data).unwrap();
+
+    let mut pool = Pool::start_with(Savings::LEN, &[
+        (settings,  Account { owner: id(), lamports: rent + 50, data: settings_data, ..Account::default() }),
+        (savings,   Account { owner: id(), lamports: SAVINGS_LAMPORTS, data: savings_data, ..Account::default() }),
+    ]).await;
+
+    let accounts = vec![
+        AccountMeta::new(settings, false),
+        AccountMeta::new(savings, false),
+        AccountMeta::new_readonly(owner.pubkey(), true),
+    ];
+
+    // the whole position would pay back 90
+    assert_rejected(
+        pool.process_signed(KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs { amount: 55 }), accounts.clone(), &[&owner]).await,
+        InstructionError::InsufficientFunds,
+    );
+
+    // 49 paid for 27 of the 55 tokens, less the 10% penalty
+    pool.process_signed(KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs { amount: 27 }), accounts.clone(), &[&owner]).await.unwrap();
+    assert_eq!(pool.lamports(settings).await, rent + 5);
+
+    assert_rejected(
+        pool.process_signed(KaizenInstruction::EarlyWithdraw(EarlyWithdrawArgs { amount: 28 }), accounts, &[&owner]).await,
+        InstructionError::InsufficientFunds,
+    );
+    assert_eq!(pool.lamports(settings).await, rent + 5);
+}
+
+//**************************************************************************************************
+//  test_failed_sale_releases_penalties, penalties taken before a sale failed are revenue once the
+//  contributions left are refunded
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_failed_sale_releases_penalties() {
+    let mut sale = Sale::start(1_000).await;
+
+    // 49 of the 100 paid for 27 of 55 tokens, less the 10% penalty
+    sale.pool.swap(100).await.unwrap();
+    sale.early_withdraw(27).await.unwrap();
+
+    let sale_end = sale.sale_end;
+    sale.pool.warp_past(sale_end).await;
+
+    let savings_lamports = sale.pool.lamports(sale.pool.savings).await;
+    sale.refund().await.unwrap();
+    assert_eq!(sale.pool.lamports(sale.pool.savings).await, savings_lamports + 51);
+
+    assert_eq!(sale.collect_revenue().await.unwrap(), 4);
+
+    let unlock = sale.unlock;
+    sale.pool.warp_past(unlock).await;
+    sale.close_pool().await.unwrap();
+    assert_eq!(sale.pool.lamports(sale.pool.settings).await, 0);
+}
+
+//**************************************************************************************************
+//  test_failed_sale_releases_penalty_pool, penalties kept for holders are revenue once the sale
+//  failed, the contributions left stay held back for their refunds
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_failed_sale_releases_penalty_pool() {
+    let mut sale = Sale::start_with(1_000, true).await;
+
+    sale.pool.swap(100).await.unwrap();
+    sale.early_withdraw(27).await.unwrap();
+    assert_eq!(sale.settings().await.penalty_pool, 4);
+
+    let sale_end = sale.sale_end;
+    sale.pool.warp_past(sale_end).await;
+
+    assert_eq!(sale.collect_revenue().await.unwrap(), 4);
+    assert_eq!(sale.settings().await.penalty_pool, 0);
+
+    let savings_lamports = sale.pool.lamports(sale.pool.savings).await;
+    sale.refund().await.unwrap();
+    assert_eq!(sale.pool.lamports(sale.pool.savings).await, savings_lamports + 51);
+
+    let unlock = sale.unlock;
+    sale.pool.warp_past(unlock).await;
+    assert_eq!(sale.collect_revenue().await.unwrap(), 0);
+    sale.close_pool().await.unwrap();
+    assert_eq!(sale.pool.lamports(sale.pool.settings).await, 0);
+}
+
+//**************************************************************************************************
+//  test_last_holder_releases_penalty_pool, with no holder left to share it the penalty pool is
+//  revenue
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_last_holder_releases_penalty_pool() {
+    let mut sale = Sale::start_with(0, true).await;
+
+    sale.pool.swap(100).await.unwrap();
+    sale.early_withdraw(55).await.unwrap();
+
+    let settings = sale.settings().await;
+    assert_eq!((settings.supply_locked, settings.penalty_pool), (0, 10));
+
+    assert_eq!(sale.collect_revenue().await.unwrap(), 10);
+    assert_eq!(sale.settings().await.penalty_pool, 0);
+
+    let unlock = sale.unlock;
+    sale.pool.warp_past(unlock).await;
+    sale.close_pool().await.unwrap();
+    assert_eq!(sale.pool.lamports(sale.pool.settings).await, 0);
+}
@@ -0,0 +1,414 @@
+mod common;
+
+use {
+    solana_program::{
+        instruction::AccountMeta,
+        program_error::ProgramError,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+        system_instruction,
+    },
+    solana_program_test::tokio,
+    solana_sdk::{
+        signature::{
+            Keypair,
+            Signer,
+        },
+        transaction::Transaction,
+        transport::TransportError,
+    },
+    kaizen::{
+        instruction::KaizenInstruction,
+        state::{
+            Savings,
+            Settings,
+            StakeAccount,
+            StakeArgs,
+            StakePool,
+            StakePoolArgs,
+        },
+    },
+    common::*,
+};
+
+const REWARD_RATE: u64 = 100;
+
+//**************************************************************************************************
+//  Staker, a wallet with its Savings and stake accounts, and a spare empty Savings account
+//--------------------------------------------------------------------------------------------------
+struct Staker {
+    owner:      Keypair,
+    savings:    Pubkey,
+    stake:      Pubkey,
+    spare:      Pubkey,
+}
+
+//**************************************************************************************************
+//  Staking, a pool with a stake pool paying `REWARD_RATE` per second, its vault holding only rent,
+//  so ClaimRewards settles what is pending without paying it, and two stakers holding 500 tokens
+//  each
+//--------------------------------------------------------------------------------------------------
+struct Staking {
+    pool:       Pool,
+    stake_pool: Pubkey,
+    stakers:    Vec<Staker>,
+    reward_end: u64,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Staking {
+
+    //==================================================================================================
+    //  start, rewards end `_reward_in` seconds from now
+    //--------------------------------------------------------------------------------------------------
+    async fn start(_reward_in: u64) -> Self {
+        let locked_token_owner  = Keypair::new();
+        let stake_pool          = Pubkey::new_unique();
+        let stakers: Vec<_>     = (0..2).map(|_| Staker {
+                owner:      Keypair::new(),
+                savings:    Pubkey::new_unique(),
+                stake:      Pubkey::new_unique(),
+                spare:      Pubkey::new_unique(),
+            })
+            .collect();
+
+        let mut accounts = vec![(stake_pool, program_account(StakePool::LEN, Rent::default().minimum_balance(StakePool::LEN)))];
+
+        for staker in stakers.iter() {
+            accounts.push((staker.owner.pubkey(),   wallet_account()));
+            accounts.push((staker.savings,          program_account(Savings::LEN, SAVINGS_LAMPORTS)));
+            accounts.push((staker.stake,            program_account(StakeAccount::LEN, Rent::default().minimum_balance(StakeAccount::LEN))));
+            accounts.push((staker.spare,            program_account(Savings::LEN, Rent::default().minimum_balance(Savings::LEN))));
+        }
+
+        let mut pool = Pool::start_with(Savings::LEN, &accounts).await;
+        let settings = pool.pool_settings(1_000_000, 36_000).await;
+        pool.initialize_account(pool.settings, Settings { locked_token_owner: locked_token_owner.pubkey(), interest_basis_points: 0, ..settings }).await.unwrap();
+
+        for staker in stakers.iter() {
+            pool.swap_from(staker.savings, &staker.owner, 1_000).await.unwrap();
+        }
+
+        let reward_end = pool.now().await + _reward_in;
+        pool.process_signed(KaizenInstruction::InitializeStakePool(StakePoolArgs { reward_rate: REWARD_RATE, reward_end }), vec![
+            AccountMeta::new_readonly(pool.settings, false),
+            AccountMeta::new(stake_pool, false),
+            AccountMeta::new_readonly(locked_token_owner.pubkey(), true),
+        ],
+        &[&locked_token_owner]).await.unwrap();
+
+        Self { pool, stake_pool, stakers, reward_end }
+    }
+    //==================================================================================================
+    //  stake, from the Savings of staker `_staker`
+    //--------------------------------------------------------------------------------------------------
+    async fn stake(&mut self, _staker: usize, _amount: u64) -> Result<(), TransportError> {
+        let staker = &self.stakers[_staker];
+
+        self.pool.process_signed(KaizenInstruction::Stake(StakeArgs { amount: _amount }), vec![
+            AccountMeta::new_readonly(self.pool.settings, false),
+            AccountMeta::new(self.stake_pool, false),
+            AccountMeta::new(staker.stake, false),
+            AccountMeta::new(staker.savings, false),
+            AccountMeta::new_readonly(staker.owner.pubkey(), true),
+        ],
+        &[&staker.owner]).await
+    }
+    //==================================================================================================
+    //  unstake, to the Savings account `_savings` of staker `_staker`
+    //--------------------------------------------------------------------------------------------------
+    async fn unstake(&mut self, _staker: usize, _savings: Pubkey, _amount: u64) -> Result<(), TransportError> {
+        let staker = &self.stakers[_staker];
+
+        self.pool.process_signed(KaizenInstruction::Unstake(StakeArgs { amount: _amount }), vec![
+            AccountMeta::new_readonly(self.pool.settings, false),
+            AccountMeta::new(self.stake_pool, false),
+            AccountMeta::new(staker.stake, false),
+            AccountMeta::new(_savings, false),
+            AccountMeta::new_readonly(staker.owner.pubkey(), true),
+        ],
+        &[&staker.owner]).await
+    }
+    //==================================================================================================
+    //  claim_rewards
+    //--------------------------------------------------------------------------------------------------
+    async fn claim_rewards(&mut self, _staker: usize) -> Result<(), TransportError> {
+        let staker = &self.stakers[_staker];
+
+        self.pool.process_signed(KaizenInstruction::ClaimRewards, vec![
+            AccountMeta::new_readonly(self.pool.settings, false),
+            AccountMeta::new(self.stake_pool, false),
+            AccountMeta::new(staker.stake, false),
+            AccountMeta::new(staker.owner.pubkey(), true),
+        ],
+        &[&staker.owner]).await
+    }
+    //==================================================================================================
+    //  rewards, pending for staker `_staker` once settled by ClaimRewards
+    //--------------------------------------------------------------------------------------------------
+    async fn rewards(&mut self, _staker: usize) -> u64 {
+        self.claim_rewards(_staker).await.unwrap();
+        self.stake_account(_staker).await.rewards_pending
+    }
+    //==================================================================================================
+    //  later, on a later bank, so the next instructions run at a later time
+    //--------------------------------------------------------------------------------------------------
+    async fn later(&mut self) {
+        let now = self.pool.now().await;
+        self.pool.warp_past(now).await;
+    }
+    //==================================================================================================
+    //  last_update, when the last instruction ran
+    //--------------------------------------------------------------------------------------------------
+    async fn last_update(&mut self) -> u64 {
+        self.stake_pool_state().await.last_update
+    }
+    //==================================================================================================
+    //  stake_pool_state
+    //--------------------------------------------------------------------------------------------------
+    async fn stake_pool_state(&mut self) -> StakePool {
+        self.pool.unpack(self.stake_pool).await
+    }
+    //==================================================================================================
+    //  stake_account, of staker `_staker`
+    //--------------------------------------------------------------------------------------------------
+    async fn stake_account(&mut self, _staker: usize) -> StakeAccount {
+        self.pool.unpack(self.stakers[_staker].stake).await
+    }
+    //==================================================================================================
+    //  savings, of staker `_staker`
+    //--------------------------------------------------------------------------------------------------
+    async fn savings(&mut self, _staker: usize) -> Savings {
+        self.pool.unpack(self.stakers[_staker].savings).await
+    }
+}
+
+//**************************************************************************************************
+//  test_rewards_by_stake_size, every second's rewards are shared pro rata to what is staked then
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_rewards_by_stake_size() {
+    let mut staking = Staking::start(1_000_000).await;
+
+    staking.stake(0, 100).await.unwrap();
+    let alice_staked = staking.last_update().await;
+
+    staking.later().await;
+    staking.stake(1, 300).await.unwrap();
+    let bob_staked = staking.last_update().await;
+
+    // alone until bob staked, then a quarter
+    staking.later().await;
+    let alice_rewards   = staking.rewards(0).await;
+    let now             = staking.last_update().await;
+
+    assert!(alice_staked < bob_staked && bob_staked < now);
+    assert_eq!(alice_rewards, REWARD_RATE * (bob_staked - alice_staked) + REWARD_RATE * (now - bob_staked) / 4);
+    assert_eq!(staking.rewards(1).await, REWARD_RATE * (now - bob_staked) * 3 / 4);
+
+    // staked tokens and what was paid for them leave the Savings
+    let (stake, savings) = (staking.stake_account(1).await, staking.savings(1).await);
+    assert_eq!((stake.amount, stake.paid), (300, 600));
+    assert_eq!((savings.total_technical, savings.total_paid), (200, 400));
+    assert_eq!(staking.stake_pool_state().await.total_staked, 400);
+}
+
+//**************************************************************************************************
+//  test_reward_end, nothing accrues past `reward_end`
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_reward_end() {
+    let mut staking = Staking::start(2_000).await;
+
+    staking.stake(0, 100).await.unwrap();
+    let staked      = staking.last_update().await;
+    let reward_end  = staking.reward_end;
+
+    staking.pool.warp_past(reward_end).await;
+    assert_eq!(staking.rewards(0).await, REWARD_RATE * (reward_end - staked));
+    assert_eq!(staking.last_update().await, reward_end);
+
+    // staking after the end earns nothing
+    staking.stake(1, 100).await.unwrap();
+    staking.later().await;
+    assert_eq!(staking.rewards(1).await, 0);
+    assert_eq!(staking.rewards(0).await, REWARD_RATE * (reward_end - staked));
+}
+
+//**************************************************************************************************
+//  test_unstake_then_restake, nothing accrues to an empty stake, restaking is not paid twice for
+//  the time before
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_unstake_then_restake() {
+    let mut staking = Staking::start(1_000_000).await;
+    let savings     = staking.stakers[0].savings;
+
+    staking.stake(0, 100).await.unwrap();
+    let staked = staking.last_update().await;
+
+    staking.later().await;
+    staking.unstake(0, savings, 100).await.unwrap();
+    let unstaked = staking.last_update().await;
+
+    let stake = staking.stake_account(0).await;
+    assert_eq!((stake.amount, stake.paid, stake.rewards_pending), (0, 0, REWARD_RATE * (unstaked - staked)));
+
+    let user_savings = staking.savings(0).await;
+    assert_eq!((user_savings.total_technical, user_savings.total_paid), (500, 1_000));
+
+    // nobody staked, these rewards are not paid to anyone
+    staking.later().await;
+    staking.stake(0, 50).await.unwrap();
+    let restaked = staking.last_update().await;
+
+    // part of it out, the rest keeps earning the whole rate
+    staking.later().await;
+    staking.unstake(0, savings, 25).await.unwrap();
+
+    staking.later().await;
+    let rewards = staking.rewards(0).await;
+    let now     = staking.last_update().await;
+
+    assert_eq!(rewards, REWARD_RATE * (unstaked - staked) + REWARD_RATE * (now - restaked));
+    assert_eq!(staking.stake_pool_state().await.total_staked, 25);
+    assert!(staking.unstake(0, savings, 26).await.is_err());
+}
+
+//**************************************************************************************************
+//  test_unstake_after_close_savings, a position staked whole and closed is unstaked to an empty
+//  Savings account
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_unstake_after_close_savings() {
+    let mut staking = Staking::start(1_000_000).await;
+
+    staking.stake(0, 500).await.unwrap();
+
+    let staker  = &staking.stakers[0];
+    let spare   = staker.spare;
+    staking.pool.process_signed(KaizenInstruction::CloseSavings, vec![
+        AccountMeta::new(staker.savings, false),
+        AccountMeta::new(staker.owner.pubkey(), true),
+    ],
+    &[&staker.owner]).await.unwrap();
+
+    // the other staker's Savings can't take it
+    let other_savings = staking.stakers[1].savings;
+    assert!(staking.unstake(0, other_savings, 500).await.is_err());
+
+    staking.unstake(0, spare, 500).await.unwrap();
+
+    let user_savings = staking.pool.unpack::<Savings>(spare).await;
+    assert_eq!((user_savings.total_technical, user_savings.total_paid), (500, 1_000));
+    assert_eq!((user_savings.owner, user_savings.settings), (staking.stakers[0].owner.pubkey(), staking.pool.settings));
+    assert_eq!(staking.stake_account(0).await.amount, 0);
+}
+
+//**************************************************************************************************
+//  test_underfunded_vault, ClaimRewards pays what the stake pool holds above rent and leaves the
+//  rest pending
+//--------------------------------------------------------------------------------------------------
+#[tokio::test]
+async fn test_underfunded_vault() {
+    let locked_token_owner  = Keypair::new();
+    let stake_pool          = Pubkey::new_unique();
+    let stake_account       = Pubkey::new_unique();
+    let stake_pool_rent     = Rent::default().minimum_balance(StakePool::LEN);
+    let mut pool            = Pool::start_with(Savings::LEN, &[
+        (stake_pool,    program_account(StakePool::LEN, stake_pool_rent + 50)),
+        (stake_account, program_account(StakeAccount::LEN, Rent::default().minimum_balance(StakeAccount::LEN))),
+    ]).await;
+
+    let settings = pool.pool_settings(1_000_000, 36_000).await;
+    pool.initialize_account(pool.settings, Settings { locked_token_owner: locked_token_owner.pubkey(), ..settings }).await.unwrap();
+    pool.swap(100).await.unwrap();
+
+    let reward_end = pool.now().await + 30_000;
+    pool.process_signed(KaizenInstruction::InitializeStakePool(StakePoolArgs { reward_rate: 10, reward_end }), vec![
+        AccountMeta::new_readonly(pool.settings, false),
+        AccountMeta::new(stake_pool, false),
+        AccountMeta::new_readonly(locked_token_owner.pubkey(), true),
+    ],
+    &[&locked_token_owner]).await.unwrap();
+
+    let owner           = pool.context.payer.pubkey();
+    let stake_accounts  = vec![
+        AccountMeta::new_readonly(pool.settings, false),
+        AccountMeta::new(stake_pool, false),
+        AccountMeta::new(stake_account, false),
+        AccountMeta::new(pool.savings, false),
+        AccountMeta::new_readonly(owner, true),
+    ];
+    let claim_accounts  = vec![
+        AccountMeta::new_readonly(pool.settings, false),
+        AccountMeta::new(stake_pool, false),
+        AccountMeta::new(stake_account, false),
+        AccountMeta::new(owner, true),
+    ];
+
+    pool.process(KaizenInstruction::Stake(StakeArgs { amount: 55 }), stake_accounts).await.unwrap();
+
+    let now = pool.now().await;
+    pool.warp_past(now + 10).await;
+    pool.process(KaizenInstruction::ClaimRewards, claim_accounts.clone()).await.unwrap();
+
+    // 10 a second for more than 10 seconds, only 50 in the vault
+    let pending = pool.unpack::<StakeAccount>(stake_account).await.rewards_pending;
+    assert!(100 < pending);
+    assert_eq!(pool.lamports(stake_pool).await, stake_pool_rent);
+
+    // funded, the next claim pays everything pending
+    let last_blockhash  = pool.context.banks_client.get_recent_blockhash().await.unwrap();
+    let transaction     = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&owner, &stake_pool, 1_000_000_000)],
+        Some(&owner),
+        &[&pool.context.payer],
+        last_blockhash,
+    );
+    pool.context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let now = pool.now().await;
+    pool.warp_past(now).await;
+    pool.process(KaizenInstruction::ClaimRewards, claim_accounts).await.unwrap();
+
+    let stake: StakeAccount = pool.unpack(stake_account).await;
+    let stake_pool_state: StakePool = pool.unpack(stake_pool).await;
+    let paid = stake_pool_rent + 1_000_000_000 - pool.lamports(stake_pool).await;
+
+    assert_eq!(stake.rewards_pending, 0);
+    assert!(pending <= paid);
+    assert!(50 + paid <= 10 * (stake_pool_state.last_update - (reward_end - 30_000)));
+}
+
+//**************************************************************************************************
+//  test_reward_overflow, a large reward rate over a long time accrues exactly while it fits and is
+//  rejected once it does not
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_reward_overflow() {
+    let ten_years           = 10 * 365 * 24 * 3_600;
+    let mut stake_pool      = StakePool { reward_rate: 1_000_000_000, reward_end: u64::MAX, total_staked: 1, ..StakePool::default() };
+    let mut stake_account   = StakeAccount { amount: 1, ..StakeAccount::default() };
+
+    stake_pool.update(ten_years).unwrap();
+    stake_account.settle(&stake_pool).unwrap();
+    assert_eq!(stake_account.rewards_pending, 1_000_000_000 * ten_years);
+
+    // more than `reward_per_share` holds
+    let mut stake_pool = StakePool { reward_rate: u64::MAX, reward_end: u64::MAX, total_staked: 1, ..StakePool::default() };
+    assert_eq!(stake_pool.update(u64::MAX), Err(ProgramError::InvalidInstructionData));
+    assert_eq!(stake_pool.last_update, 0);
+
+    // more than `rewards_pending` holds
+    let stake_pool          = StakePool { reward_per_share: u64::MAX as u128 * StakePool::PRECISION, ..StakePool::default() };
+    let mut stake_account   = StakeAccount { amount: 2, ..StakeAccount::default() };
+    assert_eq!(stake_account.settle(&stake_pool), Err(ProgramError::InvalidInstructionData));
+    assert_eq!(stake_account.rewards_pending, 0);
+
+    // more than the stake times `reward_per_share` holds
+    let stake_pool = StakePool { reward_per_share: u128::MAX / 2, ..StakePool::default() };
+    assert_eq!(stake_pool.reward_debt_of(u64::MAX), Err(ProgramError::InvalidInstructionData));
+}
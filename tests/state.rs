@@ -0,0 +1,133 @@
+use {
+    borsh::{
+        BorshDeserialize,
+        BorshSerialize,
+    },
+    solana_program::{
+        program_error::ProgramError,
+        program_pack::{
+            IsInitialized,
+            Pack,
+        },
+        pubkey::Pubkey,
+    },
+    kaizen::state::{
+        AccountType,
+        Bid,
+        Order,
+        Referrer,
+        Savings,
+        Settings,
+        StakeAccount,
+        StakePool,
+        Tier,
+        Token,
+    },
+};
+
+//**************************************************************************************************
+//  settings, every field away from its default
+//--------------------------------------------------------------------------------------------------
+fn settings() -> Settings {
+    Settings {
+        is_initialized:         true,
+        revenue_owner:          Pubkey::new_unique(),
+        interest_basis_points:  10,
+        locked_token:           Pubkey::new_unique(),
+        locked_token_owner:     Pubkey::new_unique(),
+        unlock_timestamp:       1629999999,
+        supply_total:           1_000_000,
+        supply_locked:          1_000,
+        token0:                 Token { address: Pubkey::new_unique(), price: 2 },
+        soft_cap:               500,
+        sale_end:               1620000000,
+        total_raised:           100,
+        penalty_basis_points:   1_500,
+        penalty_to_holders:     true,
+        penalty_pool:           7,
+        interest_apr:           true,
+        tiers:                  [Tier { supply_end: 200_000, price: 1 }, Tier { supply_end: 500_000, price: 2 }, Tier::default(), Tier::default()],
+        auction_start_price:    3,
+        auction_floor_price:    1,
+        auction_start:          1610000000,
+        auction_end:            1615000000,
+        batch_end:              1618000000,
+        batch_bids:             4,
+        batch_demand:           5,
+        batch_filled:           6,
+        batch_settled:          true,
+        referral_basis_points:  250,
+        referral_bonus:         true,
+        paused:                 true,
+    }
+}
+
+//**************************************************************************************************
+//  test_settings_round_trip
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_settings_round_trip() {
+    let settings    = settings();
+    let borsh       = settings.try_to_vec().unwrap();
+    let mut packed  = vec![0; Settings::LEN];
+    Settings::pack(settings, &mut packed).unwrap();
+
+    assert_eq!(borsh.len(), Settings::BODY_LEN);
+    assert_eq!(&packed[Settings::LEN - Settings::BODY_LEN..], &borsh[..]);
+    assert_eq!(Settings::try_from_slice(&borsh).unwrap(), settings);
+    assert_eq!(Settings::unpack(&packed).unwrap(), settings);
+}
+
+//**************************************************************************************************
+//  test_settings_rejects_bad_input
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_settings_rejects_bad_input() {
+    let mut borsh = settings().try_to_vec().unwrap();
+
+    assert!(Settings::try_from_slice(&borsh[..Settings::BODY_LEN - 1]).is_err());
+    assert!(Settings::try_from_slice(&[]).is_err());
+
+    borsh[0] = 2;
+    assert!(Settings::try_from_slice(&borsh).is_err());
+}
+//==================================================================================================
+//  assert_tags, `T` only unpacks from data tagged as `_account_type` or zeroed, the is_initialized
+//  flag is at `_is_initialized_offset`
+//--------------------------------------------------------------------------------------------------
+fn assert_tags<T: Pack + IsInitialized>(_account_type: AccountType, _is_initialized_offset: usize) {
+    let tagged = |tag: u8, is_initialized: u8| {
+        let mut data                    = vec![0; T::LEN];
+        data[0]                         = tag;
+        data[_is_initialized_offset]    = is_initialized;
+        data
+    };
+
+    for tag in 1..=7 {
+        if tag != _account_type as u8 {
+            assert_eq!(T::unpack_unchecked(&tagged(tag, 0)).err(), Some(ProgramError::InvalidAccountData), "tag {}", tag);
+            assert_eq!(T::unpack_unchecked(&tagged(tag, 1)).err(), Some(ProgramError::InvalidAccountData), "tag {}", tag);
+        }
+    }
+
+    // zeroed, a new account
+    assert!(T::unpack_unchecked(&tagged(0, 0)).is_ok());
+    assert_eq!(T::unpack(&tagged(0, 0)).err(), Some(ProgramError::UninitializedAccount));
+
+    // initialized but never tagged
+    assert_eq!(T::unpack_unchecked(&tagged(0, 1)).err(), Some(ProgramError::InvalidAccountData));
+}
+
+//**************************************************************************************************
+//  test_account_type_tags, no state type unpacks from another type's account
+//--------------------------------------------------------------------------------------------------
+#[test]
+fn test_account_type_tags() {
+    assert_tags::<Settings>(AccountType::Settings, 2);
+    assert_tags::<Savings>(AccountType::Savings, 2);
+    assert_tags::<Order>(AccountType::Order, 1);
+    assert_tags::<Bid>(AccountType::Bid, 1);
+    assert_tags::<Referrer>(AccountType::Referrer, 1);
+    assert_tags::<StakePool>(AccountType::StakePool, 1);
+    assert_tags::<StakeAccount>(AccountType::StakeAccount, 1);
+}
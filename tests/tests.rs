@@ -21,6 +21,7 @@ use {
         state::{
             Settings,
             Token,
+            Tier,
             SwapArgs,
             WithdrawArgs,
         },
@@ -59,7 +60,7 @@ async fn test_initialize() {
         Account {
             owner: id(),
             lamports:   Rent::default().minimum_balance(1000),
-            data:       vec![0; 165],
+            data:       vec![0; 340],
             ..Account::default()
         },
     );
@@ -68,7 +69,7 @@ async fn test_initialize() {
         Account {
             owner: id(),
             lamports:   Rent::default().minimum_balance(1000),
-            data:       vec![0; 17],
+            data:       vec![0; 107],
             ..Account::default()
         },
     );
@@ -87,6 +88,26 @@ async fn test_initialize() {
         supply_total:           1_000_000,
         supply_locked:          0,
         token0:                 Token { address: Pubkey::from_str("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB").unwrap(), price: 2 },
+        soft_cap:               0,
+        sale_end:               0,
+        total_raised:           0,
+        penalty_basis_points:   0,
+        penalty_to_holders:     false,
+        penalty_pool:           0,
+        interest_apr:           false,
+        tiers:                  [Tier::default(); 4],
+        auction_start_price:    0,
+        auction_floor_price:    0,
+        auction_start:          0,
+        auction_end:            0,
+        batch_end:              0,
+        batch_bids:             0,
+        batch_demand:           0,
+        batch_filled:           0,
+        batch_settled:          false,
+        referral_basis_points:  0,
+        referral_bonus:         false,
+        paused:                 false,
     };
 
     let     instraction_t           = KaizenInstruction::Initialize(settings);
@@ -115,6 +136,7 @@ async fn test_initialize() {
             accounts:   vec![
                 AccountMeta::new(account_settings_pubkey, false),
                 AccountMeta::new(account_from_pubkey, false),
+                AccountMeta::new_readonly(payer.pubkey(), true),
             ],
             data:       instraction_swap,
         }],